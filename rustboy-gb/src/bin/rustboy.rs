@@ -0,0 +1,283 @@
+//! Headless runner
+//!
+//! Runs a rom without a display, echoing whatever the game writes to the serial port on stdout.
+//! Test roms (Blargg's) report their result over serial, which is turned into the exit code:
+//!
+//! * `0` - the rom printed "Passed"
+//! * `1` - the rom printed "Failed"
+//! * `2` - the frame or cycle limit was reached before the rom reported a result
+//! * `3` - the rom, boot rom or camera image could not be loaded, or the link cable or infrared
+//!   port could not be connected
+//!
+//! `--info` prints the cartridge header instead of running the rom.
+//!
+//! Two instances can be linked with `--link-listen` on one and `--link-connect` on the other, over
+//! TCP (`127.0.0.1:5000`) or a Unix domain socket (`unix:/tmp/rustboy.sock`). `--printer` plugs in
+//! a Game Boy Printer instead, saving what gets printed as PNGs to `--print-dir`.
+//!
+//! The infrared port of Hudson carts is pointed at another instance the same way, with
+//! `--infrared-listen` and `--infrared-connect`. The listening end doesn't wait for the other one.
+//!
+//! `--camera` points the Game Boy Camera at a PNG, or at a directory of them taking one per photo.
+//!
+//! `--model` runs the game on other hardware than its header asks for, e.g. `sgb` for Super Game
+//! Boy palettes and borders.
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use log::error;
+
+use rustboy::constants::{DEFAULT_BOOT_ROM, DEFAULT_PRINT_DIR};
+use rustboy::gameboy::GameBoy;
+use rustboy::interconnect::cartridge::camera::open_image_source;
+use rustboy::interconnect::cartridge::header::CartridgeHeader;
+use rustboy::interconnect::infrared::InfraredSocket;
+use rustboy::interconnect::ppu::Renderer;
+use rustboy::interconnect::serial::{Printer, SocketLink};
+use rustboy::interconnect::Model;
+
+const EXIT_PASSED: u8 = 0;
+const EXIT_FAILED: u8 = 1;
+const EXIT_TIMEOUT: u8 = 2;
+const EXIT_LOAD_ERROR: u8 = 3;
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Hardware {
+    Dmg,
+    Sgb,
+    Cgb,
+}
+
+impl From<Hardware> for Model {
+    fn from(hardware: Hardware) -> Model {
+        match hardware {
+            Hardware::Dmg => Model::Dmg,
+            Hardware::Sgb => Model::Sgb,
+            Hardware::Cgb => Model::Cgb,
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "rustboy", about = "Run a Gameboy rom without a display")]
+struct Args {
+    /// Path to the game rom
+    #[arg(long)]
+    rom: String,
+
+    /// Path to the boot rom
+    #[arg(long, default_value = DEFAULT_BOOT_ROM)]
+    boot_rom: String,
+
+    /// Skip the boot rom and start executing the game at 0x100
+    #[arg(long)]
+    skip_boot: bool,
+
+    /// Run without a display. This runner never opens a window, the flag is accepted so scripts
+    /// can pass the same arguments they give the GUI
+    #[arg(long)]
+    headless: bool,
+
+    /// Stop after this many frames
+    #[arg(long)]
+    frames: Option<u64>,
+
+    /// Stop after this many T-cycles
+    #[arg(long)]
+    cycles: Option<u64>,
+
+    /// Don't echo serial port output to stdout
+    #[arg(long)]
+    no_serial: bool,
+
+    /// Keep running after the rom reported a result over serial
+    #[arg(long)]
+    no_exit_on_result: bool,
+
+    /// Print the cartridge header and exit
+    #[arg(long)]
+    info: bool,
+
+    /// Draw with the pixel FIFO renderer, slower but accurate to the dot
+    #[arg(long)]
+    fifo: bool,
+
+    /// Wait for another instance to plug in a link cable, on `host:port` or `unix:<path>`
+    #[arg(long, conflicts_with = "link_connect")]
+    link_listen: Option<String>,
+
+    /// Plug a link cable into another instance listening on `host:port` or `unix:<path>`
+    #[arg(long)]
+    link_connect: Option<String>,
+
+    /// Let another instance point its infrared port at this one, on `host:port` or `unix:<path>`
+    #[arg(long, conflicts_with = "infrared_connect")]
+    infrared_listen: Option<String>,
+
+    /// Point the infrared port at another instance listening on `host:port` or `unix:<path>`
+    #[arg(long)]
+    infrared_connect: Option<String>,
+
+    /// Plug a Game Boy Printer into the link port
+    #[arg(long, conflicts_with_all = ["link_listen", "link_connect"])]
+    printer: bool,
+
+    /// Directory printed pages are saved to
+    #[arg(long, default_value = DEFAULT_PRINT_DIR)]
+    print_dir: String,
+
+    /// Hardware to run the game on instead of the one its header asks for
+    #[arg(long, value_enum)]
+    model: Option<Hardware>,
+
+    /// What the Game Boy Camera sees: a PNG, or a directory of PNGs used in turn
+    #[arg(long)]
+    camera: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    env_logger::init();
+    let args = Args::parse();
+
+    if args.info {
+        return print_info(&args.rom);
+    }
+
+    let mut gameboy = GameBoy::new();
+    gameboy.model_override = args.model.map(Model::from);
+
+    let boot_rom = if args.skip_boot {
+        None
+    } else {
+        Some(args.boot_rom.as_str())
+    };
+
+    if let Err(e) = gameboy.boot_with(&args.rom, boot_rom) {
+        error!("COULD NOT LOAD ROM: {}", e);
+        return ExitCode::from(EXIT_LOAD_ERROR);
+    }
+
+    gameboy.interconnect.serial.set_echo(false);
+    if args.fifo {
+        gameboy.interconnect.ppu.renderer = Renderer::Fifo;
+    }
+
+    let link = match (&args.link_listen, &args.link_connect) {
+        (Some(addr), _) => Some(SocketLink::listen(addr)),
+        (_, Some(addr)) => Some(SocketLink::connect(addr)),
+        _ => None,
+    };
+
+    match link {
+        Some(Ok(link)) => gameboy.interconnect.serial.connect(Box::new(link)),
+        Some(Err(e)) => {
+            error!("COULD NOT CONNECT LINK CABLE: {}", e);
+            return ExitCode::from(EXIT_LOAD_ERROR);
+        }
+        None => (),
+    }
+
+    let infrared = match (&args.infrared_listen, &args.infrared_connect) {
+        (Some(addr), _) => Some(InfraredSocket::listen(addr)),
+        (_, Some(addr)) => Some(InfraredSocket::connect(addr)),
+        _ => None,
+    };
+
+    match infrared {
+        Some(Ok(port)) => gameboy
+            .interconnect
+            .cartridge
+            .mbc
+            .set_infrared(Box::new(port)),
+        Some(Err(e)) => {
+            error!("COULD NOT CONNECT INFRARED PORT: {}", e);
+            return ExitCode::from(EXIT_LOAD_ERROR);
+        }
+        None => (),
+    }
+
+    if args.printer {
+        let printer = Printer::new(&args.print_dir);
+        gameboy.interconnect.serial.connect(Box::new(printer));
+    }
+
+    if let Some(path) = &args.camera {
+        match open_image_source(path) {
+            Ok(source) => gameboy.interconnect.cartridge.mbc.set_image_source(source),
+            Err(e) => {
+                error!("COULD NOT LOAD CAMERA IMAGE: {}", e);
+                return ExitCode::from(EXIT_LOAD_ERROR);
+            }
+        }
+    }
+
+    let mut stdout = std::io::stdout();
+    let mut transcript = String::new();
+    let mut result: Option<u8> = None;
+    let mut frames: u64 = 0;
+
+    loop {
+        gameboy.cpu.run(&mut gameboy.interconnect);
+        frames += 1;
+
+        // There's no audio output here
+        gameboy.interconnect.apu.drain_samples();
+
+        let bytes = gameboy.interconnect.serial.take_bytes();
+        if !bytes.is_empty() {
+            let text = String::from_utf8_lossy(&bytes);
+            if !args.no_serial {
+                print!("{}", text);
+                let _ = stdout.flush();
+            }
+            transcript.push_str(&text);
+
+            if transcript.contains("Passed") {
+                result = Some(EXIT_PASSED);
+            } else if transcript.contains("Failed") {
+                result = Some(EXIT_FAILED);
+            }
+        }
+
+        if result.is_some() && !args.no_exit_on_result {
+            break;
+        }
+
+        let frame_limit = args.frames.is_some_and(|limit| frames >= limit);
+        let cycle_limit = args
+            .cycles
+            .is_some_and(|limit| gameboy.interconnect.cycles >= limit);
+        if frame_limit || cycle_limit {
+            break;
+        }
+    }
+
+    if !args.no_serial {
+        println!();
+    }
+
+    if let Err(e) = gameboy.flush_battery_save() {
+        error!("COULD NOT WRITE SAVE: {}", e);
+    }
+
+    ExitCode::from(result.unwrap_or(EXIT_TIMEOUT))
+}
+
+fn print_info(rom: &str) -> ExitCode {
+    let header = std::fs::read(rom)
+        .map_err(anyhow::Error::from)
+        .and_then(|rom| Ok(CartridgeHeader::parse(&rom)?));
+
+    match header {
+        Ok(header) => {
+            println!("{}", header);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            error!("COULD NOT LOAD ROM: {}", e);
+            ExitCode::from(EXIT_LOAD_ERROR)
+        }
+    }
+}
@@ -5,7 +5,16 @@ use crate::cpu::interrupts::InterruptType;
 pub const CLOCK_SPEED: usize = 4_194_304;
 pub const MAX_CYCLES_PER_FRAME: usize = (CLOCK_SPEED as f32 / 59.7275) as usize;
 pub const PC_AFTER_BOOT: u16 = 0x100;
+
+// Internal DIV counter when the boot rom hands over to the game
+pub const DIV_COUNTER_AFTER_BOOT: u16 = 0xABCC;
+pub const DIV_COUNTER_AFTER_BOOT_CGB: u16 = 0x1EA0;
 pub const TARGET_FRAME_TIME: u32 = 1000 / 60;
+pub const DEFAULT_BOOT_ROM: &str = "roms/bootix_dmg.bin";
+pub const DEFAULT_PRINT_DIR: &str = "prints";
+
+// APU constants
+pub const SAMPLE_RATE: u32 = 44_100;
 
 
 // PPU constants
@@ -15,6 +24,11 @@ pub const Y_RESOLUTION: u8 = 144;
 pub const X_RESOLUTION: u8 = 160;
 pub const BUFFER_SIZE: usize = (144 * 160) as usize;
 
+// The SGB puts the game screen in the middle of a 256x224 border
+pub const SGB_X_RESOLUTION: u16 = 256;
+pub const SGB_Y_RESOLUTION: u16 = 224;
+pub const SGB_BUFFER_SIZE: usize = 256 * 224;
+
 pub const TILE_COLORS: [Rgb; 4] = [
     Rgb::new(255, 255, 255),
     Rgb::new(169, 169, 169),
@@ -22,7 +36,30 @@ pub const TILE_COLORS: [Rgb; 4] = [
     Rgb::new(0, 0, 0),
 ];
 
+/// Colors the CGB gives DMG games it has no palette for (BG, OBJ0, OBJ1)
+pub const CGB_COMPATIBILITY_SHADES: [[Rgb; 4]; 3] = [
+    [
+        Rgb::new(255, 255, 255),
+        Rgb::new(123, 255, 49),
+        Rgb::new(0, 99, 197),
+        Rgb::new(0, 0, 0),
+    ],
+    [
+        Rgb::new(255, 255, 255),
+        Rgb::new(255, 132, 132),
+        Rgb::new(148, 58, 58),
+        Rgb::new(0, 0, 0),
+    ],
+    [
+        Rgb::new(255, 255, 255),
+        Rgb::new(255, 132, 132),
+        Rgb::new(148, 58, 58),
+        Rgb::new(0, 0, 0),
+    ],
+];
+
 // MMU Addresses
+pub const JOYPAD: u16 = 0xFF00;
 pub const SERIAL_TRASFER_DATA: u16 = 0xFF01;
 pub const SERIAL_TRANSFER_CONTROL: u16 = 0xFF02;
 
@@ -32,6 +69,27 @@ pub const TMA: u16 = 0xFF06;
 pub const TAC: u16 = 0xFF07;
 pub const LCDC: u16 = 0xFF40;
 
+// CGB only registers
+pub const KEY1: u16 = 0xFF4D;
+pub const VBK: u16 = 0xFF4F;
+pub const HDMA1: u16 = 0xFF51;
+pub const HDMA2: u16 = 0xFF52;
+pub const HDMA3: u16 = 0xFF53;
+pub const HDMA4: u16 = 0xFF54;
+pub const HDMA5: u16 = 0xFF55;
+pub const BCPS: u16 = 0xFF68;
+pub const BCPD: u16 = 0xFF69;
+pub const OCPS: u16 = 0xFF6A;
+pub const OCPD: u16 = 0xFF6B;
+pub const SVBK: u16 = 0xFF70;
+pub const RP: u16 = 0xFF56;
+pub const OPRI: u16 = 0xFF6C;
+pub const PCM12: u16 = 0xFF76;
+pub const PCM34: u16 = 0xFF77;
+
+// Writing any nonzero value unmaps the boot rom until the next reset
+pub const BOOT_ROM_DISABLE: u16 = 0xFF50;
+
 pub const INTERRUPT_FLAG: u16 = 0xFF0F;
 pub const INTERRUPT_ENABLE: u16 = 0xFFFF;
 
@@ -49,11 +107,17 @@ pub const ROM_BANK: Range<u16> = 0x0000..0x8000;
 pub const VRAM: Range<u16> = 0x8000..0xA000;
 pub const EXTERNAL_RAM: Range<u16> = 0xA000..0xC000;
 pub const WORK_RAM: Range<u16> = 0xC000..0xE000;
+pub const ECHO_RAM: Range<u16> = 0xE000..0xFE00;
 pub const OAM: Range<u16> = 0xFE00..0xFEA0;
+pub const UNUSABLE: Range<u16> = 0xFEA0..0xFF00;
+pub const SERIAL: Range<u16> = 0xFF01..0xFF03;
 pub const TIMER: Range<u16> = 0xFF04..0xFF08;
+pub const AUDIO: Range<u16> = 0xFF10..0xFF40;
 pub const LCD: Range<u16> = 0xFF40..0xFF4C;
 pub const IO: Range<u16> = 0xFF00..0xFF80;
 pub const HIGH_RAM: Range<u16> = 0xFF80..0xFFFF;
 
 pub const ROM_BANK_SIZE: usize = 0x4000;
 pub const RAM_BANK_SIZE: usize = 0x2000;
+pub const VRAM_BANK_SIZE: usize = 0x2000;
+pub const WORK_RAM_BANK_SIZE: usize = 0x1000;
@@ -46,15 +46,11 @@ pub fn dec_8bit(flags: &mut Flags, register: &mut u8) {
 ///         Z 0 H -      
 pub fn inc_mem(cpu: &mut Cpu, interconnect: &mut Interconnect) {
     let hl = cpu.registers.hl();
-    let mut value = interconnect.read_mem(hl);
-
-    interconnect.emu_tick(1);
+    let mut value = cpu.read_cycle(interconnect, hl);
 
     cpu.registers.f.update_half_carry_flag_sum_8bit(value, 1);
     value = value.wrapping_add(1);
-    interconnect.write_mem(cpu.registers.hl(), value);
-
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, hl, value);
 
     cpu.registers.f.update_zero_flag(value);
     cpu.registers.f.clear_sub_flag();
@@ -65,15 +61,12 @@ pub fn inc_mem(cpu: &mut Cpu, interconnect: &mut Interconnect) {
 /// Flags:  Z N H C
 ///         Z 1 H -
 pub fn dec_mem(cpu: &mut Cpu, interconnect: &mut Interconnect) {
-    let mut value: u8 = interconnect.read_mem(cpu.registers.hl());
-
-    interconnect.emu_tick(1);
+    let hl = cpu.registers.hl();
+    let mut value: u8 = cpu.read_cycle(interconnect, hl);
 
     cpu.registers.f.update_half_carry_flag_sub_8bit(value, 1);
     value = value.wrapping_sub(1);
-    interconnect.write_mem(cpu.registers.hl(), value);
-
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, hl, value);
 
     cpu.registers.f.update_zero_flag(value);
     cpu.registers.f.set_sub_flag();
@@ -417,10 +410,9 @@ pub fn rlc(f: &mut Flags, r: &mut u8) {
 ///
 /// Flags:  Z N H C
 ///         Z 0 0 C
-pub fn rlc_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
-    let mut value = interconnect.read_mem(addr);
-
-    interconnect.emu_tick(1);
+pub fn rlc_hl(cpu: &mut Cpu, interconnect: &mut Interconnect, addr: u16) {
+    let mut value = cpu.read_cycle(interconnect, addr);
+    let f = &mut cpu.registers.f;
 
     let old_msb: u8 = (value & 0x80) >> 7;
     value <<= 1;
@@ -438,8 +430,7 @@ pub fn rlc_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
     f.clear_sub_flag();
     f.clear_half_carry_flag();
 
-    interconnect.write_mem(addr, value);
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, addr, value);
 }
 
 /// Rotates register to the right (Circular)
@@ -478,9 +469,9 @@ pub fn rrc(f: &mut Flags, r: &mut u8) {
 ///
 /// Flags:  Z N H C
 ///         Z 0 0 C
-pub fn rrc_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
-    let mut value: u8 = interconnect.read_mem(addr);
-    interconnect.emu_tick(1);
+pub fn rrc_hl(cpu: &mut Cpu, interconnect: &mut Interconnect, addr: u16) {
+    let mut value: u8 = cpu.read_cycle(interconnect, addr);
+    let f = &mut cpu.registers.f;
 
     let old_lsb: u8 = value & 0x01;
     value >>= 1;
@@ -498,8 +489,7 @@ pub fn rrc_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
     f.clear_sub_flag();
     f.clear_half_carry_flag();
 
-    interconnect.write_mem(addr, value);
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, addr, value);
 }
 
 /// Rotates register to the left
@@ -539,9 +529,9 @@ pub fn rl(f: &mut Flags, r: &mut u8) {
 ///
 /// Flags:  Z N H C
 ///         Z 0 0 C
-pub fn rl_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
-    let mut value: u8 = interconnect.read_mem(addr);
-    interconnect.emu_tick(1);
+pub fn rl_hl(cpu: &mut Cpu, interconnect: &mut Interconnect, addr: u16) {
+    let mut value: u8 = cpu.read_cycle(interconnect, addr);
+    let f = &mut cpu.registers.f;
 
     let old_msb: u8 = (value & 0x80) >> 7;
     value <<= 1;
@@ -559,8 +549,7 @@ pub fn rl_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
     f.clear_sub_flag();
     f.clear_half_carry_flag();
 
-    interconnect.write_mem(addr, value);
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, addr, value);
 }
 
 /// Rotates register to the right
@@ -601,9 +590,9 @@ pub fn rr(f: &mut Flags, r: &mut u8) {
 ///
 /// Flags:  Z N H C
 ///         Z 0 0 C
-pub fn rr_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
-    let mut value: u8 = interconnect.read_mem(addr);
-    interconnect.emu_tick(1);
+pub fn rr_hl(cpu: &mut Cpu, interconnect: &mut Interconnect, addr: u16) {
+    let mut value: u8 = cpu.read_cycle(interconnect, addr);
+    let f = &mut cpu.registers.f;
 
     let old_lsb: u8 = value & 0x01;
     value >>= 1;
@@ -621,8 +610,7 @@ pub fn rr_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
     f.clear_sub_flag();
     f.clear_half_carry_flag();
 
-    interconnect.write_mem(addr, value);
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, addr, value);
 }
 
 /// Shifts register to the left arithmetically
@@ -658,9 +646,9 @@ pub fn sla(f: &mut Flags, r: &mut u8) {
 ///
 /// Flags:  Z N H C
 ///         Z 0 0 C
-pub fn sla_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
-    let mut value: u8 = interconnect.read_mem(addr);
-    interconnect.emu_tick(1);
+pub fn sla_hl(cpu: &mut Cpu, interconnect: &mut Interconnect, addr: u16) {
+    let mut value: u8 = cpu.read_cycle(interconnect, addr);
+    let f = &mut cpu.registers.f;
 
     let old_msb: u8 = (value & 0x80) >> 7;
     value <<= 1;
@@ -675,8 +663,7 @@ pub fn sla_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
     f.clear_sub_flag();
     f.clear_half_carry_flag();
 
-    interconnect.write_mem(addr, value);
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, addr, value);
 }
 
 /// Shifts register to the right arithmetically
@@ -710,9 +697,9 @@ pub fn sra(f: &mut Flags, r: &mut u8) {
 ///
 /// Flags:  Z N H C
 ///         Z 0 0 C
-pub fn sra_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
-    let mut value: u8 = interconnect.read_mem(addr);
-    interconnect.emu_tick(1);
+pub fn sra_hl(cpu: &mut Cpu, interconnect: &mut Interconnect, addr: u16) {
+    let mut value: u8 = cpu.read_cycle(interconnect, addr);
+    let f = &mut cpu.registers.f;
 
     let old_msb: u8 = value & 0x80;
     let old_lsb: u8 = value & 0x01;
@@ -731,8 +718,7 @@ pub fn sra_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
     f.clear_sub_flag();
     f.clear_half_carry_flag();
 
-    interconnect.write_mem(addr, value);
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, addr, value);
 }
 
 /// Swaps r
@@ -762,9 +748,9 @@ pub fn swap(f: &mut Flags, r: &mut u8) {
 ///
 /// Flags:  Z N H C
 ///         Z 0 0 0
-pub fn swap_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
-    let mut value = interconnect.read_mem(addr);
-    interconnect.emu_tick(1);
+pub fn swap_hl(cpu: &mut Cpu, interconnect: &mut Interconnect, addr: u16) {
+    let mut value = cpu.read_cycle(interconnect, addr);
+    let f = &mut cpu.registers.f;
 
     let lower_nibble: u8 = value & 0x0F;
     let upper_nibble: u8 = value & 0xF0;
@@ -777,8 +763,7 @@ pub fn swap_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
     f.clear_sub_flag();
     f.clear_half_carry_flag();
 
-    interconnect.write_mem(addr, value);
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, addr, value);
 }
 
 /// Shifts register to the right logically
@@ -818,9 +803,9 @@ pub fn srl(f: &mut Flags, r: &mut u8) {
 ///
 /// Flags:  Z N H C
 ///         Z 0 0 C
-pub fn srl_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
-    let mut value: u8 = interconnect.read_mem(addr);
-    interconnect.emu_tick(1);
+pub fn srl_hl(cpu: &mut Cpu, interconnect: &mut Interconnect, addr: u16) {
+    let mut value: u8 = cpu.read_cycle(interconnect, addr);
+    let f = &mut cpu.registers.f;
 
     let old_lsb: u8 = value & 0x01;
     value >>= 1;
@@ -838,8 +823,7 @@ pub fn srl_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16) {
     f.clear_sub_flag();
     f.clear_half_carry_flag();
 
-    interconnect.write_mem(addr, value);
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, addr, value);
 }
 
 /************************************************************************
@@ -975,10 +959,9 @@ pub fn jr(cpu: &mut Cpu, dd: u8) {
 pub fn jr_z(cpu: &mut Cpu, interconnect: &mut Interconnect, dd: u8) {
     if cpu.registers.f.zero_flag() == 1 {
         jr(cpu, dd);
-        interconnect.emu_tick(3);
+        cpu.internal_cycle(interconnect);
     } else {
         cpu.pc += 2;
-        interconnect.emu_tick(2);
     }
 }
 
@@ -988,10 +971,9 @@ pub fn jr_z(cpu: &mut Cpu, interconnect: &mut Interconnect, dd: u8) {
 pub fn jr_nz(cpu: &mut Cpu, interconnect: &mut Interconnect, dd: u8) {
     if cpu.registers.f.zero_flag() == 0 {
         jr(cpu, dd);
-        interconnect.emu_tick(3);
+        cpu.internal_cycle(interconnect);
     } else {
         cpu.pc += 2;
-        interconnect.emu_tick(2);
     }
 }
 
@@ -1001,10 +983,9 @@ pub fn jr_nz(cpu: &mut Cpu, interconnect: &mut Interconnect, dd: u8) {
 pub fn jr_c(cpu: &mut Cpu, interconnect: &mut Interconnect, dd: u8) {
     if cpu.registers.f.carry_flag() == 1 {
         jr(cpu, dd);
-        interconnect.emu_tick(3);
+        cpu.internal_cycle(interconnect);
     } else {
         cpu.pc += 2;
-        interconnect.emu_tick(2);
     }
 }
 
@@ -1014,10 +995,9 @@ pub fn jr_c(cpu: &mut Cpu, interconnect: &mut Interconnect, dd: u8) {
 pub fn jr_nc(cpu: &mut Cpu, interconnect: &mut Interconnect, dd: u8) {
     if cpu.registers.f.carry_flag() == 0 {
         jr(cpu, dd);
-        interconnect.emu_tick(3);
+        cpu.internal_cycle(interconnect);
     } else {
         cpu.pc += 2;
-        interconnect.emu_tick(2);
     }
 }
 
@@ -1034,10 +1014,9 @@ pub fn jp(cpu: &mut Cpu, nn: u16) {
 pub fn jp_z(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
     if cpu.registers.f.zero_flag() == 1 {
         jp(cpu, nn);
-        interconnect.emu_tick(4);
+        cpu.internal_cycle(interconnect);
     } else {
         cpu.pc += 3;
-        interconnect.emu_tick(3);
     }
 }
 
@@ -1047,10 +1026,9 @@ pub fn jp_z(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
 pub fn jp_nz(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
     if cpu.registers.f.zero_flag() == 0 {
         jp(cpu, nn);
-        interconnect.emu_tick(4);
+        cpu.internal_cycle(interconnect);
     } else {
         cpu.pc += 3;
-        interconnect.emu_tick(3);
     }
 }
 
@@ -1060,10 +1038,9 @@ pub fn jp_nz(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
 pub fn jp_c(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
     if cpu.registers.f.carry_flag() == 1 {
         jp(cpu, nn);
-        interconnect.emu_tick(4);
+        cpu.internal_cycle(interconnect);
     } else {
         cpu.pc += 3;
-        interconnect.emu_tick(3);
     }
 }
 
@@ -1073,10 +1050,9 @@ pub fn jp_c(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
 pub fn jp_nc(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
     if cpu.registers.f.carry_flag() == 0 {
         jp(cpu, nn);
-        interconnect.emu_tick(4);
+        cpu.internal_cycle(interconnect);
     } else {
         cpu.pc += 3;
-        interconnect.emu_tick(3);
     }
 }
 
@@ -1084,15 +1060,12 @@ pub fn jp_nc(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
 ///
 /// Flags: None
 pub fn call(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
-    let mut stack_pointer: u16 = cpu.sp;
-    stack_pointer -= 2;
     cpu.pc += 3;
 
-    interconnect.write_mem(stack_pointer, (cpu.pc & 0x00FF) as u8);
-    interconnect.write_mem(stack_pointer + 1, ((cpu.pc & 0xFF00) >> 8) as u8);
+    let [upper, lower] = cpu.pc.to_be_bytes();
+    push_rr(cpu, interconnect, upper, lower);
 
     cpu.pc = nn;
-    cpu.sp = stack_pointer;
 }
 
 /// Calls to nn if zero flag is set
@@ -1101,10 +1074,8 @@ pub fn call(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
 pub fn call_z(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
     if cpu.registers.f.zero_flag() == 1 {
         call(cpu, interconnect, nn);
-        interconnect.emu_tick(6);
     } else {
         cpu.pc += 3;
-        interconnect.emu_tick(3);
     }
 }
 
@@ -1114,10 +1085,8 @@ pub fn call_z(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
 pub fn call_nz(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
     if cpu.registers.f.zero_flag() == 0 {
         call(cpu, interconnect, nn);
-        interconnect.emu_tick(6);
     } else {
         cpu.pc += 3;
-        interconnect.emu_tick(3);
     }
 }
 
@@ -1127,10 +1096,8 @@ pub fn call_nz(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
 pub fn call_c(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
     if cpu.registers.f.carry_flag() == 1 {
         call(cpu, interconnect, nn);
-        interconnect.emu_tick(6);
     } else {
         cpu.pc += 3;
-        interconnect.emu_tick(3);
     }
 }
 
@@ -1140,10 +1107,8 @@ pub fn call_c(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
 pub fn call_nc(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
     if cpu.registers.f.carry_flag() == 0 {
         call(cpu, interconnect, nn);
-        interconnect.emu_tick(6);
     } else {
         cpu.pc += 3;
-        interconnect.emu_tick(3);
     }
 }
 
@@ -1151,48 +1116,36 @@ pub fn call_nc(cpu: &mut Cpu, interconnect: &mut Interconnect, nn: u16) {
 ///
 /// Flags: None
 pub fn rst(cpu: &mut Cpu, interconnect: &mut Interconnect, n: u8) {
-    let mut stack_pointer: u16 = cpu.sp;
-
-    stack_pointer = stack_pointer.wrapping_sub(2);
     cpu.pc += 1;
 
-    // mem[SP] = lower byte of program counter
-    interconnect.write_mem(stack_pointer, (cpu.pc & 0x00FF) as u8);
-
-    // mem[SP+1] = upper byte of program counter (its + 1 below because we already moved the stack pointer)
-    interconnect.write_mem(stack_pointer + 1, ((cpu.pc & 0xFF00) >> 8) as u8);
+    let [upper, lower] = cpu.pc.to_be_bytes();
+    push_rr(cpu, interconnect, upper, lower);
 
     cpu.pc = n as u16;
-    cpu.sp = stack_pointer;
 }
 
 /// Returns
 ///
 /// Flags: None
-pub fn ret(cpu: &mut Cpu, interconnect: &Interconnect) {
-    let mut sp = cpu.sp;
-
+pub fn ret(cpu: &mut Cpu, interconnect: &mut Interconnect) {
     // PC = (SP)
-    let pc = u16::from_be_bytes([
-        interconnect.read_mem(sp.wrapping_add(1)),
-        interconnect.read_mem(sp),
-    ]);
+    cpu.pc = pop_rr(cpu, interconnect);
 
-    cpu.pc = pc;
-    sp = sp.wrapping_add(2);
-    cpu.sp = sp;
+    // Setting PC takes another cycle
+    cpu.internal_cycle(interconnect);
 }
 
 /// Returns if zero flag is set
 ///
 /// Flags: None
 pub fn ret_z(cpu: &mut Cpu, interconnect: &mut Interconnect) {
+    // Checking the condition takes a cycle
+    cpu.internal_cycle(interconnect);
+
     if cpu.registers.f.zero_flag() == 1 {
         ret(cpu, interconnect);
-        interconnect.emu_tick(5);
     } else {
         cpu.pc += 1;
-        interconnect.emu_tick(2);
     }
 }
 
@@ -1200,12 +1153,13 @@ pub fn ret_z(cpu: &mut Cpu, interconnect: &mut Interconnect) {
 ///
 /// Flags: None
 pub fn ret_nz(cpu: &mut Cpu, interconnect: &mut Interconnect) {
+    // Checking the condition takes a cycle
+    cpu.internal_cycle(interconnect);
+
     if cpu.registers.f.zero_flag() == 0 {
         ret(cpu, interconnect);
-        interconnect.emu_tick(5);
     } else {
         cpu.pc += 1;
-        interconnect.emu_tick(2);
     }
 }
 
@@ -1213,12 +1167,13 @@ pub fn ret_nz(cpu: &mut Cpu, interconnect: &mut Interconnect) {
 ///
 /// Flags: None
 pub fn ret_c(cpu: &mut Cpu, interconnect: &mut Interconnect) {
+    // Checking the condition takes a cycle
+    cpu.internal_cycle(interconnect);
+
     if cpu.registers.f.carry_flag() == 1 {
         ret(cpu, interconnect);
-        interconnect.emu_tick(5);
     } else {
         cpu.pc += 1;
-        interconnect.emu_tick(2);
     }
 }
 
@@ -1226,13 +1181,13 @@ pub fn ret_c(cpu: &mut Cpu, interconnect: &mut Interconnect) {
 ///
 /// Flags: None
 pub fn ret_nc(cpu: &mut Cpu, interconnect: &mut Interconnect) {
+    // Checking the condition takes a cycle
+    cpu.internal_cycle(interconnect);
+
     if cpu.registers.f.carry_flag() == 0 {
         ret(cpu, interconnect);
-        interconnect.emu_tick(5);
     } else {
         cpu.pc += 1;
-
-        interconnect.emu_tick(2);
     }
 }
 
@@ -1250,33 +1205,33 @@ pub fn ld_8bit(r: &mut u8, data: u8) {
 /// Loads data from io-port 'n' into A register
 ///
 /// Flags: None
-pub fn ld_a_from_io(cpu: &mut Cpu, interconnect: &Interconnect, n: u8) {
+pub fn ld_a_from_io(cpu: &mut Cpu, interconnect: &mut Interconnect, n: u8) {
     let addr: u16 = 0xFF00 + (n as u16);
-    cpu.registers.a = interconnect.read_mem(addr);
+    cpu.registers.a = cpu.read_cycle(interconnect, addr);
 }
 
 /// Loads data from A register into io-port 'n'
 ///
 /// Flags: None
-pub fn ld_io_from_a(cpu: &Cpu, interconnect: &mut Interconnect, n: u8) {
+pub fn ld_io_from_a(cpu: &mut Cpu, interconnect: &mut Interconnect, n: u8) {
     let addr: u16 = 0xFF00 + (n as u16);
-    interconnect.write_mem(addr, cpu.registers.a);
+    cpu.write_cycle(interconnect, addr, cpu.registers.a);
 }
 
 /// Loads data from [$FF00 + register C] into A register
 ///
 /// Flags: None
-pub fn ld_a_from_io_c(cpu: &mut Cpu, interconnect: &Interconnect) {
+pub fn ld_a_from_io_c(cpu: &mut Cpu, interconnect: &mut Interconnect) {
     let addr: u16 = 0xFF00 + (cpu.registers.c as u16);
-    cpu.registers.a = interconnect.read_mem(addr);
+    cpu.registers.a = cpu.read_cycle(interconnect, addr);
 }
 
 /// Loads data from register A into mem[$FF00 + register C]
 ///
 /// Flags: None
-pub fn ld_io_c_from_a(cpu: &Cpu, interconnect: &mut Interconnect) {
+pub fn ld_io_c_from_a(cpu: &mut Cpu, interconnect: &mut Interconnect) {
     let addr: u16 = 0xFF00 + (cpu.registers.c as u16);
-    interconnect.write_mem(addr, cpu.registers.a);
+    cpu.write_cycle(interconnect, addr, cpu.registers.a);
 }
 
 /************************************************************************
@@ -1291,33 +1246,28 @@ pub fn ld_io_c_from_a(cpu: &Cpu, interconnect: &mut Interconnect) {
 ///
 /// Flags:  Z N H C
 ///         Z N H C
-pub fn pop_rr(interconnect: &Interconnect, upper: &mut u8, lower: &mut u8, sp: &mut u16) {
-    let mut stack_pointer = *sp;
-
+pub fn pop_rr(cpu: &mut Cpu, interconnect: &mut Interconnect) -> u16 {
     // Value in memory (mem[sp])
-    let lower_byte: u8 = interconnect.read_mem(stack_pointer);
-    let upper_byte: u8 = interconnect.read_mem(stack_pointer.wrapping_add(1));
+    let lower_byte: u8 = cpu.read_inc_cycle(interconnect, cpu.sp);
+    cpu.sp = cpu.sp.wrapping_add(1);
+    let upper_byte: u8 = cpu.read_cycle(interconnect, cpu.sp);
+    cpu.sp = cpu.sp.wrapping_add(1);
 
-    // rr = mem[sp]
-    *lower = lower_byte;
-    *upper = upper_byte;
-
-    stack_pointer = stack_pointer.wrapping_add(2);
-    *sp = stack_pointer;
+    u16::from_be_bytes([upper_byte, lower_byte])
 }
 
 /// Contents of Register Pair are pushed onto stack
 ///
 /// Flags: None
-pub fn push_rr(interconnect: &mut Interconnect, upper: u8, lower: u8, sp: &mut u16) {
-    let mut stack_pointer = *sp;
-    stack_pointer = stack_pointer.wrapping_sub(2);
-
-    // mem[sp] = rr
-    interconnect.write_mem(stack_pointer, lower);
-    interconnect.write_mem(stack_pointer + 1, upper);
+pub fn push_rr(cpu: &mut Cpu, interconnect: &mut Interconnect, upper: u8, lower: u8) {
+    // SP is decremented before the first write
+    cpu.internal_cycle_at(interconnect, cpu.sp);
 
-    *sp = stack_pointer;
+    // mem[sp] = rr, upper byte first
+    cpu.sp = cpu.sp.wrapping_sub(1);
+    cpu.write_cycle(interconnect, cpu.sp, upper);
+    cpu.sp = cpu.sp.wrapping_sub(1);
+    cpu.write_cycle(interconnect, cpu.sp, lower);
 }
 
 /************************************************************************
@@ -1345,8 +1295,9 @@ pub fn bit_n_r(f: &mut Flags, r: &mut u8, n: u8) {
 ///
 /// Flags:  Z N H C
 ///         Z 0 1 -
-pub fn bit_n_hl(f: &mut Flags, interconnect: &mut Interconnect, addr: u16, n: u8) {
-    let value: u8 = interconnect.read_mem(addr);
+pub fn bit_n_hl(cpu: &mut Cpu, interconnect: &mut Interconnect, addr: u16, n: u8) {
+    let value: u8 = cpu.read_cycle(interconnect, addr);
+    let f = &mut cpu.registers.f;
 
     let nth_bit = (value >> n) & 0x01;
 
@@ -1375,15 +1326,13 @@ pub fn set_n_r(r: &mut u8, n: u8) {
 /// Sets the nth bit of mem[HL].
 ///
 /// Flags: None
-pub fn set_n_hl(interconnect: &mut Interconnect, addr: u16, n: u8) {
-    let mut value: u8 = interconnect.read_mem(addr);
-    interconnect.emu_tick(1);
+pub fn set_n_hl(cpu: &mut Cpu, interconnect: &mut Interconnect, addr: u16, n: u8) {
+    let mut value: u8 = cpu.read_cycle(interconnect, addr);
 
     // Set the nth bit
     value |= 1 << n;
 
-    interconnect.write_mem(addr, value);
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, addr, value);
 }
 
 /// Clears the nth bit of r
@@ -1401,15 +1350,13 @@ pub fn res_n_r(r: &mut u8, n: u8) {
 /// Clears the nth bit of mem[HL]
 ///
 /// Flags: None
-pub fn res_n_hl(interconnect: &mut Interconnect, addr: u16, n: u8) {
-    let mut value: u8 = interconnect.read_mem(addr);
-    interconnect.emu_tick(1);
+pub fn res_n_hl(cpu: &mut Cpu, interconnect: &mut Interconnect, addr: u16, n: u8) {
+    let mut value: u8 = cpu.read_cycle(interconnect, addr);
 
     // Clear the nth bit
     value &= !(1 << n);
 
-    interconnect.write_mem(addr, value);
-    interconnect.emu_tick(1);
+    cpu.write_cycle(interconnect, addr, value);
 }
 
 /************************************************************************
@@ -35,3 +35,21 @@ pub fn get_interrupt(interconnect: &mut Interconnect) -> Option<InterruptType> {
     }
     None
 }
+
+/// An interrupt is both requested and enabled, regardless of IME
+pub fn interrupt_pending(interconnect: &mut Interconnect) -> bool {
+    let interrupt_enable = interconnect.read_mem(INTERRUPT_ENABLE);
+    let interrupt_flag = interconnect.read_mem(INTERRUPT_FLAG);
+    (interrupt_enable & interrupt_flag & 0x1F) != 0
+}
+
+/// Address the CPU jumps to when servicing an interrupt
+pub fn interrupt_vector(interrupt: InterruptType) -> u16 {
+    match interrupt {
+        InterruptType::VBlank => 0x40,
+        InterruptType::LcdStat => 0x48,
+        InterruptType::Timer => 0x50,
+        InterruptType::Serial => 0x58,
+        InterruptType::Joypad => 0x60,
+    }
+}
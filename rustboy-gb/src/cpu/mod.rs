@@ -6,12 +6,9 @@ pub mod interrupts;
 use log::debug;
 use serde::{Deserialize, Serialize};
 
-use crate::constants::{
-    INTERRUPTS, INTERRUPT_ENABLE, INTERRUPT_FLAG, MAX_CYCLES_PER_FRAME, SERIAL_TRANSFER_CONTROL,
-    SERIAL_TRASFER_DATA,
-};
-use crate::cpu::instructions::push_rr;
-use crate::cpu::interrupts::{get_interrupt, InterruptType};
+use crate::constants::{INTERRUPTS, INTERRUPT_ENABLE, INTERRUPT_FLAG, MAX_CYCLES_PER_FRAME};
+use crate::cpu::interrupts::{get_interrupt, interrupt_pending, interrupt_vector};
+use crate::interconnect::ppu::OamAccess;
 use crate::interconnect::Interconnect;
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -181,6 +178,34 @@ impl Registers {
         }
     }
 
+    /// Registers as left by the SGB boot rom
+    pub fn new_sgb() -> Self {
+        Registers {
+            a: 0x01,
+            b: 0x00,
+            c: 0x14,
+            d: 0x00,
+            e: 0x00,
+            h: 0xC0,
+            l: 0x60,
+            f: Flags { data: 0x00 },
+        }
+    }
+
+    /// Registers as left by the CGB boot rom, A = 0x11 tells games they run on a CGB
+    pub fn new_cgb() -> Self {
+        Registers {
+            a: 0x11,
+            b: 0x00,
+            c: 0x00,
+            d: 0xFF,
+            e: 0x56,
+            h: 0x00,
+            l: 0x0D,
+            f: Flags { data: 0x80 },
+        }
+    }
+
     pub fn bc(&self) -> u16 {
         u16::from_be_bytes([self.b, self.c])
     }
@@ -245,6 +270,9 @@ pub struct Cpu {
     pub ime: bool,
     pub ime_to_be_enabled: bool,
     pub halted: bool,
+
+    // HALT was executed with IME=0 and an interrupt pending, the next opcode is read twice
+    pub halt_bug: bool,
     pub opcode: u8,
     pub last_cycle: u64,
 }
@@ -264,98 +292,124 @@ impl Cpu {
             ime: false,
             ime_to_be_enabled: false,
             halted: false,
+            halt_bug: false,
             opcode: 0,
             last_cycle: 0,
         }
     }
 
+    /// Run for one frame worth of cycles
     pub fn run(&mut self, interconnect: &mut Interconnect) {
         let mut cycles_this_update = 0;
 
         while cycles_this_update < MAX_CYCLES_PER_FRAME {
-            let cycles = self.last_cycle as usize;
-            cycles_this_update += cycles;
+            cycles_this_update += self.step(interconnect) as usize;
+        }
+    }
 
-            if self.pc == 0x100 {
-                interconnect.write_enabled = false;
-                interconnect.boot_active = false;
-            }
-            let running = !self.halted;
-
-            if running {
-                self.execute_instruction(interconnect);
-
-                if interconnect.read_mem(SERIAL_TRANSFER_CONTROL) == 0x81 {
-                    let c: char = interconnect.read_mem(SERIAL_TRASFER_DATA) as char;
-                    interconnect.serial.write_byte(c as u8);
-                    interconnect.serial.output();
-                    interconnect.write_mem(SERIAL_TRANSFER_CONTROL, 0x00);
-                }
-            } else {
-                interconnect.emu_tick(1);
-
-                let interrupt_flag = interconnect.read_mem(INTERRUPT_FLAG);
-                let interrupt_resquestd = interrupt_flag != 0;
-
-                if interrupt_resquestd {
-                    self.halted = false;
-                }
+    /// Execute a single instruction, or idle for one M-cycle while halted
+    ///
+    /// Returns the number of T-cycles that elapsed
+    pub fn step(&mut self, interconnect: &mut Interconnect) -> u64 {
+        let start = interconnect.cycles;
+
+        if self.pc == 0x100 {
+            interconnect.write_enabled = false;
+        }
+        let running = !self.halted;
+
+        if running {
+            self.execute_instruction(interconnect);
+        } else {
+            self.internal_cycle(interconnect);
+
+            // Any pending interrupt wakes the CPU up, it's only serviced if IME is set
+            if interrupt_pending(interconnect) {
+                self.halted = false;
             }
         }
+
+        interconnect.cycles - start
     }
 
+    /// Dispatch the highest priority pending interrupt if IME is set
+    ///
+    /// Takes 5 M-cycles: 2 idle cycles, pushing PC and jumping to the vector. The interrupt is
+    /// picked while the upper byte of PC is pushed, so if that push overwrites IE and no enabled
+    /// interrupt is left the dispatch is cancelled and execution continues at 0x0000.
     pub fn handle_interrupt(&mut self, interconnect: &mut Interconnect) {
-        let interrupts_enabled = self.ime || self.halted;
-        if interrupts_enabled {
-            let triggered = get_interrupt(interconnect);
-            if let Some(triggered_interrupt) = triggered {
-                self.halted = false;
-                if !self.ime {
-                    return;
-                }
-
-                // Disable Interrupts
-                self.ime = false;
-
-                let n = INTERRUPTS
-                    .iter()
-                    .position(|&i| i == triggered_interrupt)
-                    .unwrap();
-
-                // Push Current PC onto stack
-                let lower_pc = self.pc as u8;
-                let upper_pc = (self.pc >> 8) as u8;
-                push_rr(interconnect, upper_pc, lower_pc, &mut self.sp);
-
-                // Set PC equal to address of handler
-                self.pc = match triggered_interrupt {
-                    InterruptType::VBlank => 0x40,
-                    InterruptType::LcdStat => 0x48,
-                    InterruptType::Timer => 0x50,
-                    InterruptType::Serial => 0x58,
-                    InterruptType::Joypad => 0x58,
-                };
+        if !self.ime || !interrupt_pending(interconnect) {
+            return;
+        }
 
+        self.ime = false;
+        self.internal_cycle(interconnect);
+        self.internal_cycle(interconnect);
+
+        self.sp = self.sp.wrapping_sub(1);
+        self.write_cycle(interconnect, self.sp, (self.pc >> 8) as u8);
+
+        let triggered = get_interrupt(interconnect);
+
+        self.sp = self.sp.wrapping_sub(1);
+        self.write_cycle(interconnect, self.sp, self.pc as u8);
+
+        self.pc = match triggered {
+            Some(interrupt) => {
                 // Clean up the interrupt
-                let mut interrupt_flags = interconnect.read_mem(INTERRUPT_FLAG);
-                interrupt_flags &= !(1 << n);
-                interconnect.write_mem(INTERRUPT_FLAG, interrupt_flags);
+                let n = INTERRUPTS.iter().position(|&i| i == interrupt).unwrap();
+                let interrupt_flags = interconnect.read_mem(INTERRUPT_FLAG);
+                interconnect.write_mem(INTERRUPT_FLAG, interrupt_flags & !(1 << n));
 
-                self.ime_to_be_enabled = false;
-                interconnect.emu_tick(4);
+                interrupt_vector(interrupt)
             }
-        }
+            None => 0x0000,
+        };
+        self.internal_cycle(interconnect);
+    }
+
+    /// Read a byte from the bus, the rest of the system advances by one M-cycle first
+    pub fn read_cycle(&mut self, interconnect: &mut Interconnect, addr: u16) -> u8 {
+        interconnect.emu_tick(1);
+        interconnect.oam_bug(addr, OamAccess::Read);
+        interconnect.read_mem(addr)
+    }
+
+    /// Read a byte through a register that is incremented or decremented in the same M-cycle,
+    /// e.g. LD A, (HL+) and POP
+    pub fn read_inc_cycle(&mut self, interconnect: &mut Interconnect, addr: u16) -> u8 {
+        interconnect.emu_tick(1);
+        interconnect.oam_bug(addr, OamAccess::ReadIncrease);
+        interconnect.read_mem(addr)
+    }
+
+    /// Write a byte to the bus, the rest of the system advances by one M-cycle first
+    pub fn write_cycle(&mut self, interconnect: &mut Interconnect, addr: u16, value: u8) {
+        interconnect.emu_tick(1);
+        interconnect.oam_bug(addr, OamAccess::Write);
+        interconnect.write_mem(addr, value);
+    }
+
+    /// An M-cycle spent on internal work without touching the bus
+    pub fn internal_cycle(&mut self, interconnect: &mut Interconnect) {
+        interconnect.emu_tick(1);
+    }
+
+    /// An internal M-cycle that still puts `addr` on the bus, e.g. INC rr and DEC rr
+    pub fn internal_cycle_at(&mut self, interconnect: &mut Interconnect, addr: u16) {
+        interconnect.emu_tick(1);
+        interconnect.oam_bug(addr, OamAccess::Write);
     }
 
-    pub fn fetch_opcode(&mut self, interconnect: &Interconnect) {
-        self.opcode = interconnect.read_mem(self.pc);
+    pub fn fetch_opcode(&mut self, interconnect: &mut Interconnect) {
+        self.opcode = self.read_cycle(interconnect, self.pc);
     }
 
-    pub fn get_u16(&mut self, interconnect: &Interconnect) -> u16 {
-        u16::from_be_bytes([
-            interconnect.read_mem(self.pc + 2),
-            interconnect.read_mem(self.pc + 1),
-        ])
+    /// Read the 16 bit immediate operand, lower byte first
+    pub fn get_u16(&mut self, interconnect: &mut Interconnect) -> u16 {
+        let lower = self.read_cycle(interconnect, self.pc + 1);
+        let upper = self.read_cycle(interconnect, self.pc + 2);
+        u16::from_be_bytes([upper, lower])
     }
 
     pub fn log_registers(&self) {
@@ -1,3 +1,4 @@
+use crate::constants::{INTERRUPT_ENABLE, INTERRUPT_FLAG, TAC, TIMA};
 use crate::cpu::instructions::*;
 use crate::cpu::{Cpu, RegisterPair};
 use crate::interconnect::Interconnect;
@@ -477,12 +478,8 @@ fn pop_rr_test() {
     mmu.write_mem(cpu.sp, 0x55);
     mmu.write_mem(cpu.sp + 1, 0x33);
 
-    pop_rr(
-        &mmu,
-        &mut cpu.registers.b,
-        &mut cpu.registers.c,
-        &mut cpu.sp,
-    );
+    let value = pop_rr(&mut cpu, &mut mmu);
+    cpu.registers.set_bc(value);
 
     assert_eq!(cpu.registers.bc(), 0x3355);
 }
@@ -497,7 +494,8 @@ fn push_rr_test() {
 
     cpu.sp = 0x1007;
 
-    push_rr(&mut mmu, cpu.registers.b, cpu.registers.c, &mut cpu.sp);
+    let (high, low) = (cpu.registers.b, cpu.registers.c);
+    push_rr(&mut cpu, &mut mmu, high, low);
 
     let check: Vec<u16> = vec![0x0022, 0x0033, 0x1005];
 
@@ -521,7 +519,8 @@ fn push_af_test() {
 
     cpu.sp = 0x1007;
 
-    push_rr(&mut mmu, cpu.registers.a, cpu.registers.f.data, &mut cpu.sp);
+    let (high, low) = (cpu.registers.a, cpu.registers.f.data);
+    push_rr(&mut cpu, &mut mmu, high, low);
 
     let check: Vec<u16> = vec![0x0022, 0x0033, 0x1005];
 
@@ -714,7 +713,7 @@ fn ret_test() {
     mmu.write_mem(cpu.sp, 0xB5);
     mmu.write_mem(cpu.sp + 1, 0x18);
 
-    ret(&mut cpu, &mmu);
+    ret(&mut cpu, &mut mmu);
 
     let check: Vec<u16> = vec![0x2002, 0x18B5];
 
@@ -838,7 +837,7 @@ fn rlc_hl_test() {
 
     mmu.write_mem(0xFFF, 0x88);
 
-    rlc_hl(&mut cpu.registers.f, &mut mmu, addr);
+    rlc_hl(&mut cpu, &mut mmu, addr);
 
     let check = vec![mmu.read_mem(0xFFF), 1];
     assert_eq!(check, [0x11, 1]);
@@ -890,7 +889,7 @@ fn rrc_hl_test() {
     let value: u8 = 0x31;
     mmu.write_mem(addr, value);
 
-    rrc_hl(&mut cpu.registers.f, &mut mmu, addr);
+    rrc_hl(&mut cpu, &mut mmu, addr);
 
     assert_eq!(mmu.read_mem(addr), 0x98);
 }
@@ -920,7 +919,7 @@ fn rl_hl_test() {
 
     mmu.write_mem(addr, value);
 
-    rl_hl(&mut cpu.registers.f, &mut mmu, addr);
+    rl_hl(&mut cpu, &mut mmu, addr);
 
     let check = vec![mmu.read_mem(addr), cpu.registers.f.carry_flag()];
 
@@ -952,7 +951,7 @@ fn rr_hl_test() {
 
     mmu.write_mem(addr, value);
 
-    rr_hl(&mut cpu.registers.f, &mut mmu, addr);
+    rr_hl(&mut cpu, &mut mmu, addr);
 
     let check = vec![mmu.read_mem(addr), cpu.registers.f.carry_flag()];
 
@@ -980,7 +979,7 @@ fn sla_hl_test() {
     let value = 0xB1;
     mmu.write_mem(addr, value);
 
-    sla_hl(&mut cpu.registers.f, &mut mmu, addr);
+    sla_hl(&mut cpu, &mut mmu, addr);
 
     let check = vec![mmu.read_mem(addr), cpu.registers.f.carry_flag()];
 
@@ -1008,7 +1007,7 @@ fn sra_hl_test() {
     let value = 0xB8;
     mmu.write_mem(addr, value);
 
-    sra_hl(&mut cpu.registers.f, &mut mmu, addr);
+    sra_hl(&mut cpu, &mut mmu, addr);
 
     let check = vec![mmu.read_mem(addr), cpu.registers.f.carry_flag()];
     assert_eq!(check, [0xDC, 0x00]);
@@ -1035,7 +1034,7 @@ fn swap_hl_test() {
 
     mmu.write_mem(addr, value);
 
-    swap_hl(&mut cpu.registers.f, &mut mmu, addr);
+    swap_hl(&mut cpu, &mut mmu, addr);
 
     assert_eq!(mmu.read_mem(addr), 0xCB);
 }
@@ -1063,7 +1062,7 @@ fn srl_hl_test() {
 
     mmu.write_mem(addr, value);
 
-    srl_hl(&mut cpu.registers.f, &mut mmu, addr);
+    srl_hl(&mut cpu, &mut mmu, addr);
 
     let check = vec![mmu.read_mem(addr), cpu.registers.f.carry_flag()];
 
@@ -1092,7 +1091,7 @@ fn bit_n_hl_test() {
 
     mmu.write_mem(addr, 0);
 
-    bit_n_hl(&mut cpu.registers.f, &mut mmu, addr, 2);
+    bit_n_hl(&mut cpu, &mut mmu, addr, 2);
 
     let check = vec![mmu.read_mem(addr), cpu.registers.f.zero_flag()];
 
@@ -1112,13 +1111,14 @@ fn res_n_r_test() {
 
 #[test]
 fn res_n_hl_test() {
+    let mut cpu = Cpu::new();
     let mut mmu = Interconnect::new();
 
     let addr: u16 = 0xFFF;
 
     mmu.write_mem(addr, 0x69);
 
-    res_n_hl(&mut mmu, addr, 6);
+    res_n_hl(&mut cpu, &mut mmu, addr, 6);
 
     assert_eq!(mmu.read_mem(addr), 0x29);
 }
@@ -1137,13 +1137,14 @@ fn set_n_r_test() {
 #[test]
 
 fn set_n_hl_test() {
+    let mut cpu = Cpu::new();
     let mut mmu = Interconnect::new();
 
     let addr: u16 = 0xFFF;
 
     mmu.write_mem(addr, 0x69);
 
-    set_n_hl(&mut mmu, addr, 7);
+    set_n_hl(&mut cpu, &mut mmu, addr, 7);
 
     assert_eq!(mmu.read_mem(addr), 0xE9);
 }
@@ -1161,7 +1162,7 @@ fn ld_a_from_io_test() {
     let value = 0xAB;
     mmu.write_mem(0xFF00 + (offset as u16), value);
 
-    ld_a_from_io(&mut cpu, &mmu, offset);
+    ld_a_from_io(&mut cpu, &mut mmu, offset);
 
     assert_eq!(cpu.registers.a, mmu.read_mem(0xFF02));
 }
@@ -1175,7 +1176,7 @@ fn ld_a_from_io_c_test() {
     cpu.registers.c = 0xC;
     mmu.write_mem(0xFF0C, value);
 
-    ld_a_from_io_c(&mut cpu, &mmu);
+    ld_a_from_io_c(&mut cpu, &mut mmu);
 
     assert_eq!(cpu.registers.a, mmu.read_mem(0xFF0C));
 }
@@ -1185,13 +1186,13 @@ fn ld_io_from_a_test() {
     let mut cpu = Cpu::new();
     let mut mmu = Interconnect::new();
 
-    let offset = 0x02;
+    let offset = 0x01;
 
     cpu.registers.a = 0x81;
 
-    ld_io_from_a(&cpu, &mut mmu, offset);
+    ld_io_from_a(&mut cpu, &mut mmu, offset);
 
-    assert_eq!(cpu.registers.a, mmu.read_mem(0xFF02));
+    assert_eq!(cpu.registers.a, mmu.read_mem(0xFF01));
 }
 
 #[test]
@@ -1200,9 +1201,283 @@ fn ld_io_c_from_a_test() {
     let mut mmu = Interconnect::new();
 
     cpu.registers.a = 0x81;
-    cpu.registers.c = 0x0C;
+    cpu.registers.c = 0x81;
 
-    ld_io_c_from_a(&cpu, &mut mmu);
+    ld_io_c_from_a(&mut cpu, &mut mmu);
 
-    assert_eq!(cpu.registers.a, mmu.read_mem(0xFF0C));
+    assert_eq!(cpu.registers.a, mmu.read_mem(0xFF81));
+}
+
+/*************************************************************************
+ * Interrupt Tests
+ *************************************************************************/
+
+// CPU running from work RAM with the given interrupts enabled and requested
+fn interrupt_setup(enabled: u8, requested: u8) -> (Cpu, Interconnect) {
+    let mut cpu = Cpu::new();
+    let mut mmu = Interconnect::new();
+
+    cpu.pc = 0xC000;
+    cpu.sp = 0xD000;
+    mmu.write_mem(INTERRUPT_ENABLE, enabled);
+    mmu.write_mem(INTERRUPT_FLAG, requested);
+
+    (cpu, mmu)
+}
+
+#[test]
+fn interrupt_vectors() {
+    let vectors = [0x40, 0x48, 0x50, 0x58, 0x60];
+
+    for (n, vector) in vectors.iter().enumerate() {
+        let (mut cpu, mut mmu) = interrupt_setup(0x1F, 1 << n);
+        cpu.ime = true;
+
+        cpu.handle_interrupt(&mut mmu);
+
+        assert_eq!(cpu.pc, *vector);
+        assert!(!cpu.ime);
+        assert_eq!(mmu.read_mem(INTERRUPT_FLAG) & 0x1F, 0x00);
+    }
+}
+
+#[test]
+fn interrupt_dispatch_test() {
+    let (mut cpu, mut mmu) = interrupt_setup(0x1F, 0x14);
+    cpu.ime = true;
+    cpu.pc = 0xC123;
+
+    let start = mmu.cycles;
+    cpu.handle_interrupt(&mut mmu);
+
+    // Timer has priority over Joypad
+    assert_eq!(cpu.pc, 0x50);
+    assert_eq!(mmu.read_mem(INTERRUPT_FLAG) & 0x1F, 0x10);
+
+    // 5 M-cycles with PC pushed onto the stack
+    assert_eq!(mmu.cycles - start, 20);
+    assert_eq!(cpu.sp, 0xCFFE);
+    assert_eq!(mmu.read_mem(0xCFFF), 0xC1);
+    assert_eq!(mmu.read_mem(0xCFFE), 0x23);
+}
+
+#[test]
+fn no_interrupt_without_ime() {
+    let (mut cpu, mut mmu) = interrupt_setup(0x1F, 0x01);
+
+    cpu.handle_interrupt(&mut mmu);
+
+    assert_eq!(cpu.pc, 0xC000);
+    assert_eq!(mmu.read_mem(INTERRUPT_FLAG) & 0x1F, 0x01);
+}
+
+#[test]
+fn ie_push_cancels_dispatch() {
+    let (mut cpu, mut mmu) = interrupt_setup(0x01, 0x01);
+    cpu.ime = true;
+    cpu.pc = 0x0200;
+    cpu.sp = 0x0000;
+
+    // The upper byte of PC lands in IE and disables VBlank
+    cpu.handle_interrupt(&mut mmu);
+
+    assert_eq!(cpu.pc, 0x0000);
+    assert_eq!(mmu.read_mem(INTERRUPT_ENABLE), 0x02);
+    assert_eq!(mmu.read_mem(INTERRUPT_FLAG) & 0x1F, 0x01);
+    assert!(!cpu.ime);
+}
+
+#[test]
+fn ie_push_changes_interrupt() {
+    let (mut cpu, mut mmu) = interrupt_setup(0x01, 0x03);
+    cpu.ime = true;
+    cpu.pc = 0x0200;
+    cpu.sp = 0x0000;
+
+    // Only LCD STAT is enabled once the upper byte of PC is pushed
+    cpu.handle_interrupt(&mut mmu);
+
+    assert_eq!(cpu.pc, 0x48);
+    assert_eq!(mmu.read_mem(INTERRUPT_FLAG) & 0x1F, 0x01);
+}
+
+#[test]
+fn ei_delay_test() {
+    let (mut cpu, mut mmu) = interrupt_setup(0x01, 0x01);
+
+    // EI, NOP
+    mmu.write_mem(0xC000, 0xFB);
+    mmu.write_mem(0xC001, 0x00);
+
+    cpu.execute_instruction(&mut mmu);
+    assert!(!cpu.ime);
+
+    // The instruction after EI still runs before the interrupt
+    cpu.execute_instruction(&mut mmu);
+    assert_eq!(cpu.pc, 0xC002);
+    assert!(cpu.ime);
+
+    cpu.handle_interrupt(&mut mmu);
+    assert_eq!(cpu.pc, 0x40);
+}
+
+#[test]
+fn ei_di_test() {
+    let (mut cpu, mut mmu) = interrupt_setup(0x01, 0x01);
+
+    // EI, DI, NOP
+    mmu.write_mem(0xC000, 0xFB);
+    mmu.write_mem(0xC001, 0xF3);
+    mmu.write_mem(0xC002, 0x00);
+
+    for _ in 0..3 {
+        cpu.execute_instruction(&mut mmu);
+    }
+
+    assert_eq!(cpu.pc, 0xC003);
+    assert!(!cpu.ime);
+}
+
+#[test]
+fn reti_enables_immediately() {
+    let (mut cpu, mut mmu) = interrupt_setup(0x01, 0x01);
+
+    // RETI to 0xC100
+    mmu.write_mem(0xC000, 0xD9);
+    mmu.write_mem(0xD000, 0x00);
+    mmu.write_mem(0xD001, 0xC1);
+
+    cpu.execute_instruction(&mut mmu);
+    assert_eq!(cpu.pc, 0xC100);
+    assert!(cpu.ime);
+
+    cpu.handle_interrupt(&mut mmu);
+    assert_eq!(cpu.pc, 0x40);
+}
+
+#[test]
+fn halt_bug_test() {
+    let (mut cpu, mut mmu) = interrupt_setup(0x01, 0x01);
+    cpu.registers.a = 0x00;
+    cpu.registers.d = 0x00;
+
+    // HALT, LD A, u8 (0x14 = INC D)
+    mmu.write_mem(0xC000, 0x76);
+    mmu.write_mem(0xC001, 0x3E);
+    mmu.write_mem(0xC002, 0x14);
+
+    cpu.execute_instruction(&mut mmu);
+    assert!(!cpu.halted);
+
+    // The opcode after HALT is read twice, once as its own operand
+    cpu.execute_instruction(&mut mmu);
+    assert_eq!(cpu.registers.a, 0x3E);
+    assert_eq!(cpu.pc, 0xC002);
+
+    cpu.execute_instruction(&mut mmu);
+    assert_eq!(cpu.registers.d, 0x01);
+}
+
+#[test]
+fn halt_wakes_without_ime() {
+    let (mut cpu, mut mmu) = interrupt_setup(0x01, 0x00);
+
+    // HALT, NOP
+    mmu.write_mem(0xC000, 0x76);
+    mmu.write_mem(0xC001, 0x00);
+
+    cpu.step(&mut mmu);
+    assert!(cpu.halted);
+
+    // Requested but disabled interrupts don't wake the CPU up
+    mmu.write_mem(INTERRUPT_FLAG, 0x02);
+    cpu.step(&mut mmu);
+    assert!(cpu.halted);
+
+    mmu.write_mem(INTERRUPT_FLAG, 0x01);
+    cpu.step(&mut mmu);
+    assert!(!cpu.halted);
+
+    // Execution continues after HALT without servicing the interrupt
+    cpu.step(&mut mmu);
+    assert_eq!(cpu.pc, 0xC002);
+    assert_eq!(mmu.read_mem(INTERRUPT_FLAG) & 0x1F, 0x01);
+}
+
+#[test]
+fn halt_services_interrupt_with_ime() {
+    let (mut cpu, mut mmu) = interrupt_setup(0x04, 0x00);
+    cpu.ime = true;
+
+    // HALT
+    mmu.write_mem(0xC000, 0x76);
+
+    cpu.step(&mut mmu);
+    assert!(cpu.halted);
+
+    mmu.write_mem(INTERRUPT_FLAG, 0x04);
+    cpu.step(&mut mmu);
+    assert!(!cpu.halted);
+
+    cpu.handle_interrupt(&mut mmu);
+    assert_eq!(cpu.pc, 0x50);
+    assert_eq!(mmu.read_mem(0xCFFE), 0x01);
+}
+
+/*************************************************************************
+ * Memory Timing Tests
+ *************************************************************************/
+
+#[test]
+fn read_happens_in_last_cycle() {
+    let mut cpu = Cpu::new();
+    let mut mmu = Interconnect::new();
+
+    // TIMA increments every 4 M-cycles
+    mmu.write_mem(TAC, 0x05);
+    mmu.timer.set_system_counter(0);
+
+    // LD A, (0xFF05) reads TIMA in its 4th M-cycle
+    cpu.pc = 0xC000;
+    mmu.write_mem(0xC000, 0xFA);
+    mmu.write_mem(0xC001, 0x05);
+    mmu.write_mem(0xC002, 0xFF);
+
+    cpu.execute_instruction(&mut mmu);
+    assert_eq!(cpu.registers.a, 0x01);
+
+    // LD A, (0xFF00 + 0x05) reads it in its 3rd
+    mmu.timer.set_system_counter(0);
+    mmu.write_mem(TIMA, 0x00);
+    mmu.write_mem(0xC003, 0xF0);
+    mmu.write_mem(0xC004, 0x05);
+
+    cpu.execute_instruction(&mut mmu);
+    assert_eq!(cpu.registers.a, 0x00);
+}
+
+#[test]
+fn write_happens_in_last_cycle() {
+    let mut cpu = Cpu::new();
+    let mut mmu = Interconnect::new();
+
+    // LD (0xFF04), A resets DIV in its 4th and last M-cycle
+    cpu.pc = 0xC000;
+    mmu.write_mem(0xC000, 0xEA);
+    mmu.write_mem(0xC001, 0x04);
+    mmu.write_mem(0xC002, 0xFF);
+
+    cpu.execute_instruction(&mut mmu);
+    assert_eq!(mmu.timer.system_counter(), 0);
+
+    // PUSH BC: fetch, decrement SP, write upper byte, write lower byte
+    cpu.sp = 0xD000;
+    cpu.registers.b = 0xFF;
+    mmu.write_mem(0xC003, 0xC5);
+    mmu.write_mem(0xCFFF, 0x00);
+
+    let start = mmu.cycles;
+    cpu.execute_instruction(&mut mmu);
+    assert_eq!(mmu.cycles - start, 16);
+    assert_eq!(mmu.read_mem(0xCFFF), 0xFF);
 }
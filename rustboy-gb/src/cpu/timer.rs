@@ -3,31 +3,19 @@ use log::{debug, warn};
 use crate::constants::{DIV, TAC, TIMA, TMA};
 use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Copy, Clone, Deserialize, Serialize)]
-pub struct Clock {
-    pub period: u32,
-    pub n: u32,
-}
-
-impl Clock {
-    pub fn power_up(period: u32) -> Self {
-        Self { period, n: 0x00 }
-    }
-
-    pub fn next(&mut self, cycles: u32) -> u32 {
-        self.n += cycles;
-        let res = self.n / self.period;
-        self.n %= self.period;
-        res
-    }
-}
+// T-cycles between TIMA overflowing and being reloaded from TMA
+const RELOAD_DELAY: u8 = 4;
 
 /// Gameboy Timer
+///
+/// Everything is driven by a 16 bit system counter incremented every T-cycle, DIV is its upper
+/// byte. TIMA is incremented on the falling edge of one of the counter bits (selected by TAC)
+/// ANDed with the timer enable bit, which is why writing DIV or TAC can increment TIMA too.
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Timer {
-    /// Divider Register - Incremented at rate of 16384Hz, Writing any vlaue to this register
-    /// resets it to 0x00
-    div: u8,
+    /// Internal counter, the upper 8 bits are the Divider Register. Writing any value to DIV
+    /// resets the whole counter to 0x0000
+    system_counter: u16,
 
     /// Internal Timer Counter(R/W) - Incremented by clock frequency specified by the TAC register
     /// When the value overflows then it will be reset to value specified in TMA and interrupt
@@ -40,85 +28,131 @@ pub struct Timer {
     /// Timer Control (R/W)
     tac: u8,
 
-    counter: u64,
+    // Selected counter bit AND timer enable, as of the last update
+    last_bit: bool,
 
-    pub div_clock: Clock,
-    pub tima_clock: Clock,
+    // T-cycles until an overflowed TIMA is reloaded from TMA, 0 when no reload is pending.
+    // TIMA reads 0x00 meanwhile, and writing it cancels the reload
+    reload_delay: u8,
+
+    // T-cycles left of the M-cycle in which TIMA was reloaded. TIMA writes are ignored and TMA
+    // writes go through to TIMA
+    reloading: u8,
 }
 
 impl Timer {
     pub fn new() -> Self {
         Self {
-            div: 0,
+            system_counter: 0,
             tima: 0,
             tma: 0,
             tac: 0,
-            counter: 0,
-            div_clock: Clock::power_up(256),
-            tima_clock: Clock::power_up(1024),
+            last_bit: false,
+            reload_delay: 0,
+            reloading: 0,
         }
     }
 
-    pub fn set_div(&mut self, value: u8) {
-        self.div = value;
-    }
-    pub fn div(&self) -> u8 {
-        self.div
+    /// Advance by `t_cycles`, returns true if the timer interrupt should be requested
+    pub fn tick(&mut self, t_cycles: u32) -> bool {
+        let mut interrupt = false;
+
+        for _ in 0..t_cycles {
+            self.reloading = self.reloading.saturating_sub(1);
+
+            if self.reload_delay > 0 {
+                self.reload_delay -= 1;
+
+                if self.reload_delay == 0 {
+                    self.tima = self.tma;
+                    self.reloading = RELOAD_DELAY;
+                    interrupt = true;
+                }
+            }
+
+            self.system_counter = self.system_counter.wrapping_add(1);
+            self.detect_edge();
+        }
+
+        interrupt
     }
 
-    pub fn set_tima(&mut self, value: u8) {
-        self.tima = value;
+    // Counter bit TIMA is clocked from
+    fn selected_bit(&self) -> u16 {
+        match self.tac & 0x03 {
+            0x00 => 9,
+            0x01 => 3,
+            0x02 => 5,
+            0x03 => 7,
+            _ => unreachable!(),
+        }
     }
 
-    pub fn tima(&self) -> u8 {
-        self.tima
+    fn timer_bit(&self) -> bool {
+        let enabled = (self.tac & 0x04) != 0;
+        enabled && (self.system_counter >> self.selected_bit()) & 1 == 1
     }
 
-    pub fn set_tma(&mut self, value: u8) {
-        self.tma = value;
+    fn detect_edge(&mut self) {
+        let bit = self.timer_bit();
+        if self.last_bit && !bit {
+            self.increment_tima();
+        }
+
+        self.last_bit = bit;
     }
 
-    pub fn tma(&self) -> u8 {
-        self.tma
+    fn increment_tima(&mut self) {
+        let (value, overflow) = self.tima.overflowing_add(1);
+        self.tima = value;
+
+        if overflow {
+            self.reload_delay = RELOAD_DELAY;
+        }
     }
 
-    pub fn set_tac(&mut self, value: u8) {
-        self.tac = value;
+    pub fn system_counter(&self) -> u16 {
+        self.system_counter
     }
 
-    pub fn tac(&self) -> u8 {
-        self.tac
+    /// Set the counter without any side effects, e.g. to the value the boot rom leaves behind
+    pub fn set_system_counter(&mut self, value: u16) {
+        self.system_counter = value;
+        self.last_bit = self.timer_bit();
     }
 
-    pub fn set_counter(&mut self, value: u64) {
-        self.counter = value;
+    pub fn div(&self) -> u8 {
+        (self.system_counter >> 8) as u8
     }
 
-    pub fn counter(&self) -> u64 {
-        self.counter
+    pub fn tima(&self) -> u8 {
+        self.tima
     }
 
-    pub fn div_clock(&self) -> Clock {
-        self.div_clock
+    pub fn tma(&self) -> u8 {
+        self.tma
     }
 
-    pub fn tima_clock(&self) -> Clock {
-        self.tima_clock
+    pub fn tac(&self) -> u8 {
+        self.tac
     }
 
     pub fn log_timer(&self) {
         debug!(
             "DIV: {} TIMA: {} TMA: {} TAC: {}",
-            self.div, self.tima, self.tma, self.tac
+            self.div(),
+            self.tima,
+            self.tma,
+            self.tac
         );
     }
 
     pub fn timer_read(&self, addr: u16) -> u8 {
         match addr {
-            DIV => self.div,
+            DIV => self.div(),
             TIMA => self.tima,
             TMA => self.tma,
-            TAC => self.tac,
+            TAC => self.tac | 0xF8,
             _ => panic!("{}:  NOT A READABLE TIMER ADRESS", addr),
         }
     }
@@ -126,27 +160,24 @@ impl Timer {
     pub fn timer_write(&mut self, addr: u16, value: u8) {
         match addr {
             DIV => {
-                self.div = 0x00;
-                self.div_clock.n = 0x00;
-                self.tima = 0x00;
+                self.system_counter = 0;
+                self.detect_edge();
             }
-            TIMA => self.tima = value,
-            TMA => self.tma = value,
-            TAC => {
-                let clocked_enabled: bool = (self.tac & 0x03) != (value & 0x03);
-                if clocked_enabled {
-                    self.tima_clock.n = 0x00;
-                    let clock_select = value & 0x03;
-                    self.tima_clock.period = match clock_select {
-                        0x00 => 1024,
-                        0x01 => 16,
-                        0x02 => 64,
-                        0x03 => 256,
-                        _ => panic!(""),
-                    };
-                    self.tima = self.tma;
+            TIMA => {
+                if self.reloading == 0 {
+                    self.tima = value;
+                    self.reload_delay = 0;
+                }
+            }
+            TMA => {
+                self.tma = value;
+                if self.reloading > 0 {
+                    self.tima = value;
                 }
-                self.tac = value;
+            }
+            TAC => {
+                self.tac = value & 0x07;
+                self.detect_edge();
             }
 
             _ => warn!("{}: NOT A WRITABLE TIMER ADDRESS", addr),
@@ -1,16 +1,21 @@
-use crate::constants::PC_AFTER_BOOT;
-use crate::cpu::Cpu;
-use crate::interconnect::cartridge::cartridge_info::ram_size;
-use crate::interconnect::cartridge::cartridge_info::u8_to_cart_type;
-use crate::interconnect::cartridge::cartridge_info::CartridgeType;
+use crate::constants::{
+    CGB_COMPATIBILITY_SHADES, DEFAULT_BOOT_ROM, DIV_COUNTER_AFTER_BOOT, DIV_COUNTER_AFTER_BOOT_CGB,
+    PC_AFTER_BOOT,
+};
+use crate::cpu::{Cpu, Registers};
+use crate::interconnect::cartridge::cartridge_info::BOOT_ROM_SIZE;
+use crate::interconnect::cartridge::header::{CartridgeHeader, CgbFlag};
 use crate::interconnect::cartridge::Cartridge;
-use crate::interconnect::Interconnect;
+use crate::interconnect::{Interconnect, Model};
+
+pub use crate::interconnect::cartridge::cartridge_info::{ChecksumPolicy, LoadError};
 
 use anyhow::Error;
 use anyhow::Result;
+use log::{info, warn};
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
@@ -21,6 +26,18 @@ pub struct GameBoy {
     pub cpu: Cpu,
     pub interconnect: Interconnect,
     pub booted : bool,
+
+    // Where battery backed cartridge RAM is loaded from and flushed to
+    #[serde(skip)]
+    pub battery_save: Option<PathBuf>,
+
+    // Hardware to run games on, picked from the header when None
+    #[serde(skip)]
+    pub model_override: Option<Model>,
+
+    // Rom the game was loaded from, None for roms loaded from memory
+    #[serde(skip)]
+    pub rom_path: Option<PathBuf>,
 }
 
 impl GameBoy {
@@ -29,65 +46,273 @@ impl GameBoy {
             cpu: Cpu::new(),
             interconnect: Interconnect::new(),
             booted: false,
+            battery_save: None,
+            model_override: None,
+            rom_path: None,
         }
     }
 
-    pub fn save_state(&self, name: &str) {
-        println!("NAME: {}", name);
-        let encoded: Vec<u8> = bincode::serialize(&self).unwrap();
-        let compressed = compress(&encoded, Format::Zlib, CompressionLevel::Default).unwrap();
-        let _ = std::fs::write(name, &compressed).unwrap();
+    pub fn save_state(&self, path: &Path) -> Result<(), Error> {
+        let encoded: Vec<u8> = bincode::serialize(&self)?;
+        let compressed = compress(&encoded, Format::Zlib, CompressionLevel::Default)
+            .map_err(|e| anyhow::anyhow!("COULD NOT COMPRESS STATE: {:?}", e))?;
+        fs::write(path, compressed)?;
+        info!("WROTE STATE: {}", path.display());
+        Ok(())
+    }
+
+    /// Where save states go, next to the rom or named after the title for roms loaded from memory
+    pub fn state_save_path(&self) -> PathBuf {
+        match &self.rom_path {
+            Some(path) => path.with_extension("state"),
+            None => {
+                let title = &self.interconnect.cartridge.header.title;
+                PathBuf::from(format!("{}.state", file_name(title)))
+            }
+        }
     }
 
     pub fn load_state(&mut self, compressed_state: Vec<u8>) {
         let (decompressed, checksum) = decompress(&compressed_state, Format::Zlib).unwrap();
         let decoded: GameBoy = bincode::deserialize(&decompressed[..]).unwrap();
+        let battery_save = self.battery_save.take();
+        let model_override = self.model_override;
+        let rom_path = self.rom_path.take();
         *self = decoded;
+        self.battery_save = battery_save;
+        self.model_override = model_override;
+        self.rom_path = rom_path;
     }
 
     pub fn boot(&mut self, game: &str, skip_boot: bool) -> Result<(), Error> {
-        self.booted = true;
-        let boot_rom = if !skip_boot {
-            let boot_rom = "roms/bootix_dmg.bin";
-            let boot_rom_path: &Path = Path::new(boot_rom);
-            read_file(boot_rom_path)?
+        let boot_rom = if skip_boot {
+            None
         } else {
-            Vec::new()
+            Some(DEFAULT_BOOT_ROM)
+        };
+
+        self.boot_with(game, boot_rom)
+    }
+
+    /// Boot a game, running the given boot rom first or jumping straight to the game when `None`
+    pub fn boot_with(&mut self, game: &str, boot_rom: Option<&str>) -> Result<(), Error> {
+        let boot_rom = match boot_rom {
+            Some(boot_rom) => Some(read_file(Path::new(boot_rom))?),
+            None => None,
         };
 
         let game_rom_path: &Path = Path::new(game);
         let game_rom: Vec<u8> = read_file(game_rom_path)?;
 
-        let cart_type_value: u8 = game_rom[0x147];
-        let rom_size: u8 = game_rom[0x148];
-        let ram_s: u8 = game_rom[0x149];
+        self.load_rom(&game_rom, boot_rom.as_deref(), ChecksumPolicy::default())?;
+        info!("FILE NAME: {}", game_rom_path.display());
+        self.rom_path = Some(game_rom_path.to_path_buf());
+
+        if let Err(e) = self.attach_battery_save(battery_save_path(game_rom_path)) {
+            warn!("COULD NOT LOAD SAVE: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Use `path` for battery backed RAM, loading it if the cartridge has a battery and the file
+    /// already exists. Saves of the wrong size are rejected and left untouched
+    pub fn attach_battery_save(&mut self, path: impl Into<PathBuf>) -> Result<(), Error> {
+        let path = path.into();
+        let cartridge = &mut self.interconnect.cartridge;
 
-        let ram = vec![0x00; ram_size(ram_s) as usize];
-        let cart_type: CartridgeType = u8_to_cart_type(cart_type_value);
+        if cartridge.has_battery() && path.is_file() {
+            cartridge.import_ram(&read_file(&path)?)?;
+            info!("LOADED SAVE: {}", path.display());
+        }
 
-        self.interconnect.cartridge = Cartridge::new(&game_rom, &ram, &cart_type);
-        let file_name: Vec<&str> = game_rom_path.file_name().unwrap().to_str().unwrap().split('.').collect();
-        self.interconnect.cartridge.title = file_name[0].to_string();
-        println!("FILE NAME: {}", file_name[0]);
-        println!("CART TYPE: {:?}", cart_type);
-        println!("ROM_SIZE: {:#X}", rom_size);
-        println!("RAM_SIZE: {:#X} KiB", ram_size(ram_s));
-        println!("CHECKSUM: {}", self.interconnect.cartridge.checksum());
+        self.battery_save = Some(path);
+        Ok(())
+    }
 
-        self.cpu.pc = if skip_boot {
-            //self.interconnect.load_game_rom(&game_rom);
-            self.interconnect.boot_active = false;
+    /// Write battery backed RAM to the attached save file if it changed since the last flush
+    pub fn flush_battery_save(&mut self) -> Result<(), Error> {
+        let cartridge = &mut self.interconnect.cartridge;
 
-            PC_AFTER_BOOT
+        if let Some(path) = &self.battery_save {
+            if cartridge.has_battery() && cartridge.take_ram_dirty() {
+                fs::write(path, cartridge.export_ram())?;
+                info!("WROTE SAVE: {}", path.display());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Tilt the cartridge by `x` g to the right and `y` g towards the player, up to 2 g. Only
+    /// MBC7 cartridges have an accelerometer, for the others this does nothing
+    pub fn set_tilt(&mut self, x: f32, y: f32) {
+        self.interconnect.cartridge.mbc.set_tilt(x, y);
+    }
+
+    /// Whether the rumble motor of the cartridge is running right now
+    pub fn rumble(&self) -> bool {
+        self.interconnect.cartridge.mbc.motor_on()
+    }
+
+    /// Rumble strength from 0 to 1: the share of the time the motor was on since the last call.
+    /// Meant to be called once per frame, it's always 0 for cartridges without a motor
+    pub fn take_rumble_duty(&mut self) -> f32 {
+        self.interconnect.cartridge.mbc.take_motor_duty()
+    }
+
+    /// Create a Gameboy with a game that is already in memory, running the boot rom first if one
+    /// is given. A mismatching header checksum is only logged, see `from_rom_bytes_with_policy`
+    pub fn from_rom_bytes(rom: &[u8], boot_rom: Option<&[u8]>) -> Result<GameBoy, LoadError> {
+        GameBoy::from_rom_bytes_with_policy(rom, boot_rom, ChecksumPolicy::default())
+    }
+
+    pub fn from_rom_bytes_with_policy(
+        rom: &[u8],
+        boot_rom: Option<&[u8]>,
+        checksum_policy: ChecksumPolicy,
+    ) -> Result<GameBoy, LoadError> {
+        let mut gameboy = GameBoy::new();
+        gameboy.load_rom(rom, boot_rom, checksum_policy)?;
+        Ok(gameboy)
+    }
+
+    /// Boot a game that is already in memory, running the boot rom first if one is given
+    ///
+    /// Games with CGB support run in CGB mode unless `model_override` says otherwise. The DMG
+    /// boot rom can't set up CGB hardware, so it's skipped for them. Nothing is changed if the
+    /// rom is rejected.
+    pub fn load_rom(
+        &mut self,
+        game_rom: &[u8],
+        boot_rom: Option<&[u8]>,
+        checksum_policy: ChecksumPolicy,
+    ) -> Result<(), LoadError> {
+        if let Some(boot_rom) = boot_rom {
+            if boot_rom.len() != BOOT_ROM_SIZE {
+                return Err(LoadError::InvalidBootRomSize(boot_rom.len()));
+            }
+        }
+
+        let header = CartridgeHeader::parse(game_rom)?;
+
+        if !header.valid_header_checksum {
+            let expected = header.header_checksum;
+            let actual = header.computed_header_checksum;
+
+            match checksum_policy {
+                ChecksumPolicy::Ignore => (),
+                ChecksumPolicy::Warn => warn!(
+                    "BAD HEADER CHECKSUM: EXPECTED {:#X}, GOT {:#X}",
+                    expected, actual
+                ),
+                ChecksumPolicy::Reject => {
+                    return Err(LoadError::BadHeaderChecksum { expected, actual })
+                }
+            }
+        }
+
+        if header.oversized() {
+            warn!(
+                "ROM FILE IS BIGGER THAN ITS HEADER SAYS: {:#X} > {:#X}",
+                header.file_size, header.rom_size
+            );
+        }
+
+        self.booted = true;
+
+        info!("TITLE: {}", header.title);
+        info!("CART TYPE: {:?}", header.cartridge_type);
+        info!("ROM_SIZE: {:#X}", header.rom_size);
+        info!("RAM_SIZE: {:#X}", header.ram_size);
+        info!("CHECKSUM: {}", header.valid_header_checksum);
+
+        let model = match (self.model_override, header.cgb_flag) {
+            (Some(model), _) => model,
+            (None, CgbFlag::Dmg) => Model::Dmg,
+            (None, CgbFlag::Supported | CgbFlag::Only) => Model::Cgb,
+        };
+
+        if model == Model::Sgb && !header.sgb_flag {
+            warn!("GAME DOESN'T SUPPORT THE SGB, ITS COMMANDS ARE IGNORED");
+        }
+        let sgb_supported = header.sgb_flag;
+
+        let boot_rom = if model == Model::Cgb && boot_rom.is_some() {
+            warn!("SKIPPING DMG BOOT ROM FOR CGB GAME");
+            None
         } else {
-            self.interconnect.load_game_rom(&game_rom);
-            self.interconnect.load_boot_rom(&boot_rom);
-            0x0000
+            boot_rom
+        };
+
+        // The CGB runs DMG-only games without its registers and with the DMG PPU, coloring them
+        // with a fixed palette
+        let compatibility = model == Model::Cgb && header.cgb_flag == CgbFlag::Dmg;
+
+        let ram = vec![0x00; header.ram_size];
+        self.interconnect.cartridge = Cartridge::new(game_rom, &ram, header);
+        self.interconnect.set_model(model);
+        if compatibility {
+            info!("RUNNING DMG GAME IN CGB COMPATIBILITY MODE");
+            self.interconnect.set_cgb_features(false);
+            self.interconnect.ppu.set_shades(CGB_COMPATIBILITY_SHADES);
+        }
+        self.interconnect.sgb.supported = sgb_supported;
+
+        match model {
+            Model::Dmg => (),
+            Model::Sgb => self.cpu.registers = Registers::new_sgb(),
+            Model::Cgb => self.cpu.registers = Registers::new_cgb(),
+        }
+
+        self.cpu.pc = match boot_rom {
+            None => {
+                //self.interconnect.load_game_rom(&game_rom);
+                self.interconnect.boot_active = false;
+                self.interconnect.timer.set_system_counter(match model {
+                    Model::Dmg | Model::Sgb => DIV_COUNTER_AFTER_BOOT,
+                    Model::Cgb => DIV_COUNTER_AFTER_BOOT_CGB,
+                });
+
+                PC_AFTER_BOOT
+            }
+            Some(boot_rom) => {
+                self.interconnect.load_game_rom(game_rom);
+                self.interconnect.load_boot_rom(boot_rom);
+                0x0000
+            }
         };
+
         Ok(())
     }
 }
 
+/// The .sav file next to a rom, e.g. `roms/tetris.gb` -> `roms/tetris.sav`
+pub fn battery_save_path(rom_path: &Path) -> PathBuf {
+    rom_path.with_extension("sav")
+}
+
+// `title` with anything that can't go in a file name replaced, "rustboy" when it's empty
+fn file_name(title: &str) -> String {
+    let name: String = title
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if name.is_empty() {
+        String::from("rustboy")
+    } else {
+        name
+    }
+}
+
 fn read_file(path: &Path) -> Result<Vec<u8>, std::io::Error> {
     fs::read(path)
 }
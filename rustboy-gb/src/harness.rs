@@ -0,0 +1,126 @@
+//! In-process runner for test roms
+//!
+//! Boots a rom from memory and runs it until it reports a result, so test suites can be run as
+//! ordinary `cargo test` cases. A rom is considered done when:
+//!
+//! * it prints "Passed" or "Failed" over the serial port (Blargg's tests)
+//! * it executes `LD B,B` with a Mooneye register signature (Mooneye's tests use it as a
+//!   software breakpoint and leave the Fibonacci numbers `B=3,C=5,D=8,E=13,H=21,L=34` in the
+//!   registers on success), or any `LD B,B` if breakpoints were asked for. Blargg's `ld r,r`
+//!   test executes `LD B,B` as a normal instruction, so this is opt-in
+//! * the frame limit is reached
+use crate::constants::MAX_CYCLES_PER_FRAME;
+use crate::gameboy::{GameBoy, LoadError};
+
+/// Registers (B, C, D, E, H, L) left behind by a passing Mooneye test
+pub const MOONEYE_PASS: [u8; 6] = [3, 5, 8, 13, 21, 34];
+
+/// Registers (B, C, D, E, H, L) left behind by a failing Mooneye test
+pub const MOONEYE_FAIL: [u8; 6] = [0x42; 6];
+
+/// `LD B,B`, used as a software breakpoint by test roms
+const LD_B_B: u8 = 0x40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    SerialPassed,
+    SerialFailed,
+    MooneyePassed,
+    MooneyeFailed,
+
+    /// `LD B,B` was executed without a known register signature, only when breakpoints are enabled
+    Breakpoint,
+
+    /// The frame limit was reached before the rom reported anything
+    Timeout,
+}
+
+impl StopReason {
+    pub fn passed(&self) -> bool {
+        matches!(self, StopReason::SerialPassed | StopReason::MooneyePassed)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    pub reason: StopReason,
+    pub frames: u64,
+    pub cycles: u64,
+
+    /// Everything the rom wrote to the serial port
+    pub serial: String,
+}
+
+impl TestResult {
+    pub fn passed(&self) -> bool {
+        self.reason.passed()
+    }
+}
+
+/// Boot `rom` (skipping the boot rom) and run it for at most `max_frames` frames
+pub fn run_test_rom(rom: &[u8], max_frames: u64) -> Result<TestResult, LoadError> {
+    let mut gameboy = GameBoy::from_rom_bytes(rom, None)?;
+
+    Ok(run_until_done(&mut gameboy, max_frames, false))
+}
+
+/// Run an already booted Gameboy until the rom reports a result or `max_frames` frames have run
+pub fn run_until_done(
+    gameboy: &mut GameBoy,
+    max_frames: u64,
+    stop_on_breakpoint: bool,
+) -> TestResult {
+    gameboy.interconnect.serial.set_echo(false);
+
+    let start = gameboy.interconnect.cycles;
+    let max_cycles = max_frames * MAX_CYCLES_PER_FRAME as u64;
+    let mut serial = String::new();
+
+    let reason = loop {
+        let elapsed = gameboy.interconnect.cycles - start;
+        if elapsed >= max_cycles {
+            break StopReason::Timeout;
+        }
+
+        gameboy.cpu.step(&mut gameboy.interconnect);
+
+        let bytes = gameboy.interconnect.serial.take_bytes();
+        if !bytes.is_empty() {
+            serial.push_str(&String::from_utf8_lossy(&bytes));
+
+            if serial.contains("Passed") {
+                break StopReason::SerialPassed;
+            } else if serial.contains("Failed") {
+                break StopReason::SerialFailed;
+            }
+        }
+
+        if !gameboy.cpu.halted && gameboy.cpu.opcode == LD_B_B {
+            let registers = &gameboy.cpu.registers;
+            let signature = [
+                registers.b,
+                registers.c,
+                registers.d,
+                registers.e,
+                registers.h,
+                registers.l,
+            ];
+
+            match signature {
+                MOONEYE_PASS => break StopReason::MooneyePassed,
+                MOONEYE_FAIL => break StopReason::MooneyeFailed,
+                _ if stop_on_breakpoint => break StopReason::Breakpoint,
+                _ => (),
+            }
+        }
+    };
+
+    let cycles = gameboy.interconnect.cycles - start;
+
+    TestResult {
+        reason,
+        frames: cycles / MAX_CYCLES_PER_FRAME as u64,
+        cycles,
+        serial,
+    }
+}
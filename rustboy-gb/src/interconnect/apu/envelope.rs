@@ -1,6 +1,8 @@
 use crate::nth_bit;
 
-#[derive(Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct VolumeEnvelope {
     finished: bool,
     timer: i32,
@@ -69,6 +71,7 @@ impl VolumeEnvelope {
             return;
         }
 
+        self.timer -= 1;
         if self.timer <= 0 {
             self.timer = if self.period != 0 {
                 self.period as i32
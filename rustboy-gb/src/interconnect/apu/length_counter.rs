@@ -1,6 +1,8 @@
 use crate::nth_bit;
 
-#[derive(Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct LengthCounter {
     enabled: bool,
     full_length: i32,
@@ -27,29 +29,17 @@ impl LengthCounter {
     }
 
     pub fn set_length(&mut self, value: u8) {
-        self.length = value as i32;
-    }
-
-    pub fn length(&self) -> i32 {
-        self.length
+        self.length = self.full_length - value as i32;
     }
 
     pub fn set_full_length(&mut self, value: i32) {
         self.full_length = value;
     }
 
-    pub fn full_length(&self) -> i32 {
-        self.full_length
-    }
-
     pub fn set_frame_sequencer(&mut self, frame_sequencer: i32) {
         self.frame_sequencer = frame_sequencer;
     }
 
-    pub fn frame_sequencer(&self) -> i32 {
-        self.frame_sequencer
-    }
-
     pub fn step(&mut self) {
         if self.enabled && self.length > 0 {
             self.length = self.length - 1;
@@ -1,7 +1,10 @@
+use crate::constants::{CLOCK_SPEED, SAMPLE_RATE};
 use crate::nth_bit;
 
 use self::{noise::Noise, square1::Square1, square2::Square2, wave::Wave};
 
+use serde::{Deserialize, Serialize};
+
 mod envelope;
 mod length_counter;
 mod noise;
@@ -17,6 +20,9 @@ pub const DUTY_CYCLES: [[bool; 8]; 4] = [
     [false, true, true, true, true, true, true, false],
 ];
 
+// Samples nobody drains are dropped, oldest first, beyond this many frames of video
+const MAX_QUEUED_VIDEO_FRAMES: u32 = 2;
+
 pub trait Channel {
     fn read(&self, addr: u16) -> u8;
     fn write(&mut self, addr: u16, value: u8);
@@ -24,7 +30,7 @@ pub trait Channel {
     fn enabled(&self) -> bool;
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 enum FrameSequencer {
     Step0,
     Step1,
@@ -63,6 +69,7 @@ fn frame_sequencer_to_int(fs: FrameSequencer) -> i32 {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Apu {
     channel_1: Square1,
     channel_2: Square2,
@@ -77,13 +84,27 @@ pub struct Apu {
     left_enables: [bool; 4],
     right_enables: [bool; 4],
 
-    frequency_counter: i32,
     frame_sequencer_counter: i32,
     frame_sequencer: FrameSequencer,
+
+    // Output sample rate in Hz
+    sample_rate: u32,
+
+    // Fractional position between two output samples, in units of T-cycles * sample rate
+    sample_counter: u32,
+
+    // Running sums used to average every T-cycle of a sample period into one output sample
+    left_sum: f32,
+    right_sum: f32,
+    sum_count: u32,
+
+    // Interleaved stereo samples (left, right) waiting to be drained by the frontend
+    #[serde(skip)]
+    samples: Vec<f32>,
 }
 
 impl Apu {
-    fn new() -> Apu {
+    pub fn new() -> Apu {
         Apu {
             channel_1: Square1::new(),
             channel_2: Square2::new(),
@@ -94,13 +115,54 @@ impl Apu {
             vin_right_enable: false,
             left_volume: 0,
             right_volume: 0,
-            volume: [0.; 4],
+            volume: [1.; 4],
             left_enables: [false; 4],
             right_enables: [false; 4],
-            frequency_counter: 0,
             frame_sequencer_counter: 0,
             frame_sequencer: FrameSequencer::Step0,
+            sample_rate: SAMPLE_RATE,
+            sample_counter: 0,
+            left_sum: 0.,
+            right_sum: 0.,
+            sum_count: 0,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Can be changed every frame, e.g. for dynamic rate control, without resetting the resampler
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate;
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Set the gain (0.0 - 1.0) of a single channel, useful for muting channels from the frontend
+    pub fn set_channel_volume(&mut self, channel: usize, volume: f32) {
+        self.volume[channel] = volume.clamp(0., 1.);
+    }
+
+    /// Number of stereo frames currently buffered
+    pub fn queued_frames(&self) -> usize {
+        self.samples.len() / 2
+    }
+
+    /// Take every sample produced since the last call, interleaved as (left, right) pairs
+    pub fn drain_samples(&mut self) -> Vec<f32> {
+        std::mem::take(&mut self.samples)
+    }
+
+    // Frontends without audio never drain, so the queue is kept to a couple of frames. It's
+    // trimmed once it reaches twice that, to not shift the buffer on every sample
+    fn queue_sample(&mut self, left: f32, right: f32) {
+        let max_len = (self.sample_rate * MAX_QUEUED_VIDEO_FRAMES / 60) as usize * 2;
+        if self.samples.len() >= max_len * 2 {
+            self.samples.drain(..self.samples.len() - max_len);
         }
+
+        self.samples.push(left);
+        self.samples.push(right);
     }
 
     fn clear_regs(&mut self) {
@@ -122,7 +184,14 @@ impl Apu {
         }
     }
 
-    fn tick(&mut self) {
+    pub fn tick(&mut self) {
+        self.mix();
+
+        if !self.enabled {
+            return;
+        }
+
+        self.frame_sequencer_counter -= 1;
         if self.frame_sequencer_counter <= 0 {
             self.frame_sequencer_counter = 8192;
 
@@ -183,35 +252,50 @@ impl Apu {
         self.channel_2.tick();
         self.channel_3.tick();
         self.channel_4.tick();
+    }
+
+    // Mix the current channel outputs into left/right and resample them down to the output rate
+    fn mix(&mut self) {
+        let mut left = 0.;
+        let mut right = 0.;
+
+        for i in 0..4 {
+            let output = match i {
+                0 => self.channel_1.output,
+                1 => self.channel_2.output,
+                2 => self.channel_3.output,
+                3 => self.channel_4.output,
+                _ => 0,
+            };
+            let output = f32::from(output) / 15. * self.volume[i];
+
+            if self.left_enables[i] {
+                left += output;
+            }
 
-        if self.frequency_counter <= 0 {
-            self.frequency_counter = 95;
+            if self.right_enables[i] {
+                right += output;
+            }
+        }
 
-            let mut left = 0;
-            let mut right = 0;
+        self.left_sum += left / 4. * f32::from(self.left_volume + 1) / 8.;
+        self.right_sum += right / 4. * f32::from(self.right_volume + 1) / 8.;
+        self.sum_count += 1;
 
-            let mut output = 0;
+        self.sample_counter += self.sample_rate;
+        if self.sample_counter >= CLOCK_SPEED as u32 {
+            self.sample_counter -= CLOCK_SPEED as u32;
 
-            for i in 0..4 {
-                output = match i {
-                    0 => self.channel_1.output * self.volume[i] as u8,
-                    1 => self.channel_2.output * self.volume[i] as u8,
-                    2 => self.channel_3.output * self.volume[i] as u8,
-                    3 => self.channel_4.output * self.volume[i] as u8,
-                    _ => 0,
-                };
-                if self.left_enables[i] {
-                    left += output;
-                }
+            let count = self.sum_count as f32;
+            self.queue_sample(self.left_sum / count, self.right_sum / count);
 
-                if self.right_enables[i] {
-                    right += output
-                }
-            }
+            self.left_sum = 0.;
+            self.right_sum = 0.;
+            self.sum_count = 0;
         }
     }
 
-    fn read(&self, addr: u16) -> u8 {
+    pub fn read(&self, addr: u16) -> u8 {
         if (0xFF10..=0xFF14).contains(&addr) {
             return self.channel_1.read(addr);
         } else if (0xFF15..=0xFF19).contains(&addr) {
@@ -220,6 +304,8 @@ impl Apu {
             return self.channel_3.read(addr);
         } else if (0xFF1F..=0xFF23).contains(&addr) {
             return self.channel_4.read(addr);
+        } else if (0xFF30..=0xFF3F).contains(&addr) {
+            return self.channel_3.read(addr);
         }
 
         let mut result = 0;
@@ -234,7 +320,7 @@ impl Apu {
             0xFF25 => {
                 for i in 0..4 {
                     result |= (self.right_enables[i] as u8) << i;
-                    result |= (self.left_enables[i] as u8) << i;
+                    result |= (self.left_enables[i] as u8) << (i + 4);
                 }
 
                 result
@@ -253,11 +339,11 @@ impl Apu {
                 }
                 result | 0x70
             }
-            _ => 0,
+            _ => 0xFF,
         }
     }
 
-    fn write(&mut self, addr: u16, value: u8) {
+    pub fn write(&mut self, addr: u16, value: u8) {
         if addr == 0xFF26 {
             let enable = nth_bit!(value, 7) != 0;
 
@@ -265,6 +351,7 @@ impl Apu {
                 self.clear_regs();
             } else if !self.enabled && enable {
                 self.frame_sequencer = int_to_frame_sequencer(0);
+                self.frame_sequencer_counter = 8192;
             }
 
             self.enabled = enable;
@@ -323,3 +410,9 @@ impl Apu {
         }
     }
 }
+
+impl Default for Apu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -2,9 +2,11 @@ use crate::nth_bit;
 
 use super::{envelope::VolumeEnvelope, length_counter::LengthCounter, Channel};
 
+use serde::{Deserialize, Serialize};
+
 const DIVISOR_CODES: [i32; 8] = [8, 16, 32, 48, 64, 80, 96, 112];
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Noise {
     pub length_counter: LengthCounter,
     vol_envelope: VolumeEnvelope,
@@ -60,6 +62,7 @@ impl Noise {
     }
 
     pub fn tick(&mut self) {
+        self.timer -= 1;
         if self.timer <= 0 {
             self.timer = DIVISOR_CODES[self.div_mode as usize] << self.shift;
             let xor_result = (self.lsfr & 0b01) ^ ((self.lsfr & 0b10) >> 1);
@@ -121,6 +124,8 @@ impl Channel for Noise {
     }
 
     fn length_clock(&mut self) {
+        self.length_counter.step();
+
         if self.length_counter.enabled() && self.length_counter.zero() {
             self.channel_enabled = false;
         }
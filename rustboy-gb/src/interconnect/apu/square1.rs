@@ -6,7 +6,9 @@ use super::envelope::VolumeEnvelope;
 use super::length_counter::LengthCounter;
 use super::sweep::FrequencySweep;
 
-#[derive(Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Square1 {
     vol_envelope: VolumeEnvelope,
     freq_sweep: FrequencySweep,
@@ -27,7 +29,7 @@ impl Square1 {
         Square1 {
             vol_envelope: VolumeEnvelope::new(),
             freq_sweep: FrequencySweep::new(),
-            length_counter: LengthCounter::new(),
+            length_counter: lc,
             timer: 0,
             sequence: 0,
             duty: 0,
@@ -49,7 +51,7 @@ impl Square1 {
 
 
     pub fn trigger(&mut self) {
-        self.timer = (2048 * self.freq_sweep.frequency()) * 4;
+        self.timer = (2048 - self.freq_sweep.frequency()) * 4;
 
         self.vol_envelope.trigger();
         self.freq_sweep.trigger();
@@ -62,6 +64,7 @@ impl Square1 {
     }
 
     pub fn tick(&mut self) {
+        self.timer -= 1;
         if self.timer <= 0 {
             self.timer = (2048 - self.freq_sweep.frequency()) * 4;
 
@@ -82,7 +85,7 @@ impl Square1 {
     pub fn sweep_clock(&mut self) {
         self.freq_sweep.step();
 
-        if self.freq_sweep.enabled() {
+        if !self.freq_sweep.enabled() {
             self.channel_enabled = false;
         }
     }
@@ -99,7 +102,7 @@ impl Channel for Square1 {
             0xFF11 => (self.duty << 6) | 0x3F,
             0xFF12 => self.vol_envelope.read_nr2(),
             0xFF13 => 0xFF,
-            0xFF14 => ((self.length_counter.enabled() as u8) >> 6) | 0xBF,
+            0xFF14 => ((self.length_counter.enabled() as u8) << 6) | 0xBF,
             _ => panic!("NOT A CHANNEL 1 REGISTER"),
         }
     }
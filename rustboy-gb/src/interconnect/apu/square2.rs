@@ -2,7 +2,9 @@ use crate::nth_bit;
 
 use super::{envelope::VolumeEnvelope, length_counter::LengthCounter, Channel, DUTY_CYCLES};
 
-#[derive(Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Square2 {
     vol_envelope: VolumeEnvelope,
     pub length_counter: LengthCounter,
@@ -33,6 +35,7 @@ impl Square2 {
     }
 
     pub fn tick(&mut self) {
+        self.timer -= 1;
         if self.timer <= 0 {
             self.timer = (2048 - self.freq) * 4;
 
@@ -44,6 +47,8 @@ impl Square2 {
                 } else {
                     0
                 };
+            } else {
+                self.output = 0;
             }
         }
     }
@@ -117,6 +122,8 @@ impl Channel for Square2 {
     }
 
     fn length_clock(&mut self) {
+        self.length_counter.step();
+
         if self.length_counter.enabled() && self.length_counter.zero() {
             self.channel_enabled = false;
         }
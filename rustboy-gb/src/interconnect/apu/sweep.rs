@@ -1,6 +1,8 @@
 use crate::nth_bit;
 
-#[derive(Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct FrequencySweep {
     freq: i32,
     shadow_freq: i32,
@@ -102,6 +104,7 @@ impl FrequencySweep {
             return;
         }
 
+        self.timer -= 1;
         if self.timer <= 0 {
             self.timer = if self.period != 0 {
                 self.period as i32
@@ -2,7 +2,9 @@ use crate::nth_bit;
 
 use super::{length_counter::LengthCounter, Channel};
 
-#[derive(Clone, Copy)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Wave {
     dac_enabled: bool,
     channel_enabled: bool,
@@ -23,7 +25,7 @@ pub struct Wave {
 impl Wave {
     pub fn new() -> Wave {
         let mut lc = LengthCounter::new();
-        lc.set_full_length(64);
+        lc.set_full_length(256);
         Wave {
             timer: 0,
             position: 4,
@@ -77,6 +79,7 @@ impl Wave {
     pub fn tick(&mut self) {
         self.ticks_since_read += 1;
 
+        self.timer -= 1;
         if self.timer <= 0 {
             self.timer = (2048 - self.frequency) << 1;
 
@@ -169,6 +172,8 @@ impl Channel for Wave {
     }
 
     fn length_clock(&mut self) {
+        self.length_counter.step();
+
         if self.length_counter.enabled() && self.length_counter.zero() {
             self.channel_enabled = false;
         }
@@ -0,0 +1,185 @@
+//! Game Boy Camera (Pocket Camera)
+//!
+//! Behaves like an MBC with 128 KiB of RAM, except RAM bank 0x10 maps the registers of the
+//! camera at 0xA000 - 0xA035 (mirrored up to 0xBFFF). Writing 1 to bit 0 of 0xA000 takes a
+//! photo: the bit reads 1 until the sensor is done, then the picture is in RAM bank 0 at
+//! 0xA100 - 0xAEFF as 16 x 14 tiles. What the sensor sees comes from an `ImageSource`.
+mod sensor;
+mod source;
+
+pub use source::{open_image_source, FrameDirectory, ImageSource, StillImage, TestPattern};
+
+use sensor::{capture, capture_cycles, IMAGE_SIZE};
+
+use crate::constants::{RAM_BANK_SIZE, ROM_BANK_SIZE};
+
+use serde::{Deserialize, Serialize};
+use serde_big_array::BigArray;
+
+/// Part of the sensor that ends up in a photo
+pub const SENSOR_WIDTH: usize = 128;
+pub const SENSOR_HEIGHT: usize = 112;
+
+// Capture control, gain and edge mode, exposure, edge ratio and invert, offset, 48 thresholds
+const REGISTER_COUNT: usize = 0x36;
+
+// RAM bank number mapping the registers instead
+const REGISTER_BANK: usize = 0x10;
+
+// Where photos are written in RAM bank 0
+const IMAGE_ADDRESS: usize = 0x100;
+
+fn test_pattern() -> Box<dyn ImageSource> {
+    Box::new(TestPattern)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CameraState {
+    pub rom: Vec<u8>,
+    pub ram: Vec<u8>,
+    rom_bank_number: usize,
+    ram_bank_number: usize,
+    ram_enabled: bool,
+
+    #[serde(with = "BigArray")]
+    registers: [u8; REGISTER_COUNT],
+
+    // T-cycles until the photo being taken is done, 0 when idle
+    capture_cycles: u32,
+
+    // Not part of save states, has to be set again after loading one
+    #[serde(skip, default = "test_pattern")]
+    source: Box<dyn ImageSource>,
+}
+
+impl CameraState {
+    pub fn new(rom: &[u8], ram: &[u8]) -> CameraState {
+        CameraState {
+            rom: rom.to_vec(),
+            ram: ram.to_vec(),
+            rom_bank_number: 1,
+            ram_bank_number: 0,
+            ram_enabled: false,
+            registers: [0; REGISTER_COUNT],
+            capture_cycles: 0,
+            source: test_pattern(),
+        }
+    }
+
+    /// Point the camera at something else
+    pub fn set_image_source(&mut self, source: Box<dyn ImageSource>) {
+        self.source = source;
+    }
+
+    pub fn tick(&mut self, t_cycles: u32) {
+        if self.capture_cycles == 0 {
+            return;
+        }
+
+        self.capture_cycles = self.capture_cycles.saturating_sub(t_cycles);
+        if self.capture_cycles == 0 {
+            self.finish_capture();
+        }
+    }
+
+    fn finish_capture(&mut self) {
+        let image = capture(&self.source.frame(), &self.registers);
+        if let Some(ram) = self.ram.get_mut(IMAGE_ADDRESS..IMAGE_ADDRESS + IMAGE_SIZE) {
+            ram.copy_from_slice(&image);
+        }
+
+        self.registers[0] &= !0x01;
+    }
+
+    fn capturing(&self) -> bool {
+        self.capture_cycles > 0
+    }
+
+    fn ram_address(&self, addr: u16) -> usize {
+        (RAM_BANK_SIZE * self.ram_bank_number + (addr & 0x1FFF) as usize) & (self.ram.len() - 1)
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x3FFF => self.rom[addr as usize],
+
+            0x4000..=0x7FFF => {
+                let new_addr = ROM_BANK_SIZE * self.rom_bank_number + (addr & 0x3FFF) as usize;
+                let new_addr = new_addr & (self.rom.len() - 1);
+                self.rom[new_addr]
+            }
+
+            // Only the capture control register can be read back
+            0xA000..=0xBFFF if self.ram_bank_number == REGISTER_BANK => {
+                if addr & 0x7F == 0 {
+                    self.registers[0] & 0x07
+                } else {
+                    0x00
+                }
+            }
+
+            // RAM is readable without being enabled, but not while the sensor writes to it
+            0xA000..=0xBFFF => {
+                if self.ram.is_empty() || self.capturing() {
+                    return 0x00;
+                }
+                self.ram[self.ram_address(addr)]
+            }
+
+            _ => 0xFF,
+        }
+    }
+
+    pub fn ram_writable(&self) -> bool {
+        self.ram_enabled && self.ram_bank_number != REGISTER_BANK
+            && !self.ram.is_empty()
+            && !self.capturing()
+    }
+
+    pub fn write(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x0000..=0x1FFF => {
+                self.ram_enabled = (value & 0x0F) == 0x0A;
+            }
+
+            0x2000..=0x3FFF => {
+                self.rom_bank_number = (value & 0x3F) as usize;
+            }
+
+            0x4000..=0x5FFF => {
+                self.ram_bank_number = if value & 0x10 != 0 {
+                    REGISTER_BANK
+                } else {
+                    (value & 0x0F) as usize
+                };
+            }
+
+            0xA000..=0xBFFF if self.ram_bank_number == REGISTER_BANK => {
+                let register = (addr & 0x7F) as usize;
+
+                match register {
+                    0 => {
+                        if value & 0x01 != 0 && !self.capturing() {
+                            self.capture_cycles = capture_cycles(&self.registers);
+                        }
+
+                        // Bit 0 reads 1 for as long as the capture runs
+                        self.registers[0] = (value & 0x06) | u8::from(self.capturing());
+                    }
+                    1..=0x35 => self.registers[register] = value,
+                    _ => (),
+                }
+            }
+
+            0xA000..=0xBFFF => {
+                if self.ram.is_empty() || !self.ram_enabled || self.capturing() {
+                    return;
+                }
+                let ram_addr = self.ram_address(addr);
+                self.ram[ram_addr] = value;
+            }
+
+            _ => (),
+        }
+    }
+}
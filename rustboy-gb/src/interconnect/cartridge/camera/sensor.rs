@@ -0,0 +1,135 @@
+//! Mitsubishi M64282FP ("artificial retina") model
+//!
+//! Every pixel is turned into a voltage by the exposure time and gain, then optionally edge
+//! enhanced against its neighbours, inverted, and finally compared against the 3 thresholds of
+//! its place in the 4x4 dithering matrix to get a shade. The output offset (O) and reference
+//! voltage (V) registers only shift the voltage range the cart's thresholds are picked for, they
+//! aren't modelled.
+use super::{REGISTER_COUNT, SENSOR_HEIGHT, SENSOR_WIDTH};
+
+// Register indices
+const GAIN: usize = 0x01;
+const EXPOSURE_HIGH: usize = 0x02;
+const EXPOSURE_LOW: usize = 0x03;
+const EDGE: usize = 0x04;
+const MATRIX: usize = 0x06;
+
+// Exposure that passes the brightness through as it is, in 16 µs steps
+const EXPOSURE_REFERENCE: f32 = 0x1000 as f32;
+
+// Gain steps are 1.5 dB
+const GAIN_STEP_DB: f32 = 1.5;
+
+// Edge enhancement ratio picked by bits 4-6 of register 4
+const EDGE_RATIOS: [f32; 8] = [0.5, 0.75, 1.0, 1.25, 2.0, 3.0, 4.0, 5.0];
+
+// Register 4 bit 3 inverts the output
+const INVERT: u8 = 0x08;
+
+// Size of a photo in cart RAM, 16 x 14 tiles
+pub const IMAGE_SIZE: usize = SENSOR_WIDTH / 8 * SENSOR_HEIGHT / 8 * 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EdgeMode {
+    None,
+    Vertical,
+    Horizontal,
+    Both,
+}
+
+/// Take a photo of `frame` (brightness per pixel, see `ImageSource`), returns it as 2bpp tiles
+/// the way the cart writes them to RAM
+pub fn capture(frame: &[u8], registers: &[u8; REGISTER_COUNT]) -> Vec<u8> {
+    let voltages = expose(frame, registers);
+    let voltages = enhance_edges(&voltages, registers);
+
+    let mut tiles = vec![0; IMAGE_SIZE];
+    let invert = registers[EDGE] & INVERT != 0;
+
+    for y in 0..SENSOR_HEIGHT {
+        for x in 0..SENSOR_WIDTH {
+            let mut voltage = voltages[y * SENSOR_WIDTH + x];
+            if invert {
+                voltage = 255.0 - voltage;
+            }
+
+            let shade = dither(voltage, x, y, registers);
+
+            let tile = (y / 8) * (SENSOR_WIDTH / 8) + x / 8;
+            let row = tile * 16 + (y % 8) * 2;
+            let bit = 7 - (x % 8);
+            tiles[row] |= (shade & 1) << bit;
+            tiles[row + 1] |= (shade >> 1) << bit;
+        }
+    }
+
+    tiles
+}
+
+/// Cycles a capture takes, in T-cycles
+pub fn capture_cycles(registers: &[u8; REGISTER_COUNT]) -> u32 {
+    // Leaving N off adds a bit of processing
+    let n = if registers[GAIN] & 0x80 != 0 { 0 } else { 512 };
+    4 * (32446 + n + 16 * u32::from(exposure(registers)))
+}
+
+fn exposure(registers: &[u8; REGISTER_COUNT]) -> u16 {
+    u16::from_be_bytes([registers[EXPOSURE_HIGH], registers[EXPOSURE_LOW]])
+}
+
+fn expose(frame: &[u8], registers: &[u8; REGISTER_COUNT]) -> Vec<f32> {
+    let gain = 10f32.powf(f32::from(registers[GAIN] & 0x1F) * GAIN_STEP_DB / 20.0);
+    let scale = gain * f32::from(exposure(registers)) / EXPOSURE_REFERENCE;
+
+    frame
+        .iter()
+        .map(|&brightness| (f32::from(brightness) * scale).min(255.0))
+        .collect()
+}
+
+fn enhance_edges(voltages: &[f32], registers: &[u8; REGISTER_COUNT]) -> Vec<f32> {
+    let mode = match (registers[GAIN] >> 5) & 0b11 {
+        0b00 => EdgeMode::None,
+        0b01 => EdgeMode::Vertical,
+        0b10 => EdgeMode::Horizontal,
+        _ => EdgeMode::Both,
+    };
+    if mode == EdgeMode::None {
+        return voltages.to_vec();
+    }
+
+    let ratio = EDGE_RATIOS[usize::from((registers[EDGE] >> 4) & 0x07)];
+
+    // Pixels past the border are the border itself
+    let at = |x: usize, y: usize| voltages[y * SENSOR_WIDTH + x];
+    let mut enhanced = Vec::with_capacity(voltages.len());
+
+    for y in 0..SENSOR_HEIGHT {
+        for x in 0..SENSOR_WIDTH {
+            let center = at(x, y);
+            let horizontal =
+                2.0 * center - at(x.saturating_sub(1), y) - at((x + 1).min(SENSOR_WIDTH - 1), y);
+            let vertical =
+                2.0 * center - at(x, y.saturating_sub(1)) - at(x, (y + 1).min(SENSOR_HEIGHT - 1));
+
+            let edge = match mode {
+                EdgeMode::Vertical => vertical,
+                EdgeMode::Horizontal => horizontal,
+                _ => horizontal + vertical,
+            };
+            enhanced.push((center + edge * ratio).clamp(0.0, 255.0));
+        }
+    }
+
+    enhanced
+}
+
+// Below the first threshold is black, above the last one white
+fn dither(voltage: f32, x: usize, y: usize, registers: &[u8; REGISTER_COUNT]) -> u8 {
+    let thresholds = &registers[MATRIX + ((y % 4) * 4 + x % 4) * 3..][..3];
+
+    thresholds
+        .iter()
+        .position(|&threshold| voltage < f32::from(threshold))
+        .map_or(0, |i| 3 - i as u8)
+}
@@ -0,0 +1,131 @@
+use std::fmt::Debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Result};
+use log::error;
+
+use super::{SENSOR_HEIGHT, SENSOR_WIDTH};
+use crate::screenshot::Image;
+
+/// Whatever the camera is pointed at
+pub trait ImageSource: Debug + Send {
+    /// Brightness of the `SENSOR_WIDTH` x `SENSOR_HEIGHT` pixels the sensor sees, row by row,
+    /// from 0 (black) to 255 (white). Called once per photo
+    fn frame(&mut self) -> Vec<u8>;
+}
+
+/// A PNG, or a directory of them taken in turn
+pub fn open_image_source(path: &Path) -> Result<Box<dyn ImageSource>> {
+    if path.is_dir() {
+        Ok(Box::new(FrameDirectory::open(path)?))
+    } else {
+        Ok(Box::new(StillImage::open(path)?))
+    }
+}
+
+/// Something to look at without an image: a gradient from black on the left to white on the
+/// right, with a black square in the middle
+#[derive(Debug, Default)]
+pub struct TestPattern;
+
+impl ImageSource for TestPattern {
+    fn frame(&mut self) -> Vec<u8> {
+        let square_x = SENSOR_WIDTH / 2 - 16..SENSOR_WIDTH / 2 + 16;
+        let square_y = SENSOR_HEIGHT / 2 - 16..SENSOR_HEIGHT / 2 + 16;
+
+        (0..SENSOR_HEIGHT)
+            .flat_map(|y| (0..SENSOR_WIDTH).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                if square_x.contains(&x) && square_y.contains(&y) {
+                    0
+                } else {
+                    (x * 255 / (SENSOR_WIDTH - 1)) as u8
+                }
+            })
+            .collect()
+    }
+}
+
+/// The same picture for every photo
+#[derive(Debug, Clone)]
+pub struct StillImage {
+    pixels: Vec<u8>,
+}
+
+impl StillImage {
+    /// Picture of any size, stretched over the sensor
+    pub fn new(image: &Image) -> StillImage {
+        StillImage {
+            pixels: sensor_pixels(image),
+        }
+    }
+
+    pub fn open(path: &Path) -> Result<StillImage> {
+        Ok(StillImage::new(&Image::load_png(path)?))
+    }
+}
+
+impl ImageSource for StillImage {
+    fn frame(&mut self) -> Vec<u8> {
+        self.pixels.clone()
+    }
+}
+
+/// The PNGs of a directory in name order, one per photo, starting over after the last one
+#[derive(Debug)]
+pub struct FrameDirectory {
+    frames: Vec<PathBuf>,
+    next: usize,
+}
+
+impl FrameDirectory {
+    pub fn open(dir: &Path) -> Result<FrameDirectory> {
+        let mut frames = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "png"))
+            .collect::<Vec<PathBuf>>();
+        frames.sort();
+
+        if frames.is_empty() {
+            bail!("NO PNG FRAMES IN {}", dir.display());
+        }
+
+        Ok(FrameDirectory { frames, next: 0 })
+    }
+}
+
+impl ImageSource for FrameDirectory {
+    fn frame(&mut self) -> Vec<u8> {
+        let path = &self.frames[self.next];
+        self.next = (self.next + 1) % self.frames.len();
+
+        match Image::load_png(path) {
+            Ok(image) => sensor_pixels(&image),
+            Err(e) => {
+                error!("COULD NOT LOAD CAMERA FRAME {}: {}", path.display(), e);
+                vec![0; SENSOR_WIDTH * SENSOR_HEIGHT]
+            }
+        }
+    }
+}
+
+/// Brightness of a picture stretched over the sensor, nearest neighbour
+fn sensor_pixels(image: &Image) -> Vec<u8> {
+    let width = image.width as usize;
+    let height = image.height as usize;
+
+    if width == 0 || height == 0 {
+        return vec![0; SENSOR_WIDTH * SENSOR_HEIGHT];
+    }
+
+    (0..SENSOR_HEIGHT)
+        .flat_map(|y| (0..SENSOR_WIDTH).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let (r, g, b) = image.pixels
+                [(y * height / SENSOR_HEIGHT) * width + x * width / SENSOR_WIDTH]
+                .get_rgb();
+            ((u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1000) as u8
+        })
+        .collect()
+}
@@ -1,38 +1,134 @@
 use core::fmt;
 use serde::{Serialize, Deserialize};
 
-fn rom_size_as_str(value: u8) -> &'static str {
+// The header ends right before the entry point of the game at 0x150
+pub const HEADER_END: usize = 0x150;
+
+/// Size of the DMG boot rom mapped over 0x0000 - 0x00FF
+pub const BOOT_ROM_SIZE: usize = 0x100;
+
+/// Why a rom could not be loaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadError {
+    /// The rom is shorter than its header or than the size the header declares
+    TruncatedRom { expected: usize, actual: usize },
+
+    /// The cartridge type at 0x147 isn't emulated
+    UnsupportedCartridgeType(u8),
+
+    /// The rom size code at 0x148 isn't valid
+    InvalidRomSize(u8),
+
+    /// The ram size code at 0x149 isn't valid
+    InvalidRamSize(u8),
+
+    /// The header checksum at 0x14D doesn't match, only with `ChecksumPolicy::Reject`
+    BadHeaderChecksum { expected: u8, actual: u8 },
+
+    /// The boot rom isn't 256 bytes long
+    InvalidBootRomSize(usize),
+
+    /// A battery save doesn't match the size of the cartridge RAM
+    InvalidSaveSize { expected: usize, actual: usize },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::TruncatedRom { expected, actual } => write!(
+                f,
+                "ROM IS TRUNCATED: EXPECTED {:#X} BYTES, GOT {:#X}",
+                expected, actual
+            ),
+            LoadError::UnsupportedCartridgeType(value) => {
+                write!(f, "CARTYPE TYPE NOT IMPLEMENTED: {:#X}", value)
+            }
+            LoadError::InvalidRomSize(value) => write!(f, "NOT A ROM SIZE: {:#X}", value),
+            LoadError::InvalidRamSize(value) => write!(f, "NOT A RAM SIZE: {:#X}", value),
+            LoadError::BadHeaderChecksum { expected, actual } => write!(
+                f,
+                "BAD HEADER CHECKSUM: EXPECTED {:#X}, GOT {:#X}",
+                expected, actual
+            ),
+            LoadError::InvalidBootRomSize(size) => write!(
+                f,
+                "BOOT ROM MUST BE {:#X} BYTES, GOT {:#X}",
+                BOOT_ROM_SIZE, size
+            ),
+            LoadError::InvalidSaveSize { expected, actual } => write!(
+                f,
+                "SAVE MUST BE {:#X} BYTES, GOT {:#X}",
+                expected, actual
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// What to do with a rom whose header checksum doesn't match
+///
+/// Real hardware refuses to boot such a rom, but plenty of homebrew and test roms don't bother
+/// setting it, so by default it's only logged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChecksumPolicy {
+    Ignore,
+    #[default]
+    Warn,
+    Reject,
+}
+
+pub fn rom_size_as_str(value: u8) -> Result<&'static str, LoadError> {
+    match value {
+        0x00 => Ok("32 KiB"),
+        0x01 => Ok("64 KiB"),
+        0x02 => Ok("128 KiB"),
+        0x03 => Ok("256 KiB"),
+        0x04 => Ok("512 KiB"),
+        0x05 => Ok("1 MiB"),
+        0x06 => Ok("2 MiB"),
+        0x07 => Ok("4 MiB"),
+        0x08 => Ok("8 MiB"),
+        0x52 => Ok("1.1 MiB"),
+        0x53 => Ok("1.2 MiB"),
+        0x54 => Ok("1.5 MiB"),
+        _ => Err(LoadError::InvalidRomSize(value)),
+    }
+}
+
+/// Size in bytes of the rom described by the rom size code
+pub fn rom_size(value: u8) -> Result<usize, LoadError> {
     match value {
-        0x00 => "32 KiB",
-        0x01 => "64 KiB",
-        0x02 => "128 KiB",
-        0x03 => "256 KiB",
-        0x04 => "512 KiB",
-        0x05 => "1 MiB",
-        0x06 => "2 MiB",
-        0x07 => "4 MiB",
-        0x08 => "8 MiB",
-        0x52 => "1.1 MiB",
-        0x53 => "1.2 MiB",
-        0x54 => "1.5 MiB",
-        _ => panic!("NOT A  ROM SIZE"),
+        0x00..=0x08 => Ok(0x8000 << value),
+        0x52 => Ok(0x12_0000),
+        0x53 => Ok(0x14_0000),
+        0x54 => Ok(0x18_0000),
+        _ => Err(LoadError::InvalidRomSize(value)),
     }
 }
 
-pub fn ram_size(value: u8) -> usize {
+pub fn ram_size(value: u8) -> Result<usize, LoadError> {
     match value {
-        0x00 => 0,
-        0x01 => 0,
-        0x02 => 0x2000,
-        0x03 => 0x8000,
-        0x04 => 0x20000,
-        0x05 => 0x10000,
-        _ => panic!("NOT A RAM SIZE"),
+        0x00 => Ok(0),
+        0x01 => Ok(0),
+        0x02 => Ok(0x2000),
+        0x03 => Ok(0x8000),
+        0x04 => Ok(0x20000),
+        0x05 => Ok(0x10000),
+        _ => Err(LoadError::InvalidRamSize(value)),
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Header checksum over 0x134 - 0x14C, as computed by the boot rom
+pub fn header_checksum(rom: &[u8]) -> u8 {
+    rom[0x134..=0x14C].iter().fold(0u8, |check_sum, &byte| {
+        check_sum.wrapping_sub(byte).wrapping_sub(1)
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
 pub enum CartridgeType {
+    #[default]
     ROMOnly,
     MBC1,
     MBC1RAM,
@@ -55,33 +151,66 @@ pub enum CartridgeType {
     MBC5Rumble,
     MBC5RumbleRAM,
     MBC5RumbleRAMBattery,
+    MBC7SensorRumbleRAMBattery,
+    PocketCamera,
+    HuC3,
+    HuC1RAMBattery,
+}
+
+impl CartridgeType {
+    /// True for cartridges whose RAM is kept alive by a battery and should be saved to disk
+    pub fn has_battery(&self) -> bool {
+        matches!(
+            self,
+            CartridgeType::MBC1RAMBattery
+                | CartridgeType::MBC2Battery
+                | CartridgeType::ROMRAMBattery1
+                | CartridgeType::MMM01RAMBattery
+                | CartridgeType::MBC3TimerBattery
+                | CartridgeType::MBC3TimerRAMBattery
+                | CartridgeType::MBC3RAMBattery
+                | CartridgeType::MBC5RAMBattery
+                | CartridgeType::MBC5RumbleRAMBattery
+                | CartridgeType::MBC7SensorRumbleRAMBattery
+                | CartridgeType::PocketCamera
+                | CartridgeType::HuC3
+                | CartridgeType::HuC1RAMBattery
+        )
+    }
 }
 
 impl std::fmt::Display for CartridgeType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self)
+        write!(f, "{:?}", self)
     }
 }
 
-pub fn u8_to_cart_type(value: u8) -> CartridgeType {
+pub fn u8_to_cart_type(value: u8) -> Result<CartridgeType, LoadError> {
     match value {
-        0x00 => CartridgeType::ROMOnly,
-        0x01 => CartridgeType::MBC1,
-        0x02 => CartridgeType::MBC1RAM,
-        0x03 => CartridgeType::MBC1RAMBattery,
-        0x05 => CartridgeType::MBC2,
-        0x06 => CartridgeType::MBC2Battery,
-        0x0F => CartridgeType::MBC3TimerBattery,
-        0x10 => CartridgeType::MBC3TimerRAMBattery,
-        0x11 => CartridgeType::MBC3,
-        0x12 => CartridgeType::MBC3RAM,
-        0x13 => CartridgeType::MBC3RAMBattery,
-        0x19 => CartridgeType::MBC5,
-        0x1A => CartridgeType::MBC5RAM,
-        0x1B => CartridgeType::MBC5RAMBattery,
-        0x1C => CartridgeType::MBC5Rumble,
-        0x1D => CartridgeType::MBC5RumbleRAM,
-        0x1E => CartridgeType::MBC5RumbleRAMBattery,
-        _ => panic!("CARTYPE TYPE NOT IMPLEMENTED: {:#X}", value),
+        0x00 => Ok(CartridgeType::ROMOnly),
+        0x01 => Ok(CartridgeType::MBC1),
+        0x02 => Ok(CartridgeType::MBC1RAM),
+        0x03 => Ok(CartridgeType::MBC1RAMBattery),
+        0x05 => Ok(CartridgeType::MBC2),
+        0x06 => Ok(CartridgeType::MBC2Battery),
+        0x0B => Ok(CartridgeType::MMM01),
+        0x0C => Ok(CartridgeType::MMM01RAM),
+        0x0D => Ok(CartridgeType::MMM01RAMBattery),
+        0x0F => Ok(CartridgeType::MBC3TimerBattery),
+        0x10 => Ok(CartridgeType::MBC3TimerRAMBattery),
+        0x11 => Ok(CartridgeType::MBC3),
+        0x12 => Ok(CartridgeType::MBC3RAM),
+        0x13 => Ok(CartridgeType::MBC3RAMBattery),
+        0x19 => Ok(CartridgeType::MBC5),
+        0x1A => Ok(CartridgeType::MBC5RAM),
+        0x1B => Ok(CartridgeType::MBC5RAMBattery),
+        0x1C => Ok(CartridgeType::MBC5Rumble),
+        0x1D => Ok(CartridgeType::MBC5RumbleRAM),
+        0x1E => Ok(CartridgeType::MBC5RumbleRAMBattery),
+        0x22 => Ok(CartridgeType::MBC7SensorRumbleRAMBattery),
+        0xFC => Ok(CartridgeType::PocketCamera),
+        0xFE => Ok(CartridgeType::HuC3),
+        0xFF => Ok(CartridgeType::HuC1RAMBattery),
+        _ => Err(LoadError::UnsupportedCartridgeType(value)),
     }
 }
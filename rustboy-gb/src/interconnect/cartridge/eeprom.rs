@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+/// Size of the 93LC56, 128 words of 16 bits
+pub const EEPROM_SIZE: usize = 0x100;
+
+// Bits of the pins register
+const CS: u8 = 0x80;
+const CLK: u8 = 0x40;
+const DI: u8 = 0x02;
+const DO: u8 = 0x01;
+
+// Opcode and address bits following the start bit
+const COMMAND_BITS: u8 = 10;
+const WORD_BITS: u8 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum State {
+    // Waiting for the start bit
+    Idle,
+    Command {
+        value: u16,
+        bits: u8,
+    },
+    Read {
+        addr: usize,
+        bits: u8,
+    },
+    Write {
+        addr: Option<usize>,
+        value: u16,
+        bits: u8,
+    },
+    // Command done, nothing happens until CS goes low
+    Done,
+}
+
+/// 93LC56 serial EEPROM of MBC7 cartridges
+///
+/// The game bit bangs the pins: while CS is high, DI is sampled on every rising edge of CLK.
+/// A command is a start bit, a 2 bit opcode and an 8 bit word address (the top bit unused),
+/// write commands are followed by 16 data bits. Data goes in and out MSB first, words are
+/// stored little endian in `data`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Eeprom {
+    pub data: Vec<u8>,
+    cs: bool,
+    clk: bool,
+    di: bool,
+
+    // Output pin, high when ready
+    do_: bool,
+
+    // Set by EWEN, everything but reads is ignored until then
+    write_enabled: bool,
+    state: State,
+}
+
+impl Eeprom {
+    pub fn new() -> Eeprom {
+        Eeprom {
+            // Erased EEPROM reads all 1s
+            data: vec![0xFF; EEPROM_SIZE],
+            cs: false,
+            clk: false,
+            di: false,
+            do_: true,
+            write_enabled: false,
+            state: State::Idle,
+        }
+    }
+
+    pub fn read(&self) -> u8 {
+        let mut value = 0;
+        if self.cs {
+            value |= CS;
+        }
+        if self.clk {
+            value |= CLK;
+        }
+        if self.di {
+            value |= DI;
+        }
+        if self.do_ {
+            value |= DO;
+        }
+
+        value
+    }
+
+    pub fn write(&mut self, value: u8) {
+        let cs = value & CS != 0;
+        let clk = value & CLK != 0;
+        self.di = value & DI != 0;
+
+        if !cs {
+            self.state = State::Idle;
+        } else if clk && !self.clk {
+            self.clock();
+        }
+
+        self.cs = cs;
+        self.clk = clk;
+    }
+
+    fn word(&self, addr: usize) -> u16 {
+        u16::from_le_bytes([self.data[addr * 2], self.data[addr * 2 + 1]])
+    }
+
+    fn set_word(&mut self, addr: usize, value: u16) {
+        if self.write_enabled {
+            self.data[addr * 2..addr * 2 + 2].copy_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    // Rising edge of CLK
+    fn clock(&mut self) {
+        let bit = u16::from(self.di);
+        let words = self.data.len() / 2;
+
+        self.state = match self.state {
+            State::Idle if self.di => State::Command { value: 0, bits: 0 },
+            State::Idle => State::Idle,
+            State::Command { value, bits } if bits + 1 < COMMAND_BITS => State::Command {
+                value: (value << 1) | bit,
+                bits: bits + 1,
+            },
+            State::Command { value, .. } => {
+                let value = (value << 1) | bit;
+                self.command(value >> 8, value & 0xFF)
+            }
+            State::Read { addr, bits } => {
+                self.do_ = self.word(addr) & (0x8000 >> bits) != 0;
+
+                // Reading on moves to the next word
+                if bits + 1 < WORD_BITS {
+                    State::Read {
+                        addr,
+                        bits: bits + 1,
+                    }
+                } else {
+                    State::Read {
+                        addr: (addr + 1) % words,
+                        bits: 0,
+                    }
+                }
+            }
+            State::Write { addr, value, bits } if bits + 1 < WORD_BITS => State::Write {
+                addr,
+                value: (value << 1) | bit,
+                bits: bits + 1,
+            },
+            State::Write { addr, value, .. } => {
+                let value = (value << 1) | bit;
+                match addr {
+                    Some(addr) => self.set_word(addr, value),
+                    None => (0..words).for_each(|addr| self.set_word(addr, value)),
+                }
+
+                self.do_ = true;
+                State::Done
+            }
+            State::Done => State::Done,
+        };
+    }
+
+    fn command(&mut self, opcode: u16, addr_bits: u16) -> State {
+        let words = self.data.len() / 2;
+        let addr = usize::from(addr_bits) % words;
+        self.do_ = true;
+
+        match (opcode, addr_bits >> 6) {
+            // READ, a dummy 0 comes out first
+            (0b10, _) => {
+                self.do_ = false;
+                State::Read { addr, bits: 0 }
+            }
+            // WRITE
+            (0b01, _) => State::Write {
+                addr: Some(addr),
+                value: 0,
+                bits: 0,
+            },
+            // ERASE
+            (0b11, _) => {
+                self.set_word(addr, 0xFFFF);
+                State::Done
+            }
+            // WRAL
+            (_, 0b01) => State::Write {
+                addr: None,
+                value: 0,
+                bits: 0,
+            },
+            // ERAL
+            (_, 0b10) => {
+                (0..words).for_each(|addr| self.set_word(addr, 0xFFFF));
+                State::Done
+            }
+            // EWEN and EWDS
+            (_, mode) => {
+                self.write_enabled = mode == 0b11;
+                State::Done
+            }
+        }
+    }
+}
+
+impl Default for Eeprom {
+    fn default() -> Self {
+        Eeprom::new()
+    }
+}
@@ -0,0 +1,498 @@
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+use super::cartridge_info::{
+    header_checksum, ram_size, rom_size, u8_to_cart_type, CartridgeType, LoadError, HEADER_END,
+};
+
+/// Logo every licensed cartridge carries at 0x104 - 0x133, checked by the boot rom
+pub const NINTENDO_LOGO: [u8; 48] = [
+    0xCE, 0xED, 0x66, 0x66, 0xCC, 0x0D, 0x00, 0x0B, 0x03, 0x73, 0x00, 0x83, 0x00, 0x0C, 0x00, 0x0D,
+    0x00, 0x08, 0x11, 0x1F, 0x88, 0x89, 0x00, 0x0E, 0xDC, 0xCC, 0x6E, 0xE6, 0xDD, 0xDD, 0xD9, 0x99,
+    0xBB, 0xBB, 0x67, 0x63, 0x6E, 0x0E, 0xEC, 0xCC, 0xDD, 0xDC, 0x99, 0x9F, 0xBB, 0xB9, 0x33, 0x3E,
+];
+
+// Old licensee code telling that the new licensee code at 0x144 - 0x145 is used instead
+const USE_NEW_LICENSEE: u8 = 0x33;
+
+// SGB flag value for games supporting SGB functions
+const SGB_SUPPORTED: u8 = 0x03;
+
+// MMM01 carts boot into a menu in their last 32 KiB
+const MMM01_MENU_SIZE: usize = 0x8000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CgbFlag {
+    // Plain DMG game
+    #[default]
+    Dmg,
+
+    // Works on both DMG and CGB, with CGB enhancements
+    Supported,
+
+    // Only runs on a CGB
+    Only,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Destination {
+    #[default]
+    Japan,
+    Overseas,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Licensee {
+    // One byte code at 0x14B
+    Old(u8),
+
+    // Two ASCII characters at 0x144 - 0x145, used when the old code is 0x33
+    New([u8; 2]),
+}
+
+impl Default for Licensee {
+    fn default() -> Self {
+        Licensee::Old(0x00)
+    }
+}
+
+impl Licensee {
+    pub fn code(&self) -> String {
+        match self {
+            Licensee::Old(code) => format!("{:02X}", code),
+            Licensee::New(code) => String::from_utf8_lossy(code).to_string(),
+        }
+    }
+
+    pub fn publisher(&self) -> &'static str {
+        match self {
+            Licensee::Old(code) => old_licensee_publisher(*code),
+            Licensee::New(code) => new_licensee_publisher(code),
+        }
+    }
+}
+
+/// Everything in the cartridge header at 0x100 - 0x14F
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct CartridgeHeader {
+    pub valid_logo: bool,
+    pub title: String,
+
+    // 4 character code at 0x13F - 0x142, only on newer (CGB era) cartridges
+    pub manufacturer_code: Option<String>,
+
+    pub cgb_flag: CgbFlag,
+    pub sgb_flag: bool,
+    pub licensee: Licensee,
+    pub cartridge_type: CartridgeType,
+
+    // Sizes in bytes
+    pub rom_size: usize,
+    pub ram_size: usize,
+
+    // Size of the rom file the header was parsed from
+    pub file_size: usize,
+
+    pub destination: Destination,
+    pub mask_rom_version: u8,
+
+    pub header_checksum: u8,
+    pub valid_header_checksum: bool,
+
+    // Checksum of the header bytes that were parsed, what `header_checksum` should have been
+    pub computed_header_checksum: u8,
+    pub global_checksum: u16,
+    pub valid_global_checksum: bool,
+}
+
+impl CartridgeHeader {
+    /// Parse the header of a rom, failing if the rom is shorter than the header or than the rom
+    /// size it declares, or if the cartridge type or size codes aren't known
+    pub fn parse(rom: &[u8]) -> Result<CartridgeHeader, LoadError> {
+        if rom.len() < HEADER_END {
+            return Err(LoadError::TruncatedRom {
+                expected: HEADER_END,
+                actual: rom.len(),
+            });
+        }
+
+        // MMM01 carts start in the menu at the end of the rom, its header is the one that counts
+        let header = match mmm01_menu(rom) {
+            Some(menu) => menu,
+            None => rom,
+        };
+
+        let cartridge_type = u8_to_cart_type(header[0x147])?;
+        let rom_s = rom_size(header[0x148])?;
+        let ram_s = ram_size(header[0x149])?;
+
+        if rom.len() < rom_s {
+            return Err(LoadError::TruncatedRom {
+                expected: rom_s,
+                actual: rom.len(),
+            });
+        }
+
+        let cgb_flag = match header[0x143] {
+            0xC0 => CgbFlag::Only,
+            flag if flag & 0x80 != 0 => CgbFlag::Supported,
+            _ => CgbFlag::Dmg,
+        };
+
+        // CGB games end the title at 0x142 and may store a manufacturer code in its last 4 bytes,
+        // older games use the full 16 bytes up to 0x143 for the title
+        let manufacturer = &header[0x13F..0x143];
+        let (title, manufacturer_code) = if cgb_flag == CgbFlag::Dmg {
+            (parse_title(&header[0x134..0x144]), None)
+        } else if manufacturer
+            .iter()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        {
+            (
+                parse_title(&header[0x134..0x13F]),
+                Some(String::from_utf8_lossy(manufacturer).to_string()),
+            )
+        } else {
+            (parse_title(&header[0x134..0x143]), None)
+        };
+
+        let licensee = match header[0x14B] {
+            USE_NEW_LICENSEE => Licensee::New([header[0x144], header[0x145]]),
+            code => Licensee::Old(code),
+        };
+
+        let destination = match header[0x14A] {
+            0x00 => Destination::Japan,
+            _ => Destination::Overseas,
+        };
+
+        let header_checksum_value = header[0x14D];
+        let computed_header_checksum = header_checksum(header);
+        let global_checksum = u16::from_be_bytes([header[0x14E], header[0x14F]]);
+
+        Ok(CartridgeHeader {
+            valid_logo: header[0x104..0x134] == NINTENDO_LOGO,
+            title,
+            manufacturer_code,
+            cgb_flag,
+            sgb_flag: header[0x146] == SGB_SUPPORTED,
+            licensee,
+            cartridge_type,
+            rom_size: rom_s,
+            ram_size: ram_s,
+            file_size: rom.len(),
+            destination,
+            mask_rom_version: header[0x14C],
+            header_checksum: header_checksum_value,
+            valid_header_checksum: computed_header_checksum == header_checksum_value,
+            computed_header_checksum,
+            global_checksum,
+            valid_global_checksum: compute_global_checksum(rom) == global_checksum,
+        })
+    }
+
+    /// True when the rom file is bigger than the rom size in the header. Smaller files are
+    /// rejected when parsing
+    pub fn oversized(&self) -> bool {
+        self.file_size > self.rom_size
+    }
+}
+
+// The last 32 KiB of the rom if they hold an MMM01 menu
+fn mmm01_menu(rom: &[u8]) -> Option<&[u8]> {
+    let menu = &rom[rom.len().checked_sub(MMM01_MENU_SIZE)?..];
+    let is_menu = menu[0x104..0x134] == NINTENDO_LOGO && matches!(menu[0x147], 0x0B..=0x0D);
+    is_menu.then_some(menu)
+}
+
+impl fmt::Display for CartridgeHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "TITLE: {}", self.title)?;
+        if let Some(code) = &self.manufacturer_code {
+            writeln!(f, "MANUFACTURER: {}", code)?;
+        }
+        writeln!(
+            f,
+            "LICENSEE: {} ({})",
+            self.licensee.publisher(),
+            self.licensee.code()
+        )?;
+        writeln!(f, "CART TYPE: {}", self.cartridge_type)?;
+        writeln!(
+            f,
+            "ROM SIZE: {:#X} BYTES (FILE {:#X} BYTES)",
+            self.rom_size, self.file_size
+        )?;
+        writeln!(f, "RAM SIZE: {:#X} BYTES", self.ram_size)?;
+        writeln!(f, "CGB: {:?}", self.cgb_flag)?;
+        writeln!(f, "SGB: {}", self.sgb_flag)?;
+        writeln!(f, "DESTINATION: {:?}", self.destination)?;
+        writeln!(f, "VERSION: {}", self.mask_rom_version)?;
+        writeln!(f, "LOGO: {}", if self.valid_logo { "OK" } else { "BAD" })?;
+        writeln!(
+            f,
+            "HEADER CHECKSUM: {:#04X} ({})",
+            self.header_checksum,
+            if self.valid_header_checksum {
+                "OK"
+            } else {
+                "BAD"
+            }
+        )?;
+        write!(
+            f,
+            "GLOBAL CHECKSUM: {:#06X} ({})",
+            self.global_checksum,
+            if self.valid_global_checksum {
+                "OK"
+            } else {
+                "BAD"
+            }
+        )
+    }
+}
+
+/// Sum of every byte of the rom except the global checksum itself at 0x14E - 0x14F
+pub fn compute_global_checksum(rom: &[u8]) -> u16 {
+    rom.iter()
+        .enumerate()
+        .filter(|(addr, _)| *addr != 0x14E && *addr != 0x14F)
+        .fold(0u16, |sum, (_, &byte)| sum.wrapping_add(u16::from(byte)))
+}
+
+// Titles are upper case ASCII padded with zeroes
+fn parse_title(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| {
+            if c.is_ascii_graphic() || c == b' ' {
+                c as char
+            } else {
+                '?'
+            }
+        })
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+fn new_licensee_publisher(code: &[u8; 2]) -> &'static str {
+    match code {
+        b"00" => "None",
+        b"01" => "Nintendo R&D1",
+        b"08" => "Capcom",
+        b"13" => "Electronic Arts",
+        b"18" => "Hudson Soft",
+        b"19" => "b-ai",
+        b"20" => "kss",
+        b"22" => "pow",
+        b"24" => "PCM Complete",
+        b"25" => "san-x",
+        b"28" => "Kemco Japan",
+        b"29" => "seta",
+        b"30" => "Viacom",
+        b"31" => "Nintendo",
+        b"32" => "Bandai",
+        b"33" => "Ocean/Acclaim",
+        b"34" => "Konami",
+        b"35" => "Hector",
+        b"37" => "Taito",
+        b"38" => "Hudson",
+        b"39" => "Banpresto",
+        b"41" => "Ubi Soft",
+        b"42" => "Atlus",
+        b"44" => "Malibu",
+        b"46" => "angel",
+        b"47" => "Bullet-Proof",
+        b"49" => "irem",
+        b"50" => "Absolute",
+        b"51" => "Acclaim",
+        b"52" => "Activision",
+        b"53" => "American sammy",
+        b"54" => "Konami",
+        b"55" => "Hi tech entertainment",
+        b"56" => "LJN",
+        b"57" => "Matchbox",
+        b"58" => "Mattel",
+        b"59" => "Milton Bradley",
+        b"60" => "Titus",
+        b"61" => "Virgin",
+        b"64" => "LucasArts",
+        b"67" => "Ocean",
+        b"69" => "Electronic Arts",
+        b"70" => "Infogrames",
+        b"71" => "Interplay",
+        b"72" => "Broderbund",
+        b"73" => "sculptured",
+        b"75" => "sci",
+        b"78" => "THQ",
+        b"79" => "Accolade",
+        b"80" => "misawa",
+        b"83" => "lozc",
+        b"86" => "Tokuma Shoten Intermedia",
+        b"87" => "Tsukuda Original",
+        b"91" => "Chunsoft",
+        b"92" => "Video system",
+        b"93" => "Ocean/Acclaim",
+        b"95" => "Varie",
+        b"96" => "Yonezawa/s'pal",
+        b"97" => "Kaneko",
+        b"99" => "Pack in soft",
+        b"9H" => "Bottom Up",
+        b"A4" => "Konami (Yu-Gi-Oh!)",
+        _ => "Unknown",
+    }
+}
+
+fn old_licensee_publisher(code: u8) -> &'static str {
+    match code {
+        0x00 => "None",
+        0x01 => "Nintendo",
+        0x08 => "Capcom",
+        0x09 => "Hot-B",
+        0x0A => "Jaleco",
+        0x0B => "Coconuts Japan",
+        0x0C => "Elite Systems",
+        0x13 => "Electronic Arts",
+        0x18 => "Hudsonsoft",
+        0x19 => "ITC Entertainment",
+        0x1A => "Yanoman",
+        0x1D => "Japan Clary",
+        0x1F => "Virgin Interactive",
+        0x24 => "PCM Complete",
+        0x25 => "San-X",
+        0x28 => "Kotobuki Systems",
+        0x29 => "Seta",
+        0x30 => "Infogrames",
+        0x31 => "Nintendo",
+        0x32 => "Bandai",
+        0x34 => "Konami",
+        0x35 => "HectorSoft",
+        0x38 => "Capcom",
+        0x39 => "Banpresto",
+        0x3C => "Entertainment i",
+        0x3E => "Gremlin",
+        0x41 => "Ubisoft",
+        0x42 => "Atlus",
+        0x44 => "Malibu",
+        0x46 => "Angel",
+        0x47 => "Spectrum Holoby",
+        0x49 => "Irem",
+        0x4A => "Virgin Interactive",
+        0x4D => "Malibu",
+        0x4F => "U.S. Gold",
+        0x50 => "Absolute",
+        0x51 => "Acclaim",
+        0x52 => "Activision",
+        0x53 => "American Sammy",
+        0x54 => "GameTek",
+        0x55 => "Park Place",
+        0x56 => "LJN",
+        0x57 => "Matchbox",
+        0x59 => "Milton Bradley",
+        0x5A => "Mindscape",
+        0x5B => "Romstar",
+        0x5C => "Naxat Soft",
+        0x5D => "Tradewest",
+        0x60 => "Titus",
+        0x61 => "Virgin Interactive",
+        0x67 => "Ocean Interactive",
+        0x69 => "Electronic Arts",
+        0x6E => "Elite Systems",
+        0x6F => "Electro Brain",
+        0x70 => "Infogrames",
+        0x71 => "Interplay",
+        0x72 => "Broderbund",
+        0x73 => "Sculptered Soft",
+        0x75 => "The Sales Curve",
+        0x78 => "THQ",
+        0x79 => "Accolade",
+        0x7A => "Triffix Entertainment",
+        0x7C => "Microprose",
+        0x7F => "Kemco",
+        0x80 => "Misawa Entertainment",
+        0x83 => "Lozc",
+        0x86 => "Tokuma Shoten Intermedia",
+        0x8B => "Bullet-Proof Software",
+        0x8C => "Vic Tokai",
+        0x8E => "Ape",
+        0x8F => "I'Max",
+        0x91 => "Chunsoft",
+        0x92 => "Video System",
+        0x93 => "Tsubaraya Productions",
+        0x95 => "Varie",
+        0x96 => "Yonezawa/S'Pal",
+        0x97 => "Kaneko",
+        0x99 => "Arc",
+        0x9A => "Nihon Bussan",
+        0x9B => "Tecmo",
+        0x9C => "Imagineer",
+        0x9D => "Banpresto",
+        0x9F => "Nova",
+        0xA1 => "Hori Electric",
+        0xA2 => "Bandai",
+        0xA4 => "Konami",
+        0xA6 => "Kawada",
+        0xA7 => "Takara",
+        0xA9 => "Technos Japan",
+        0xAA => "Broderbund",
+        0xAC => "Toei Animation",
+        0xAD => "Toho",
+        0xAF => "Namco",
+        0xB0 => "Acclaim",
+        0xB1 => "ASCII or Nexsoft",
+        0xB2 => "Bandai",
+        0xB4 => "Square Enix",
+        0xB6 => "HAL Laboratory",
+        0xB7 => "SNK",
+        0xB9 => "Pony Canyon",
+        0xBA => "Culture Brain",
+        0xBB => "Sunsoft",
+        0xBD => "Sony Imagesoft",
+        0xBF => "Sammy",
+        0xC0 => "Taito",
+        0xC2 => "Kemco",
+        0xC3 => "Squaresoft",
+        0xC4 => "Tokuma Shoten Intermedia",
+        0xC5 => "Data East",
+        0xC6 => "Tonkinhouse",
+        0xC8 => "Koei",
+        0xC9 => "UFL",
+        0xCA => "Ultra",
+        0xCB => "Vap",
+        0xCC => "Use Corporation",
+        0xCD => "Meldac",
+        0xCE => "Pony Canyon",
+        0xCF => "Angel",
+        0xD0 => "Taito",
+        0xD1 => "Sofel",
+        0xD2 => "Quest",
+        0xD3 => "Sigma Enterprises",
+        0xD4 => "ASK Kodansha",
+        0xD6 => "Naxat Soft",
+        0xD7 => "Copya System",
+        0xD9 => "Banpresto",
+        0xDA => "Tomy",
+        0xDB => "LJN",
+        0xDD => "NCS",
+        0xDE => "Human",
+        0xDF => "Altron",
+        0xE0 => "Jaleco",
+        0xE1 => "Towa Chiki",
+        0xE2 => "Yutaka",
+        0xE3 => "Varie",
+        0xE5 => "Epcoh",
+        0xE7 => "Athena",
+        0xE8 => "Asmik ACE Entertainment",
+        0xE9 => "Natsume",
+        0xEA => "King Records",
+        0xEB => "Atlus",
+        0xEC => "Epic/Sony Records",
+        0xEE => "IGS",
+        0xF0 => "A Wave",
+        0xF3 => "Extreme Entertainment",
+        0xFF => "LJN",
+        _ => "Unknown",
+    }
+}
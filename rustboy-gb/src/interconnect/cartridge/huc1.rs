@@ -0,0 +1,100 @@
+use crate::constants::{RAM_BANK_SIZE, ROM_BANK_SIZE};
+use crate::interconnect::infrared::{no_infrared, InfraredPort};
+
+use serde::{Deserialize, Serialize};
+
+// Written to 0x0000 - 0x1FFF, maps the IR port over RAM
+const IR_MODE: u8 = 0x0E;
+
+/// Hudson HuC1: an MBC1 without the RAM enable, where 0x0000 - 0x1FFF switches 0xA000 - 0xBFFF
+/// between RAM and the IR port
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HuC1State {
+    pub rom: Vec<u8>,
+    pub ram: Vec<u8>,
+    rom_bank_number: usize,
+    ram_bank_number: usize,
+    ir_mode: bool,
+
+    // Not part of save states, has to be set again after loading one
+    #[serde(skip, default = "no_infrared")]
+    infrared: Box<dyn InfraredPort>,
+}
+
+impl HuC1State {
+    pub fn new(rom: &[u8], ram: &[u8]) -> HuC1State {
+        HuC1State {
+            rom: rom.to_vec(),
+            ram: ram.to_vec(),
+            rom_bank_number: 1,
+            ram_bank_number: 0,
+            ir_mode: false,
+            infrared: no_infrared(),
+        }
+    }
+
+    pub fn set_infrared(&mut self, port: Box<dyn InfraredPort>) {
+        self.infrared = port;
+    }
+
+    fn ram_address(&self, addr: u16) -> usize {
+        (RAM_BANK_SIZE * self.ram_bank_number + (addr & 0x1FFF) as usize) & (self.ram.len() - 1)
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x3FFF => self.rom[addr as usize],
+
+            0x4000..=0x7FFF => {
+                let new_addr = ROM_BANK_SIZE * self.rom_bank_number + (addr & 0x3FFF) as usize;
+                let new_addr = new_addr & (self.rom.len() - 1);
+                self.rom[new_addr]
+            }
+
+            // Bit 0 is set while light comes in
+            0xA000..=0xBFFF if self.ir_mode => 0xC0 | u8::from(self.infrared.receiving()),
+
+            0xA000..=0xBFFF => {
+                if self.ram.is_empty() {
+                    return 0xFF;
+                }
+                self.ram[self.ram_address(addr)]
+            }
+
+            _ => 0xFF,
+        }
+    }
+
+    pub fn ram_writable(&self) -> bool {
+        !self.ir_mode && !self.ram.is_empty()
+    }
+
+    pub fn write(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x0000..=0x1FFF => {
+                self.ir_mode = value & 0x0F == IR_MODE;
+            }
+
+            0x2000..=0x3FFF => {
+                let bank = (value & 0x3F) as usize;
+                self.rom_bank_number = if bank == 0 { 1 } else { bank };
+            }
+
+            0x4000..=0x5FFF => {
+                self.ram_bank_number = (value & 0x03) as usize;
+            }
+
+            0xA000..=0xBFFF if self.ir_mode => self.infrared.set_led(value & 0x01 != 0),
+
+            0xA000..=0xBFFF => {
+                if self.ram.is_empty() {
+                    return;
+                }
+                let ram_addr = self.ram_address(addr);
+                self.ram[ram_addr] = value;
+            }
+
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use crate::constants::{CLOCK_SPEED, RAM_BANK_SIZE, ROM_BANK_SIZE};
+use crate::interconnect::infrared::{no_infrared, InfraredPort};
+
+use serde::{Deserialize, Serialize};
+
+/// Size of the clock footer appended to save RAM, laid out like SameBoy's
+pub const HUC3_FOOTER_SIZE: usize = 17;
+
+const MINUTES_PER_DAY: u64 = 24 * 60;
+const CYCLES_PER_MINUTE: u32 = CLOCK_SPEED as u32 * 60;
+
+// What 0xA000 - 0xBFFF maps, written to 0x0000 - 0x1FFF
+const MODE_RAM_READ_ONLY: u8 = 0x0;
+const MODE_RAM: u8 = 0xA;
+const MODE_COMMAND: u8 = 0xB;
+const MODE_RESPONSE: u8 = 0xC;
+const MODE_SEMAPHORE: u8 = 0xD;
+const MODE_IR: u8 = 0xE;
+
+// Clock commands, the high nibble of a write in command mode
+const READ_NEXT: u8 = 0x1;
+const WRITE: u8 = 0x2;
+const WRITE_NEXT: u8 = 0x3;
+const INDEX_LOW: u8 = 0x4;
+const INDEX_HIGH: u8 = 0x5;
+const EXTENDED: u8 = 0x6;
+
+// Extended command asking whether the clock is there, answered with 1
+const STATUS: u8 = 0x2;
+
+/// HuC3 clock, counting minutes and days
+///
+/// The game reaches it a nibble at a time through commands: an index into the clock memory is
+/// set, then nibbles are read or written from there. Minutes live at 0x00 - 0x02, days at
+/// 0x03 - 0x06, the alarm at 0x58 - 0x5F. The alarm is kept but never rings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HuC3Clock {
+    // Minutes since midnight
+    minutes: u16,
+    days: u16,
+
+    alarm_minutes: u16,
+    alarm_days: u16,
+    alarm_enabled: bool,
+
+    // Clock memory nibble the next read or write goes to
+    index: u8,
+
+    // Last command, and the nibble it read in the low bits
+    response: u8,
+
+    // T-cycles since the last minute
+    cycles: u32,
+}
+
+impl HuC3Clock {
+    pub fn tick(&mut self, t_cycles: u32) {
+        self.cycles += t_cycles;
+        while self.cycles >= CYCLES_PER_MINUTE {
+            self.cycles -= CYCLES_PER_MINUTE;
+            self.advance_minutes(1);
+        }
+    }
+
+    fn advance_minutes(&mut self, minutes: u64) {
+        let total = u64::from(self.minutes) + minutes;
+        self.days = self.days.wrapping_add((total / MINUTES_PER_DAY) as u16);
+        self.minutes = (total % MINUTES_PER_DAY) as u16;
+    }
+
+    // Counter a clock memory nibble belongs to, and the nibble of it
+    fn counter(&mut self, index: u8) -> Option<(&mut u16, u8)> {
+        match index {
+            0x00..=0x02 => Some((&mut self.minutes, index)),
+            0x03..=0x06 => Some((&mut self.days, index - 0x03)),
+            0x58..=0x5A => Some((&mut self.alarm_minutes, index - 0x58)),
+            0x5B..=0x5E => Some((&mut self.alarm_days, index - 0x5B)),
+            _ => None,
+        }
+    }
+
+    fn read_nibble(&mut self, index: u8) -> u8 {
+        if index == 0x5F {
+            return u8::from(self.alarm_enabled);
+        }
+
+        self.counter(index).map_or(0, |(counter, nibble)| {
+            (*counter >> (nibble * 4)) as u8 & 0x0F
+        })
+    }
+
+    fn write_nibble(&mut self, index: u8, value: u8) {
+        if index == 0x5F {
+            self.alarm_enabled = value & 0x01 != 0;
+        } else if let Some((counter, nibble)) = self.counter(index) {
+            let shift = nibble * 4;
+            *counter = (*counter & !(0x0F << shift)) | (u16::from(value & 0x0F) << shift);
+        }
+    }
+
+    /// Write in command mode
+    pub fn command(&mut self, value: u8) {
+        let command = (value >> 4) & 0x07;
+        let data = value & 0x0F;
+        let mut result = 0;
+
+        match command {
+            READ_NEXT => {
+                result = self.read_nibble(self.index);
+                self.index = self.index.wrapping_add(1);
+            }
+            WRITE => self.write_nibble(self.index, data),
+            WRITE_NEXT => {
+                self.write_nibble(self.index, data);
+                self.index = self.index.wrapping_add(1);
+            }
+            INDEX_LOW => self.index = (self.index & 0xF0) | data,
+            INDEX_HIGH => self.index = (self.index & 0x0F) | (data << 4),
+            EXTENDED if data == STATUS => result = 0x01,
+            _ => (),
+        }
+
+        self.response = (command << 4) | result;
+    }
+
+    /// Read in response mode
+    pub fn response(&self) -> u8 {
+        self.response
+    }
+
+    /// Footer appended to save RAM: the unix time it was written at as 64 bits, then minutes,
+    /// days, alarm minutes and alarm days as 16 bits and whether the alarm is on, little endian
+    pub fn to_footer(&self, now: u64) -> [u8; HUC3_FOOTER_SIZE] {
+        let mut footer = [0; HUC3_FOOTER_SIZE];
+        footer[0..8].copy_from_slice(&now.to_le_bytes());
+
+        let counters = [self.minutes, self.days, self.alarm_minutes, self.alarm_days];
+        for (i, counter) in counters.iter().enumerate() {
+            footer[8 + i * 2..10 + i * 2].copy_from_slice(&counter.to_le_bytes());
+        }
+
+        footer[16] = u8::from(self.alarm_enabled);
+        footer
+    }
+
+    /// Restore from a footer, adding the time that passed since it was written
+    pub fn load_footer(&mut self, footer: &[u8], now: u64) {
+        let timestamp = u64::from_le_bytes(footer[0..8].try_into().unwrap());
+        let counter = |i: usize| u16::from_le_bytes([footer[8 + i * 2], footer[9 + i * 2]]);
+
+        self.minutes = counter(0);
+        self.days = counter(1);
+        self.alarm_minutes = counter(2);
+        self.alarm_days = counter(3);
+        self.alarm_enabled = footer[16] & 0x01 != 0;
+
+        let elapsed = now.saturating_sub(timestamp);
+        self.cycles = 0;
+        self.advance_minutes(elapsed / 60);
+        self.tick((elapsed % 60) as u32 * CLOCK_SPEED as u32);
+    }
+}
+
+/// Hudson HuC3: 0x0000 - 0x1FFF picks what 0xA000 - 0xBFFF maps, RAM, the clock's command and
+/// response registers, or the IR port
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HuC3State {
+    pub rom: Vec<u8>,
+    pub ram: Vec<u8>,
+    pub clock: HuC3Clock,
+    rom_bank_number: usize,
+    ram_bank_number: usize,
+    mode: u8,
+
+    // Not part of save states, has to be set again after loading one
+    #[serde(skip, default = "no_infrared")]
+    infrared: Box<dyn InfraredPort>,
+}
+
+impl HuC3State {
+    pub fn new(rom: &[u8], ram: &[u8]) -> HuC3State {
+        HuC3State {
+            rom: rom.to_vec(),
+            ram: ram.to_vec(),
+            clock: HuC3Clock::default(),
+            rom_bank_number: 1,
+            ram_bank_number: 0,
+            mode: MODE_RAM_READ_ONLY,
+            infrared: no_infrared(),
+        }
+    }
+
+    pub fn set_infrared(&mut self, port: Box<dyn InfraredPort>) {
+        self.infrared = port;
+    }
+
+    pub fn tick(&mut self, t_cycles: u32) {
+        self.clock.tick(t_cycles);
+    }
+
+    fn ram_address(&self, addr: u16) -> usize {
+        (RAM_BANK_SIZE * self.ram_bank_number + (addr & 0x1FFF) as usize) & (self.ram.len() - 1)
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x3FFF => self.rom[addr as usize],
+
+            0x4000..=0x7FFF => {
+                let new_addr = ROM_BANK_SIZE * self.rom_bank_number + (addr & 0x3FFF) as usize;
+                let new_addr = new_addr & (self.rom.len() - 1);
+                self.rom[new_addr]
+            }
+
+            0xA000..=0xBFFF => match self.mode {
+                MODE_RAM_READ_ONLY | MODE_RAM if !self.ram.is_empty() => {
+                    self.ram[self.ram_address(addr)]
+                }
+                MODE_RESPONSE => self.clock.response(),
+                // Commands are done straight away, the clock is always ready
+                MODE_SEMAPHORE => 0x01,
+                MODE_IR => 0xC0 | u8::from(self.infrared.receiving()),
+                _ => 0xFF,
+            },
+
+            _ => 0xFF,
+        }
+    }
+
+    pub fn ram_writable(&self) -> bool {
+        self.mode == MODE_COMMAND || (self.mode == MODE_RAM && !self.ram.is_empty())
+    }
+
+    pub fn write(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x0000..=0x1FFF => {
+                self.mode = value & 0x0F;
+            }
+
+            0x2000..=0x3FFF => {
+                let bank = (value & 0x7F) as usize;
+                self.rom_bank_number = if bank == 0 { 1 } else { bank };
+            }
+
+            0x4000..=0x5FFF => {
+                self.ram_bank_number = (value & 0x03) as usize;
+            }
+
+            0xA000..=0xBFFF => match self.mode {
+                MODE_RAM if !self.ram.is_empty() => {
+                    let ram_addr = self.ram_address(addr);
+                    self.ram[ram_addr] = value;
+                }
+                MODE_COMMAND => self.clock.command(value),
+                MODE_IR => self.infrared.set_led(value & 0x01 != 0),
+                _ => (),
+            },
+
+            _ => (),
+        }
+    }
+}
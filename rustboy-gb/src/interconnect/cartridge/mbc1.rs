@@ -1,6 +1,8 @@
 use crate::constants::RAM_BANK_SIZE;
 use crate::constants::ROM_BANK_SIZE;
 
+use super::header::NINTENDO_LOGO;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -9,6 +11,17 @@ pub enum BankingMode {
     Ram,
 }
 
+// Multicarts are 1 MiB, with a game (and its header) every 256 KiB
+const MULTICART_SIZE: usize = 0x100000;
+const MULTICART_GAME_SIZE: usize = 0x40000;
+
+/// Compilation carts (MBC1M) wire the 2 bit register to bank bits 4-5 instead of 5-6, so every
+/// game gets 16 banks. They are told apart by the Nintendo logo of the second game
+pub fn is_multicart(rom: &[u8]) -> bool {
+    let logo = MULTICART_GAME_SIZE + 0x104;
+    rom.len() == MULTICART_SIZE && rom[logo..logo + NINTENDO_LOGO.len()] == NINTENDO_LOGO
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Mbc1State {
     pub rom: Vec<u8>,
@@ -16,6 +29,9 @@ pub struct Mbc1State {
     ram_bank_number: usize,
     ram_enabled: bool,
     banking_mode: BankingMode,
+
+    // MBC1M wiring, see `is_multicart`
+    multicart: bool,
 }
 
 impl Mbc1State {
@@ -27,6 +43,14 @@ impl Mbc1State {
             banking_mode: BankingMode::Rom,
             rom_bank_number: 1,
             ram_bank_number: 0,
+            multicart: false,
+        }
+    }
+
+    pub fn new_multicart(rom: &[u8], ram: &[u8]) -> Mbc1State {
+        Mbc1State {
+            multicart: true,
+            ..Mbc1State::new(rom, ram)
         }
     }
 
@@ -38,6 +62,16 @@ impl Mbc1State {
             banking_mode: BankingMode::Rom,
             rom_bank_number: 1,
             ram_bank_number: 0,
+            multicart: false,
+        }
+    }
+
+    // Bit of the bank number the 2 bit register starts at
+    fn upper_bits_shift(&self) -> usize {
+        if self.multicart {
+            4
+        } else {
+            5
         }
     }
 
@@ -56,12 +90,14 @@ impl Mbc1State {
     pub fn get_lower_rom_bank(&self) -> usize {
         match self.banking_mode {
             BankingMode::Rom => 0,
-            BankingMode::Ram => (self.ram_bank_number << 5) as usize,
+            BankingMode::Ram => self.ram_bank_number << self.upper_bits_shift(),
         }
     }
 
     pub fn get_upper_rom_bank(&self) -> usize {
-        (self.rom_bank_number | self.ram_bank_number << 5) as usize
+        // Bit 4 of the bank number register isn't connected on multicarts
+        let lower_bits = (1 << self.upper_bits_shift()) - 1;
+        (self.rom_bank_number & lower_bits) | (self.ram_bank_number << self.upper_bits_shift())
     }
 
     pub fn get_ram_bank(&self) -> usize {
@@ -106,6 +142,10 @@ impl Mbc1State {
         }
     }
 
+    pub fn ram_writable(&self) -> bool {
+        self.ram_enabled && !self.ram.is_empty()
+    }
+
     pub fn write(&mut self, addr: u16, value: u8) {
         match addr {
             0x000..=0x1FFF => self.ram_enabled = value == 0xA,
@@ -41,6 +41,10 @@ impl Mbc2State {
         }
     }
 
+    pub fn ram_writable(&self) -> bool {
+        self.ram_enabled
+    }
+
     pub fn write(&mut self, addr: u16, value: u8) {
         match addr {
             0x0000..=0x3FFF => {
@@ -1,5 +1,7 @@
 use crate::constants::{RAM_BANK_SIZE, ROM_BANK_SIZE};
 
+use super::rtc::Rtc;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -7,18 +9,30 @@ pub struct Mbc3State {
     pub rom: Vec<u8>,
     pub ram: Vec<u8>,
     rom_bank_number: usize,
+
+    // 0x00 - 0x03 selects a RAM bank, 0x08 - 0x0C an RTC register
     ram_bank_number: usize,
     ram_enabled: bool,
+
+    // Only present on MBC3 + TIMER cartridges
+    pub rtc: Option<Rtc>,
 }
 
 impl Mbc3State {
-    pub fn new(rom: &[u8], ram: &[u8]) -> Mbc3State {
+    pub fn new(rom: &[u8], ram: &[u8], has_rtc: bool) -> Mbc3State {
         Mbc3State {
             rom: rom.to_vec(),
             ram: ram.to_vec(),
             rom_bank_number: 1,
             ram_bank_number: 0,
             ram_enabled: false,
+            rtc: if has_rtc { Some(Rtc::new()) } else { None },
+        }
+    }
+
+    pub fn tick(&mut self, t_cycles: u32) {
+        if let Some(rtc) = &mut self.rtc {
+            rtc.tick(t_cycles);
         }
     }
 
@@ -37,24 +51,26 @@ impl Mbc3State {
                     return 0xFF;
                 }
 
-                let new_addr = (RAM_BANK_SIZE * self.ram_bank_number) + (addr & 0x1FFF) as usize
-                    & (self.ram.len() - 1);
-                return self.ram[new_addr];
-                /*
-                match addr {
-                    0x00..=0x03 => {
-                        let new_addr = (RAM_BANK_SIZE * self.ram_bank_number) + (addr & 0x1FFF) as usize & (self.ram.len() - 1);
-                        return self.ram[new_addr];
+                match (self.ram_bank_number, &self.rtc) {
+                    (0x00..=0x03, _) if !self.ram.is_empty() => {
+                        let new_addr = ((RAM_BANK_SIZE * self.ram_bank_number)
+                            + (addr & 0x1FFF) as usize)
+                            & (self.ram.len() - 1);
+                        self.ram[new_addr]
                     }
-                    _ => return 0xFF,
+                    (0x08..=0x0C, Some(rtc)) => rtc.read(self.ram_bank_number),
+                    _ => 0xFF,
                 }
-                */
             }
 
             _ => panic!("NOT REACHABLE MBC3 {:#X}", addr),
         }
     }
 
+    pub fn ram_writable(&self) -> bool {
+        self.ram_enabled
+    }
+
     pub fn write(&mut self, addr: u16, value: u8) {
         match addr {
             0x0000..=0x1FFF => {
@@ -64,27 +80,31 @@ impl Mbc3State {
             0x2000..=0x3FFF => self.rom_bank_number = if value == 0 { 1 } else { value as usize },
 
             0x4000..=0x5FFF => match value {
-                0x00..=0x03 => {
+                0x00..=0x03 | 0x08..=0x0C => {
                     self.ram_bank_number = value as usize;
                 }
                 _ => (),
             },
 
-            0x6000..=0x7FFF => {}
+            0x6000..=0x7FFF => {
+                if let Some(rtc) = &mut self.rtc {
+                    rtc.write_latch(value);
+                }
+            }
 
             0xA000..=0xBFFF => {
                 if !self.ram_enabled {
                     return;
                 }
 
-                match self.ram_bank_number {
-                    0x00..=0x03 => {
-                        let new_addr = (RAM_BANK_SIZE * self.ram_bank_number)
-                            + (addr & 0x1FFF) as usize
+                match (self.ram_bank_number, &mut self.rtc) {
+                    (0x00..=0x03, _) if !self.ram.is_empty() => {
+                        let new_addr = ((RAM_BANK_SIZE * self.ram_bank_number)
+                            + (addr & 0x1FFF) as usize)
                             & (self.ram.len() - 1);
                         self.ram[new_addr] = value;
                     }
-
+                    (0x08..=0x0C, Some(rtc)) => rtc.write(self.ram_bank_number, value),
                     _ => (),
                 }
             }
@@ -2,6 +2,9 @@ use crate::constants::{RAM_BANK_SIZE, ROM_BANK_SIZE};
 
 use serde::{Deserialize, Serialize};
 
+// Bit 3 of the RAM bank number drives the motor on rumble cartridges
+const MOTOR_BIT: u8 = 0x08;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Mbc5State {
     pub rom: Vec<u8>,
@@ -9,19 +12,59 @@ pub struct Mbc5State {
     rom_bank_number: usize,
     ram_bank_number: usize,
     ram_enabled: bool,
+
+    has_rumble: bool,
+    motor_on: bool,
+
+    // T-cycles the motor was on, out of the T-cycles since the duty cycle was last taken
+    motor_cycles: u32,
+    duty_cycles: u32,
 }
 
 impl Mbc5State {
-    pub fn new(rom: &[u8], ram: &[u8]) -> Mbc5State {
+    pub fn new(rom: &[u8], ram: &[u8], has_rumble: bool) -> Mbc5State {
         Mbc5State {
             rom: rom.to_vec(),
             ram: ram.to_vec(),
             rom_bank_number: 1,
             ram_bank_number: 0,
             ram_enabled: false,
+            has_rumble,
+            motor_on: false,
+            motor_cycles: 0,
+            duty_cycles: 0,
+        }
+    }
+
+    pub fn tick(&mut self, t_cycles: u32) {
+        if !self.has_rumble {
+            return;
+        }
+
+        self.duty_cycles = self.duty_cycles.saturating_add(t_cycles);
+        if self.motor_on {
+            self.motor_cycles = self.motor_cycles.saturating_add(t_cycles);
         }
     }
 
+    pub fn motor_on(&self) -> bool {
+        self.motor_on
+    }
+
+    /// Share of the time the motor was on since the last call, from 0 to 1. Games vary the
+    /// strength by toggling it quickly, so this is what a frontend should rumble with
+    pub fn take_motor_duty(&mut self) -> f32 {
+        let duty = if self.duty_cycles == 0 {
+            f32::from(u8::from(self.motor_on))
+        } else {
+            self.motor_cycles as f32 / self.duty_cycles as f32
+        };
+
+        self.motor_cycles = 0;
+        self.duty_cycles = 0;
+        duty
+    }
+
     pub fn read(&self, addr: u16) -> u8 {
         match addr {
             0x0000..=0x3FFF => self.rom[addr as usize],
@@ -45,6 +88,10 @@ impl Mbc5State {
         }
     }
 
+    pub fn ram_writable(&self) -> bool {
+        self.ram_enabled && !self.ram.is_empty()
+    }
+
     pub fn write(&mut self, addr: u16, value: u8) {
         match addr {
             0x0000..=0x1FFF => {
@@ -60,7 +107,13 @@ impl Mbc5State {
                 self.rom_bank_number |= new_value as usize;
             }
             0x4000..=0x7FFF => {
-                self.ram_bank_number = (value & 0xF) as usize;
+                // Rumble cartridges only have 3 RAM bank bits, the 4th one is the motor
+                if self.has_rumble {
+                    self.motor_on = value & MOTOR_BIT != 0;
+                    self.ram_bank_number = (value & 0x07) as usize;
+                } else {
+                    self.ram_bank_number = (value & 0x0F) as usize;
+                }
             }
 
             0xA000..=0xBFFF => {
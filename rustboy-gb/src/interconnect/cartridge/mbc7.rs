@@ -0,0 +1,131 @@
+use crate::constants::ROM_BANK_SIZE;
+
+use super::eeprom::Eeprom;
+
+use serde::{Deserialize, Serialize};
+
+// Accelerometer reading when level, and how much it changes per g of tilt
+const ACCELEROMETER_CENTER: f32 = 0x81D0 as f32;
+const ACCELEROMETER_G: f32 = 0x70 as f32;
+
+// What the latch reads after being erased
+const ACCELEROMETER_ERASED: u16 = 0x8000;
+
+// The ADXL202E measures up to 2 g
+const MAX_TILT: f32 = 2.0;
+
+/// MBC7, with an ADXL202E accelerometer and a 93LC56 EEPROM instead of RAM
+///
+/// Both are reached through registers at 0xA000 - 0xAFFF (bits 4-7 of the address), once the
+/// game wrote 0x0A to 0x0000 - 0x1FFF and 0x40 to 0x4000 - 0x5FFF.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Mbc7State {
+    pub rom: Vec<u8>,
+    pub eeprom: Eeprom,
+    rom_bank_number: usize,
+    ram_enabled: bool,
+    registers_enabled: bool,
+
+    // How the cart is tilted in g, positive x to the right and positive y towards the player
+    tilt_x: f32,
+    tilt_y: f32,
+
+    // Values the game reads, only updated when latched
+    latched_x: u16,
+    latched_y: u16,
+
+    // Set by erasing the latch, a new value can only be latched after that
+    latch_ready: bool,
+}
+
+impl Mbc7State {
+    pub fn new(rom: &[u8]) -> Mbc7State {
+        Mbc7State {
+            rom: rom.to_vec(),
+            eeprom: Eeprom::new(),
+            rom_bank_number: 1,
+            ram_enabled: false,
+            registers_enabled: false,
+            tilt_x: 0.0,
+            tilt_y: 0.0,
+            latched_x: ACCELEROMETER_ERASED,
+            latched_y: ACCELEROMETER_ERASED,
+            latch_ready: false,
+        }
+    }
+
+    /// Tilt of the cart in g, the game sees it the next time it latches the accelerometer
+    pub fn set_tilt(&mut self, x: f32, y: f32) {
+        self.tilt_x = x.clamp(-MAX_TILT, MAX_TILT);
+        self.tilt_y = y.clamp(-MAX_TILT, MAX_TILT);
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            0x0000..=0x3FFF => self.rom[addr as usize],
+
+            0x4000..=0x7FFF => {
+                let new_addr = ROM_BANK_SIZE * self.rom_bank_number + (addr & 0x3FFF) as usize;
+                let new_addr = new_addr & (self.rom.len() - 1);
+                self.rom[new_addr]
+            }
+
+            0xA000..=0xAFFF if self.ram_enabled && self.registers_enabled => {
+                match (addr >> 4) & 0x0F {
+                    0x2 => self.latched_x as u8,
+                    0x3 => (self.latched_x >> 8) as u8,
+                    0x4 => self.latched_y as u8,
+                    0x5 => (self.latched_y >> 8) as u8,
+                    0x6 => 0x00,
+                    0x8 => self.eeprom.read(),
+                    _ => 0xFF,
+                }
+            }
+
+            _ => 0xFF,
+        }
+    }
+
+    pub fn ram_writable(&self) -> bool {
+        self.ram_enabled && self.registers_enabled
+    }
+
+    pub fn write(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x0000..=0x1FFF => {
+                self.ram_enabled = value == 0x0A;
+            }
+
+            0x2000..=0x3FFF => {
+                self.rom_bank_number = value as usize;
+            }
+
+            0x4000..=0x5FFF => {
+                self.registers_enabled = value == 0x40;
+            }
+
+            0xA000..=0xAFFF if self.ram_enabled && self.registers_enabled => {
+                match (addr >> 4) & 0x0F {
+                    0x0 if value == 0x55 => {
+                        self.latched_x = ACCELEROMETER_ERASED;
+                        self.latched_y = ACCELEROMETER_ERASED;
+                        self.latch_ready = true;
+                    }
+                    0x1 if value == 0xAA && self.latch_ready => {
+                        self.latched_x = accelerometer(self.tilt_x);
+                        self.latched_y = accelerometer(self.tilt_y);
+                        self.latch_ready = false;
+                    }
+                    0x8 => self.eeprom.write(value),
+                    _ => (),
+                }
+            }
+
+            _ => (),
+        }
+    }
+}
+
+fn accelerometer(g: f32) -> u16 {
+    (ACCELEROMETER_CENTER + g * ACCELEROMETER_G) as u16
+}
@@ -0,0 +1,184 @@
+use crate::constants::{RAM_BANK_SIZE, ROM_BANK_SIZE};
+
+use serde::{Deserialize, Serialize};
+
+// Banks mapped before the menu picked a game, the last 32 KiB of the rom
+const MENU_LOWER_BANK: usize = 0x1FE;
+const MENU_UPPER_BANK: usize = 0x1FF;
+
+/// Mapper of multi game carts. They boot into a menu in the last 32 KiB of the rom, which sets
+/// where the game it starts lives and how many banks it gets, then locks that in (maps the game)
+/// until the next reset. The game then sees an MBC1 over its part of the rom
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Mmm01State {
+    pub rom: Vec<u8>,
+    pub ram: Vec<u8>,
+    ram_enabled: bool,
+
+    // Once set only the bits the game owns can be changed
+    mapped: bool,
+
+    // ROM bank bits 0-4, 5-6 and 7-8
+    rom_bank_low: usize,
+    rom_bank_mid: usize,
+    rom_bank_high: usize,
+
+    // RAM bank bits 0-1 and 2-3
+    ram_bank_low: usize,
+    ram_bank_high: usize,
+
+    // Bits 1-4 of `rom_bank_low` and bits 0-1 of `ram_bank_low` the game can't change
+    rom_bank_mask: usize,
+    ram_bank_mask: usize,
+
+    // MBC1 banking mode, the menu may stop the game from changing it
+    ram_banking_mode: bool,
+    banking_mode_locked: bool,
+
+    // Swaps the roles of `rom_bank_mid` and `ram_bank_low`
+    multiplex: bool,
+}
+
+impl Mmm01State {
+    pub fn new(rom: &[u8], ram: &[u8]) -> Mmm01State {
+        Mmm01State {
+            rom: rom.to_vec(),
+            ram: ram.to_vec(),
+            ram_enabled: false,
+            mapped: false,
+            rom_bank_low: 0,
+            rom_bank_mid: 0,
+            rom_bank_high: 0,
+            ram_bank_low: 0,
+            ram_bank_high: 0,
+            rom_bank_mask: 0,
+            ram_bank_mask: 0,
+            ram_banking_mode: false,
+            banking_mode_locked: false,
+            multiplex: false,
+        }
+    }
+
+    // Banks mapped at 0x0000 - 0x3FFF and 0x4000 - 0x7FFF
+    fn rom_banks(&self) -> (usize, usize) {
+        if !self.mapped {
+            return (MENU_LOWER_BANK, MENU_UPPER_BANK);
+        }
+
+        let (mid, lower_mid) = if self.multiplex {
+            let lower_mid = if self.ram_banking_mode {
+                self.ram_bank_low
+            } else {
+                0
+            };
+            (self.ram_bank_low, lower_mid)
+        } else {
+            (self.rom_bank_mid, self.rom_bank_mid)
+        };
+
+        let fixed = self.rom_bank_mask << 1;
+        let fixed_low = self.rom_bank_low & fixed;
+
+        // Like the MBC1, the game writing 0 to its bits of the bank number selects 1
+        let low = if self.rom_bank_low & !fixed == 0 {
+            self.rom_bank_low | 1
+        } else {
+            self.rom_bank_low
+        };
+
+        let lower = fixed_low | (lower_mid << 5) | (self.rom_bank_high << 7);
+        let upper = low | (mid << 5) | (self.rom_bank_high << 7);
+        (lower, upper)
+    }
+
+    fn ram_bank(&self) -> usize {
+        let low = if self.multiplex {
+            self.rom_bank_mid
+        } else if self.ram_banking_mode {
+            self.ram_bank_low
+        } else {
+            self.ram_bank_low & self.ram_bank_mask
+        };
+
+        low | (self.ram_bank_high << 2)
+    }
+
+    fn rom_address(&self, addr: u16, bank: usize) -> usize {
+        (bank * ROM_BANK_SIZE + (addr & 0x3FFF) as usize) & (self.rom.len() - 1)
+    }
+
+    fn ram_address(&self, addr: u16) -> usize {
+        (self.ram_bank() * RAM_BANK_SIZE + (addr & 0x1FFF) as usize) & (self.ram.len() - 1)
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        let (lower, upper) = self.rom_banks();
+
+        match addr {
+            0x0000..=0x3FFF => self.rom[self.rom_address(addr, lower)],
+            0x4000..=0x7FFF => self.rom[self.rom_address(addr, upper)],
+            0xA000..=0xBFFF => {
+                if self.ram.is_empty() || !self.ram_enabled {
+                    return 0xFF;
+                }
+                self.ram[self.ram_address(addr)]
+            }
+            _ => 0xFF,
+        }
+    }
+
+    pub fn ram_writable(&self) -> bool {
+        self.ram_enabled && !self.ram.is_empty()
+    }
+
+    pub fn write(&mut self, addr: u16, value: u8) {
+        let value = value as usize;
+
+        match addr {
+            0x0000..=0x1FFF => {
+                self.ram_enabled = value & 0x0F == 0x0A;
+
+                if !self.mapped {
+                    self.ram_bank_mask = (value >> 4) & 0b11;
+                    self.mapped = value & 0x40 != 0;
+                }
+            }
+            0x2000..=0x3FFF => {
+                if !self.mapped {
+                    self.rom_bank_mid = (value >> 5) & 0b11;
+                }
+
+                let fixed = self.rom_bank_mask << 1;
+                self.rom_bank_low = (self.rom_bank_low & fixed) | (value & 0x1F & !fixed);
+            }
+            0x4000..=0x5FFF => {
+                let fixed = self.ram_bank_mask;
+                self.ram_bank_low = (self.ram_bank_low & fixed) | (value & 0b11 & !fixed);
+
+                if !self.mapped {
+                    self.ram_bank_high = (value >> 2) & 0b11;
+                    self.rom_bank_high = (value >> 4) & 0b11;
+                    self.banking_mode_locked = value & 0x40 != 0;
+                }
+            }
+            0x6000..=0x7FFF => {
+                if !self.banking_mode_locked {
+                    self.ram_banking_mode = value & 1 != 0;
+                }
+
+                if !self.mapped {
+                    self.rom_bank_mask = (value >> 2) & 0x0F;
+                    self.multiplex = value & 0x40 != 0;
+                }
+            }
+            0xA000..=0xBFFF => {
+                if self.ram.is_empty() || !self.ram_enabled {
+                    return;
+                }
+                let ram_addr = self.ram_address(addr);
+                self.ram[ram_addr] = value as u8;
+            }
+            _ => (),
+        }
+    }
+}
@@ -1,25 +1,53 @@
+pub mod camera;
 pub mod cartridge_info;
+mod eeprom;
+pub mod header;
+mod huc1;
+mod huc3;
 mod mbc1;
 mod mbc2;
 mod mbc3;
 mod mbc5;
+mod mbc7;
+mod mmm01;
 mod nombc;
+pub mod rtc;
 
-use cartridge_info::CartridgeType;
-use mbc1::Mbc1State;
+use camera::{CameraState, ImageSource};
+use cartridge_info::{CartridgeType, LoadError};
+use header::CartridgeHeader;
+use huc1::HuC1State;
+use huc3::{HuC3State, HUC3_FOOTER_SIZE};
+use mbc1::{is_multicart, Mbc1State};
 use mbc2::Mbc2State;
 use mbc3::Mbc3State;
 use mbc5::Mbc5State;
+use mbc7::Mbc7State;
+use mmm01::Mmm01State;
 use nombc::NoMbcState;
+use rtc::{Rtc, RTC_FOOTER_SIZE, RTC_FOOTER_SIZE_32};
+
+use crate::constants::EXTERNAL_RAM;
+use crate::interconnect::infrared::InfraredPort;
+
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
-pub enum Mbc { NoMbc(NoMbcState),
+pub enum Mbc {
+    NoMbc(NoMbcState),
     Mbc1(Mbc1State),
+    // MBC1 wired for 1 MiB compilation carts
+    Mbc1M(Mbc1State),
     Mbc2(Mbc2State),
     Mbc3(Mbc3State),
     Mbc5(Mbc5State),
+    Mbc7(Mbc7State),
+    Mmm01(Mmm01State),
+    Camera(CameraState),
+    HuC1(HuC1State),
+    HuC3(HuC3State),
 }
 
 impl Mbc {
@@ -27,9 +55,15 @@ impl Mbc {
         match self {
             Mbc::NoMbc(mbc) => mbc.read(addr),
             Mbc::Mbc1(mbc) => mbc.read(addr),
+            Mbc::Mbc1M(mbc) => mbc.read(addr),
             Mbc::Mbc2(mbc) => mbc.read(addr),
             Mbc::Mbc3(mbc) => mbc.read(addr),
             Mbc::Mbc5(mbc) => mbc.read(addr),
+            Mbc::Mbc7(mbc) => mbc.read(addr),
+            Mbc::Mmm01(mbc) => mbc.read(addr),
+            Mbc::Camera(mbc) => mbc.read(addr),
+            Mbc::HuC1(mbc) => mbc.read(addr),
+            Mbc::HuC3(mbc) => mbc.read(addr),
         }
     }
 
@@ -37,72 +71,311 @@ impl Mbc {
         match self {
             Mbc::NoMbc(mbc) => mbc.write(addr, value),
             Mbc::Mbc1(mbc) => mbc.write(addr, value),
+            Mbc::Mbc1M(mbc) => mbc.write(addr, value),
             Mbc::Mbc2(mbc) => mbc.write(addr, value),
             Mbc::Mbc3(mbc) => mbc.write(addr, value),
             Mbc::Mbc5(mbc) => mbc.write(addr, value),
+            Mbc::Mbc7(mbc) => mbc.write(addr, value),
+            Mbc::Mmm01(mbc) => mbc.write(addr, value),
+            Mbc::Camera(mbc) => mbc.write(addr, value),
+            Mbc::HuC1(mbc) => mbc.write(addr, value),
+            Mbc::HuC3(mbc) => mbc.write(addr, value),
+        }
+    }
+
+    /// Whether a write to external RAM would reach the save data
+    pub fn ram_writable(&self) -> bool {
+        match self {
+            Mbc::NoMbc(_) => false,
+            Mbc::Mbc1(mbc) => mbc.ram_writable(),
+            Mbc::Mbc1M(mbc) => mbc.ram_writable(),
+            Mbc::Mbc2(mbc) => mbc.ram_writable(),
+            Mbc::Mbc3(mbc) => mbc.ram_writable(),
+            Mbc::Mbc5(mbc) => mbc.ram_writable(),
+            Mbc::Mbc7(mbc) => mbc.ram_writable(),
+            Mbc::Mmm01(mbc) => mbc.ram_writable(),
+            Mbc::Camera(mbc) => mbc.ram_writable(),
+            Mbc::HuC1(mbc) => mbc.ram_writable(),
+            Mbc::HuC3(mbc) => mbc.ram_writable(),
+        }
+    }
+
+    pub fn ram(&self) -> &[u8] {
+        match self {
+            Mbc::NoMbc(_) => &[],
+            Mbc::Mbc1(mbc) => &mbc.ram,
+            Mbc::Mbc1M(mbc) => &mbc.ram,
+            Mbc::Mbc2(mbc) => &mbc.ram,
+            Mbc::Mbc3(mbc) => &mbc.ram,
+            Mbc::Mbc5(mbc) => &mbc.ram,
+            Mbc::Mbc7(mbc) => &mbc.eeprom.data,
+            Mbc::Mmm01(mbc) => &mbc.ram,
+            Mbc::Camera(mbc) => &mbc.ram,
+            Mbc::HuC1(mbc) => &mbc.ram,
+            Mbc::HuC3(mbc) => &mbc.ram,
+        }
+    }
+
+    pub fn ram_mut(&mut self) -> &mut [u8] {
+        match self {
+            Mbc::NoMbc(_) => &mut [],
+            Mbc::Mbc1(mbc) => &mut mbc.ram,
+            Mbc::Mbc1M(mbc) => &mut mbc.ram,
+            Mbc::Mbc2(mbc) => &mut mbc.ram,
+            Mbc::Mbc3(mbc) => &mut mbc.ram,
+            Mbc::Mbc5(mbc) => &mut mbc.ram,
+            Mbc::Mbc7(mbc) => &mut mbc.eeprom.data,
+            Mbc::Mmm01(mbc) => &mut mbc.ram,
+            Mbc::Camera(mbc) => &mut mbc.ram,
+            Mbc::HuC1(mbc) => &mut mbc.ram,
+            Mbc::HuC3(mbc) => &mut mbc.ram,
+        }
+    }
+
+    pub fn tick(&mut self, t_cycles: u32) {
+        match self {
+            Mbc::Mbc3(mbc) => mbc.tick(t_cycles),
+            Mbc::Mbc5(mbc) => mbc.tick(t_cycles),
+            Mbc::Camera(mbc) => mbc.tick(t_cycles),
+            Mbc::HuC3(mbc) => mbc.tick(t_cycles),
+            _ => (),
+        }
+    }
+
+    /// Tilt in g, only MBC7 cartridges have an accelerometer
+    pub fn set_tilt(&mut self, x: f32, y: f32) {
+        if let Mbc::Mbc7(mbc) = self {
+            mbc.set_tilt(x, y);
+        }
+    }
+
+    /// Whether the rumble motor is running, only MBC5 rumble cartridges have one
+    pub fn motor_on(&self) -> bool {
+        matches!(self, Mbc::Mbc5(mbc) if mbc.motor_on())
+    }
+
+    /// Share of the time the rumble motor was on since the last call, 0 without a motor
+    pub fn take_motor_duty(&mut self) -> f32 {
+        match self {
+            Mbc::Mbc5(mbc) => mbc.take_motor_duty(),
+            _ => 0.0,
+        }
+    }
+
+    /// Point the camera at `source`, only the Pocket Camera has one
+    pub fn set_image_source(&mut self, source: Box<dyn ImageSource>) {
+        if let Mbc::Camera(mbc) = self {
+            mbc.set_image_source(source);
+        }
+    }
+
+    /// Point the IR port at `port`, only Hudson cartridges have one
+    pub fn set_infrared(&mut self, port: Box<dyn InfraredPort>) {
+        match self {
+            Mbc::HuC1(mbc) => mbc.set_infrared(port),
+            Mbc::HuC3(mbc) => mbc.set_infrared(port),
+            _ => (),
+        }
+    }
+
+    pub fn rtc(&self) -> Option<&Rtc> {
+        match self {
+            Mbc::Mbc3(mbc) => mbc.rtc.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn rtc_mut(&mut self) -> Option<&mut Rtc> {
+        match self {
+            Mbc::Mbc3(mbc) => mbc.rtc.as_mut(),
+            _ => None,
+        }
+    }
+
+    // Sizes of the clock footer a save can come with, the first one is what gets written
+    fn clock_footer_sizes(&self) -> &'static [usize] {
+        match self {
+            Mbc::Mbc3(mbc) if mbc.rtc.is_some() => &[RTC_FOOTER_SIZE, RTC_FOOTER_SIZE_32],
+            Mbc::HuC3(_) => &[HUC3_FOOTER_SIZE],
+            _ => &[],
+        }
+    }
+
+    fn clock_footer(&self, now: u64) -> Option<Vec<u8>> {
+        match self {
+            Mbc::HuC3(mbc) => Some(mbc.clock.to_footer(now).to_vec()),
+            _ => self.rtc().map(|rtc| rtc.to_footer(now).to_vec()),
+        }
+    }
+
+    fn load_clock_footer(&mut self, footer: &[u8], now: u64) {
+        match self {
+            Mbc::HuC3(mbc) => mbc.clock.load_footer(footer, now),
+            _ => {
+                if let Some(rtc) = self.rtc_mut() {
+                    rtc.load_footer(footer, now);
+                }
+            }
         }
     }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Cartridge {
-    pub title: String,
-    pub cartridge_type: CartridgeType,
-    pub valid_checksum: bool,
+    pub header: CartridgeHeader,
     pub mbc: Mbc,
+
+    // Set on every write to cartridge RAM, so battery saves are only written when needed
+    #[serde(skip)]
+    ram_dirty: bool,
 }
 
 impl Cartridge {
     pub fn empty() -> Cartridge {
         Cartridge {
-            title: String::new(),
-            cartridge_type: CartridgeType::ROMOnly,
-            valid_checksum: false,
+            header: CartridgeHeader::default(),
             mbc: Mbc::NoMbc(NoMbcState::new(&[])),
+            ram_dirty: false,
         }
     }
 
-    pub fn new(rom: &Vec<u8>, ram: &Vec<u8>, cart_type: &CartridgeType) -> Cartridge {
-        let mbc_test: Mbc = match cart_type {
+    pub fn new(rom: &[u8], ram: &[u8], header: CartridgeHeader) -> Cartridge {
+        let mbc_test: Mbc = match header.cartridge_type {
             CartridgeType::ROMOnly => Mbc::NoMbc(NoMbcState::new(rom)),
+            CartridgeType::MBC1 | CartridgeType::MBC1RAM | CartridgeType::MBC1RAMBattery
+                if is_multicart(rom) =>
+            {
+                Mbc::Mbc1M(Mbc1State::new_multicart(rom, ram))
+            }
             CartridgeType::MBC1 => Mbc::Mbc1(Mbc1State::new(rom, ram)),
             CartridgeType::MBC1RAM => Mbc::Mbc1(Mbc1State::new(rom, ram)),
             CartridgeType::MBC1RAMBattery => Mbc::Mbc1(Mbc1State::new(rom, ram)),
+            CartridgeType::MMM01 => Mbc::Mmm01(Mmm01State::new(rom, ram)),
+            CartridgeType::MMM01RAM => Mbc::Mmm01(Mmm01State::new(rom, ram)),
+            CartridgeType::MMM01RAMBattery => Mbc::Mmm01(Mmm01State::new(rom, ram)),
             CartridgeType::MBC2 => Mbc::Mbc2(Mbc2State::new(rom)),
             CartridgeType::MBC2Battery => Mbc::Mbc2(Mbc2State::new(rom)),
-            CartridgeType::MBC3 => Mbc::Mbc3(Mbc3State::new(rom, ram)),
-            CartridgeType::MBC3RAM => Mbc::Mbc3(Mbc3State::new(rom, ram)),
-            CartridgeType::MBC3RAMBattery => Mbc::Mbc3(Mbc3State::new(rom, ram)),
-            CartridgeType::MBC3TimerBattery => Mbc::Mbc3(Mbc3State::new(rom, ram)),
-            CartridgeType::MBC3TimerRAMBattery => Mbc::Mbc3(Mbc3State::new(rom, ram)),
-            CartridgeType::MBC5 => Mbc::Mbc5(Mbc5State::new(rom, ram)),
-            CartridgeType::MBC5RAM => Mbc::Mbc5(Mbc5State::new(rom, ram)),
-            CartridgeType::MBC5RAMBattery => Mbc::Mbc5(Mbc5State::new(rom, ram)),
-            CartridgeType::MBC5Rumble => Mbc::Mbc5(Mbc5State::new(rom, ram)),
-            CartridgeType::MBC5RumbleRAM => Mbc::Mbc5(Mbc5State::new(rom, ram)),
-            CartridgeType::MBC5RumbleRAMBattery => Mbc::Mbc5(Mbc5State::new(rom, ram)),
+            CartridgeType::MBC3 => Mbc::Mbc3(Mbc3State::new(rom, ram, false)),
+            CartridgeType::MBC3RAM => Mbc::Mbc3(Mbc3State::new(rom, ram, false)),
+            CartridgeType::MBC3RAMBattery => Mbc::Mbc3(Mbc3State::new(rom, ram, false)),
+            CartridgeType::MBC3TimerBattery => Mbc::Mbc3(Mbc3State::new(rom, ram, true)),
+            CartridgeType::MBC3TimerRAMBattery => Mbc::Mbc3(Mbc3State::new(rom, ram, true)),
+            CartridgeType::MBC5 => Mbc::Mbc5(Mbc5State::new(rom, ram, false)),
+            CartridgeType::MBC5RAM => Mbc::Mbc5(Mbc5State::new(rom, ram, false)),
+            CartridgeType::MBC5RAMBattery => Mbc::Mbc5(Mbc5State::new(rom, ram, false)),
+            CartridgeType::MBC5Rumble => Mbc::Mbc5(Mbc5State::new(rom, ram, true)),
+            CartridgeType::MBC5RumbleRAM => Mbc::Mbc5(Mbc5State::new(rom, ram, true)),
+            CartridgeType::MBC5RumbleRAMBattery => Mbc::Mbc5(Mbc5State::new(rom, ram, true)),
+            CartridgeType::MBC7SensorRumbleRAMBattery => Mbc::Mbc7(Mbc7State::new(rom)),
+            CartridgeType::PocketCamera => Mbc::Camera(CameraState::new(rom, ram)),
+            CartridgeType::HuC3 => Mbc::HuC3(HuC3State::new(rom, ram)),
+            CartridgeType::HuC1RAMBattery => Mbc::HuC1(HuC1State::new(rom, ram)),
             _ => Mbc::NoMbc(NoMbcState::new(rom)),
         };
 
         Cartridge {
-            title: String::new(),
-            cartridge_type: CartridgeType::ROMOnly,
-            valid_checksum: false,
+            header,
             mbc: mbc_test,
+            ram_dirty: false,
+        }
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        self.mbc.read(addr)
+    }
+
+    pub fn write(&mut self, addr: u16, value: u8) {
+        if EXTERNAL_RAM.contains(&addr) && self.mbc.ram_writable() {
+            self.ram_dirty = true;
+        }
+
+        self.mbc.write(addr, value);
+    }
+
+    pub fn has_battery(&self) -> bool {
+        self.header.cartridge_type.has_battery()
+    }
+
+    pub fn has_accelerometer(&self) -> bool {
+        matches!(self.mbc, Mbc::Mbc7(_))
+    }
+
+    pub fn has_camera(&self) -> bool {
+        matches!(self.mbc, Mbc::Camera(_))
+    }
+
+    pub fn has_rumble(&self) -> bool {
+        matches!(
+            self.header.cartridge_type,
+            CartridgeType::MBC5Rumble
+                | CartridgeType::MBC5RumbleRAM
+                | CartridgeType::MBC5RumbleRAMBattery
+        )
+    }
+
+    pub fn has_infrared(&self) -> bool {
+        matches!(self.mbc, Mbc::HuC1(_) | Mbc::HuC3(_))
+    }
+
+    pub fn tick(&mut self, t_cycles: u32) {
+        self.mbc.tick(t_cycles);
+    }
+
+    /// Copy of the cartridge RAM, as stored in a .sav file
+    pub fn export_ram(&self) -> Vec<u8> {
+        self.export_ram_at(unix_now())
+    }
+
+    /// Like `export_ram`, with the clock footer (if any) stamped with `now` in unix seconds
+    pub fn export_ram_at(&self, now: u64) -> Vec<u8> {
+        let mut data = self.mbc.ram().to_vec();
+        if let Some(footer) = self.mbc.clock_footer(now) {
+            data.extend_from_slice(&footer);
         }
+
+        data
+    }
+
+    /// Replace the cartridge RAM with a save made by `export_ram`
+    pub fn import_ram(&mut self, data: &[u8]) -> Result<(), LoadError> {
+        self.import_ram_at(data, unix_now())
     }
 
-    pub fn checksum(&mut self) -> bool {
-        let mut check_sum: i16 = 0;
+    /// Like `import_ram`, the clock catches up with the time between the footer and `now`
+    ///
+    /// Saves without a footer are accepted for cartridges with a clock, it's then left as is.
+    pub fn import_ram_at(&mut self, data: &[u8], now: u64) -> Result<(), LoadError> {
+        let ram_len = self.mbc.ram().len();
+        let footer_sizes = self.mbc.clock_footer_sizes();
 
-        for addr in 0x134..=0x14C {
-            check_sum = check_sum
-                .wrapping_sub(self.mbc.read(addr) as i16)
-                .wrapping_sub(1);
+        let footer_len = data.len().wrapping_sub(ram_len);
+        let valid = data.len() == ram_len || footer_sizes.contains(&footer_len);
+        if !valid {
+            return Err(LoadError::InvalidSaveSize {
+                expected: ram_len + footer_sizes.first().copied().unwrap_or(0),
+                actual: data.len(),
+            });
         }
 
-        self.valid_checksum = (check_sum & 0xF) == (self.mbc.read(0x14D) & 0xF) as i16;
-        self.valid_checksum
+        let (ram, footer) = data.split_at(ram_len);
+        self.mbc.ram_mut().copy_from_slice(ram);
+        if !footer.is_empty() {
+            self.mbc.load_clock_footer(footer, now);
+        }
+
+        self.ram_dirty = false;
+        Ok(())
+    }
+
+    /// Returns true if the cartridge RAM may have changed since the last call
+    pub fn take_ram_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.ram_dirty)
     }
 }
 
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or(0)
+}
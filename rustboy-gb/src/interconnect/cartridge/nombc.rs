@@ -12,7 +12,8 @@ impl NoMbcState {
 
     pub fn read(&self, addr: u16) -> u8 {
         if addr < 0x8000 {
-            self.rom[addr as usize]
+            // Nothing drives the bus past the end of the rom (or without a cartridge)
+            self.rom.get(addr as usize).copied().unwrap_or(0xFF)
         } else {
             0xFF
         }
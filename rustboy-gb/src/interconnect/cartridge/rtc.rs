@@ -0,0 +1,220 @@
+use crate::constants::CLOCK_SPEED;
+
+use serde::{Deserialize, Serialize};
+
+/// Size of the RTC footer appended to save RAM, as written by BGB and VBA-M
+pub const RTC_FOOTER_SIZE: usize = 48;
+
+/// Older VBA versions store the timestamp as 32 bits
+pub const RTC_FOOTER_SIZE_32: usize = 44;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Bits of the day counter high register (0x0C)
+const DAY_HIGH_BIT: u8 = 0x01;
+const HALT_BIT: u8 = 0x40;
+const CARRY_BIT: u8 = 0x80;
+
+/// MBC3 real time clock
+///
+/// The clock counts emulated time: one second every `CLOCK_SPEED` T-cycles. Time that passed
+/// while the emulator wasn't running is added from the timestamp in the save footer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Rtc {
+    seconds: u8,
+    minutes: u8,
+    hours: u8,
+
+    // 9 bit day counter
+    days: u16,
+
+    halt: bool,
+
+    // Set when the day counter overflows, only cleared by the game
+    carry: bool,
+
+    // Registers (0x08 - 0x0C) as last latched, this is what the game reads
+    latched: [u8; 5],
+
+    // Last value written to 0x6000 - 0x7FFF, latching happens on a 0x00 -> 0x01 sequence
+    last_latch_write: u8,
+
+    // T-cycles since the last second
+    cycles: u32,
+}
+
+impl Rtc {
+    pub fn new() -> Rtc {
+        Rtc {
+            last_latch_write: 0xFF,
+            ..Rtc::default()
+        }
+    }
+
+    pub fn tick(&mut self, t_cycles: u32) {
+        if self.halt {
+            return;
+        }
+
+        self.cycles += t_cycles;
+        while self.cycles >= CLOCK_SPEED as u32 {
+            self.cycles -= CLOCK_SPEED as u32;
+            self.advance_second();
+        }
+    }
+
+    /// Advance the clock by whole seconds, used to catch up with time spent outside the emulator
+    pub fn advance_seconds(&mut self, mut seconds: u64) {
+        if self.halt {
+            return;
+        }
+
+        // Games can write out of range values, step until the counters are back in range
+        while seconds > 0 && !self.in_range() {
+            self.advance_second();
+            seconds -= 1;
+        }
+
+        let total = seconds
+            + u64::from(self.seconds)
+            + u64::from(self.minutes) * 60
+            + u64::from(self.hours) * 60 * 60
+            + u64::from(self.days) * SECONDS_PER_DAY;
+
+        let days = total / SECONDS_PER_DAY;
+        if days > 0x1FF {
+            self.carry = true;
+        }
+
+        self.days = (days & 0x1FF) as u16;
+        self.hours = ((total % SECONDS_PER_DAY) / 3600) as u8;
+        self.minutes = ((total % 3600) / 60) as u8;
+        self.seconds = (total % 60) as u8;
+    }
+
+    fn in_range(&self) -> bool {
+        self.seconds < 60 && self.minutes < 60 && self.hours < 24
+    }
+
+    // The counters are 6/6/5 bits wide and only carry into the next one when hitting exactly
+    // 60/60/24, out of range values written by the game wrap around without carrying
+    fn advance_second(&mut self) {
+        self.seconds = (self.seconds + 1) & 0x3F;
+        if self.seconds != 60 {
+            return;
+        }
+        self.seconds = 0;
+
+        self.minutes = (self.minutes + 1) & 0x3F;
+        if self.minutes != 60 {
+            return;
+        }
+        self.minutes = 0;
+
+        self.hours = (self.hours + 1) & 0x1F;
+        if self.hours != 24 {
+            return;
+        }
+        self.hours = 0;
+
+        self.days = (self.days + 1) & 0x1FF;
+        if self.days == 0 {
+            self.carry = true;
+        }
+    }
+
+    fn registers(&self) -> [u8; 5] {
+        [
+            self.seconds,
+            self.minutes,
+            self.hours,
+            self.days as u8,
+            self.day_high(),
+        ]
+    }
+
+    fn day_high(&self) -> u8 {
+        ((self.days >> 8) as u8 & DAY_HIGH_BIT)
+            | if self.halt { HALT_BIT } else { 0 }
+            | if self.carry { CARRY_BIT } else { 0 }
+    }
+
+    /// Write to 0x6000 - 0x7FFF
+    pub fn write_latch(&mut self, value: u8) {
+        if self.last_latch_write == 0x00 && value == 0x01 {
+            self.latched = self.registers();
+        }
+
+        self.last_latch_write = value;
+    }
+
+    /// Read the latched register selected with 0x08 - 0x0C
+    pub fn read(&self, register: usize) -> u8 {
+        match register {
+            0x08..=0x0C => self.latched[register - 0x08],
+            _ => 0xFF,
+        }
+    }
+
+    /// Write the live register selected with 0x08 - 0x0C
+    pub fn write(&mut self, register: usize, value: u8) {
+        match register {
+            0x08 => {
+                self.seconds = value & 0x3F;
+                self.cycles = 0;
+            }
+            0x09 => self.minutes = value & 0x3F,
+            0x0A => self.hours = value & 0x1F,
+            0x0B => self.days = (self.days & 0x100) | u16::from(value),
+            0x0C => {
+                self.days = (self.days & 0xFF) | (u16::from(value & DAY_HIGH_BIT) << 8);
+                self.halt = value & HALT_BIT != 0;
+                self.carry = value & CARRY_BIT != 0;
+            }
+            _ => (),
+        }
+
+        // Writes show up in the latched registers straight away
+        if (0x08..=0x0C).contains(&register) {
+            self.latched[register - 0x08] = self.registers()[register - 0x08];
+        }
+    }
+
+    /// Footer appended to save RAM: live and latched registers as 32 bit little endian values,
+    /// followed by the unix time it was written at as 64 bits
+    pub fn to_footer(&self, now: u64) -> [u8; RTC_FOOTER_SIZE] {
+        let mut footer = [0; RTC_FOOTER_SIZE];
+
+        let registers = self.registers();
+        for (i, value) in registers.iter().chain(self.latched.iter()).enumerate() {
+            footer[i * 4..i * 4 + 4].copy_from_slice(&u32::from(*value).to_le_bytes());
+        }
+
+        footer[40..48].copy_from_slice(&now.to_le_bytes());
+        footer
+    }
+
+    /// Restore from a 48 or 44 byte footer, adding the time that passed since it was written
+    pub fn load_footer(&mut self, footer: &[u8], now: u64) {
+        let register = |i: usize| footer[i * 4];
+
+        self.seconds = register(0) & 0x3F;
+        self.minutes = register(1) & 0x3F;
+        self.hours = register(2) & 0x1F;
+        self.days = u16::from(register(3)) | (u16::from(register(4) & DAY_HIGH_BIT) << 8);
+        self.halt = register(4) & HALT_BIT != 0;
+        self.carry = register(4) & CARRY_BIT != 0;
+
+        for i in 0..5 {
+            self.latched[i] = register(i + 5);
+        }
+
+        let timestamp = match footer.len() {
+            RTC_FOOTER_SIZE => u64::from_le_bytes(footer[40..48].try_into().unwrap()),
+            _ => u64::from(u32::from_le_bytes(footer[40..44].try_into().unwrap())),
+        };
+
+        self.cycles = 0;
+        self.advance_seconds(now.saturating_sub(timestamp));
+    }
+}
@@ -0,0 +1,83 @@
+#![allow(clippy::must_use_candidate)]
+use crate::constants::{HDMA1, HDMA2, HDMA3, HDMA4};
+
+use serde::{Deserialize, Serialize};
+
+/// CGB VRAM DMA (HDMA1 - HDMA5)
+///
+/// Copies 16 byte blocks into VRAM, either all at once (general purpose DMA) or one block per
+/// HBlank. The copying itself is done by the interconnect, which owns the memory map.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Hdma {
+    source: u16,
+    destination: u16,
+
+    // 16 byte blocks left to copy
+    remaining: u8,
+
+    // An HBlank transfer is running
+    hblank: bool,
+}
+
+impl Hdma {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(&mut self, addr: u16, value: u8) {
+        match addr {
+            HDMA1 => self.source = (self.source & 0x00FF) | (u16::from(value) << 8),
+            HDMA2 => self.source = (self.source & 0xFF00) | u16::from(value & 0xF0),
+            HDMA3 => {
+                self.destination = (self.destination & 0x00FF) | (u16::from(value & 0x1F) << 8)
+            }
+            HDMA4 => self.destination = (self.destination & 0xFF00) | u16::from(value & 0xF0),
+            _ => panic!("NOT AN HDMA REGISTER: {:#X}", addr),
+        }
+    }
+
+    /// HDMA5: blocks left minus one, bit 7 is set when no HBlank transfer is running
+    pub fn read_control(&self) -> u8 {
+        let remaining = self.remaining.wrapping_sub(1) & 0x7F;
+        if self.hblank {
+            remaining
+        } else {
+            0x80 | remaining
+        }
+    }
+
+    /// Write to HDMA5, returns true if a general purpose transfer should run right away
+    pub fn write_control(&mut self, value: u8) -> bool {
+        // Clearing bit 7 while an HBlank transfer runs stops it
+        if self.hblank && value & 0x80 == 0 {
+            self.hblank = false;
+            return false;
+        }
+
+        self.remaining = (value & 0x7F) + 1;
+        self.hblank = value & 0x80 != 0;
+        !self.hblank
+    }
+
+    pub fn hblank_active(&self) -> bool {
+        self.hblank
+    }
+
+    pub fn remaining(&self) -> u8 {
+        self.remaining
+    }
+
+    /// Source and VRAM destination of the next block, advancing to the one after
+    pub fn next_block(&mut self) -> (u16, u16) {
+        let block = (self.source, 0x8000 | self.destination);
+
+        self.source = self.source.wrapping_add(0x10);
+        self.destination = (self.destination + 0x10) & 0x1FF0;
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            self.hblank = false;
+        }
+
+        block
+    }
+}
@@ -0,0 +1,169 @@
+//! Infrared port
+//!
+//! Hudson carts (HuC1, HuC3) carry an IR LED and receiver for trading with another cart. The
+//! game turns the LED on and off and polls whether light is coming in, so all an `InfraredPort`
+//! has to do is pass the LED state to whatever is in front of it.
+use std::fmt::Debug;
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use log::{info, warn};
+
+use crate::interconnect::stream::{Listener, Stream};
+
+/// Whatever the IR port is pointed at
+pub trait InfraredPort: Debug + Send {
+    /// The LED was turned on or off
+    fn set_led(&mut self, on: bool);
+
+    /// True while light from the other end reaches the receiver
+    fn receiving(&self) -> bool;
+}
+
+/// Nothing in front of the port, no light ever comes in
+#[derive(Debug, Default)]
+pub struct NoInfrared;
+
+impl InfraredPort for NoInfrared {
+    fn set_led(&mut self, _on: bool) {}
+
+    fn receiving(&self) -> bool {
+        false
+    }
+}
+
+pub fn no_infrared() -> Box<dyn InfraredPort> {
+    Box::new(NoInfrared)
+}
+
+/// One of two ports facing each other, e.g. the carts of two instances in the same process,
+/// each seeing the LED of the other one
+#[derive(Debug)]
+pub struct InfraredLink {
+    led: Arc<AtomicBool>,
+    other_led: Arc<AtomicBool>,
+}
+
+impl InfraredLink {
+    pub fn pair() -> (InfraredLink, InfraredLink) {
+        let first = Arc::new(AtomicBool::new(false));
+        let second = Arc::new(AtomicBool::new(false));
+
+        (
+            InfraredLink {
+                led: first.clone(),
+                other_led: second.clone(),
+            },
+            InfraredLink {
+                led: second,
+                other_led: first,
+            },
+        )
+    }
+}
+
+impl InfraredPort for InfraredLink {
+    fn set_led(&mut self, on: bool) {
+        self.led.store(on, Ordering::Relaxed);
+    }
+
+    fn receiving(&self) -> bool {
+        self.other_led.load(Ordering::Relaxed)
+    }
+}
+
+/// Port facing another emulator over a socket, `host:port` or `unix:<path>` as for the link cable
+///
+/// Every LED change is sent as a byte, 1 for on and 0 for off. Unlike the link cable the ends
+/// don't run in lockstep, light from the other end shows up as soon as its byte arrives.
+#[derive(Debug)]
+pub struct InfraredSocket {
+    // None until the other end is there and once it's gone
+    stream: Arc<Mutex<Option<Stream>>>,
+    led: Arc<AtomicBool>,
+    other_led: Arc<AtomicBool>,
+}
+
+impl InfraredSocket {
+    fn new() -> InfraredSocket {
+        InfraredSocket {
+            stream: Arc::new(Mutex::new(None)),
+            led: Arc::new(AtomicBool::new(false)),
+            other_led: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Wait for the other end on `addr` in the background, the port sees nothing until then
+    pub fn listen(addr: &str) -> io::Result<InfraredSocket> {
+        info!("WAITING FOR INFRARED ON {}", addr);
+        let listener = Listener::bind(addr)?;
+        let port = Self::new();
+        port.spawn(move || listener.accept());
+        Ok(port)
+    }
+
+    /// Point the port at the other end listening on `addr`
+    pub fn connect(addr: &str) -> io::Result<InfraredSocket> {
+        let stream = Stream::connect(addr)?;
+        let port = Self::new();
+        port.spawn(move || Ok(stream));
+        Ok(port)
+    }
+
+    pub fn connected(&self) -> bool {
+        self.stream.lock().unwrap().is_some()
+    }
+
+    // Open the stream and keep reading the other end's LED from it
+    fn spawn(&self, open: impl FnOnce() -> io::Result<Stream> + Send + 'static) {
+        let shared = self.stream.clone();
+        let led = self.led.clone();
+        let other_led = self.other_led.clone();
+
+        thread::spawn(move || {
+            let result: io::Result<()> = open().and_then(|stream| {
+                let mut reader = stream.try_clone()?;
+                {
+                    // The other end starts out not knowing the LED state
+                    let mut shared = shared.lock().unwrap();
+                    let stream = shared.insert(stream);
+                    stream.write_all(&[u8::from(led.load(Ordering::Relaxed))])?;
+                }
+
+                let mut byte = [0];
+                loop {
+                    reader.read_exact(&mut byte)?;
+                    other_led.store(byte[0] != 0, Ordering::Relaxed);
+                }
+            });
+
+            if let Err(e) = result {
+                warn!("INFRARED DISCONNECTED: {}", e);
+            }
+            *shared.lock().unwrap() = None;
+            other_led.store(false, Ordering::Relaxed);
+        });
+    }
+}
+
+impl InfraredPort for InfraredSocket {
+    fn set_led(&mut self, on: bool) {
+        if self.led.swap(on, Ordering::Relaxed) == on {
+            return;
+        }
+
+        let mut stream = self.stream.lock().unwrap();
+        if let Some(writer) = stream.as_mut() {
+            if let Err(e) = writer.write_all(&[u8::from(on)]) {
+                warn!("INFRARED DISCONNECTED: {}", e);
+                *stream = None;
+            }
+        }
+    }
+
+    fn receiving(&self) -> bool {
+        self.other_led.load(Ordering::Relaxed)
+    }
+}
@@ -72,22 +72,23 @@ impl Joypad {
     }
 
     pub fn read(&self) -> u8 {
+        let mut keys = 0x0F;
+
+        // Selecting both groups ANDs them together
         if self.select_direction() == 0 {
-            return self.directions();
+            keys &= self.directions();
         }
 
         if self.select_action() == 0 {
-            return self.buttons();
+            keys &= self.buttons();
         }
 
-        0xFF
+        0xC0 | (self.select_action() << 5) | (self.select_direction() << 4) | keys
     }
 
     pub fn write(&mut self, value: u8) {
-        let select = (value & 0b0011_0000) >> 4;
-        let buttons = (!(select & 0b01)) & 1;
-        let directions = (!((select & 0b10) >> 1)) & 1;
-        self.set_select_action(buttons);
-        self.set_select_direction(directions);
+        // Only the select bits are writable, 0 selects a group
+        self.set_select_direction((value >> 4) & 1);
+        self.set_select_action((value >> 5) & 1);
     }
 }
@@ -1,4 +1,6 @@
 #![allow(clippy::must_use_candidate)]
+use crate::constants::{INTERRUPT_FLAG, OPRI, PCM12, PCM34, RP, WORK_RAM_BANK_SIZE};
+
 use serde::{Deserialize, Serialize};
 use serde_big_array::BigArray;
 
@@ -19,8 +21,12 @@ pub struct Mmu {
     #[serde(with = "BigArray")]
     external_ram: [u8; 0x2000],
 
+    // 8 banks of 4 KiB, only banks 0 and 1 are used in DMG mode
     #[serde(with = "BigArray")]
-    work_ram: [u8; 0x2000],
+    work_ram: [u8; 0x8000],
+
+    // Bank mapped to 0xD000 - 0xDFFF (SVBK), 0 selects bank 1
+    work_ram_bank: u8,
 
     interrupt_enable: u8,
 }
@@ -39,7 +45,8 @@ impl Mmu {
             io: [0; 0x80],
             high_ram: [0; 0x7F],
             external_ram: [0; 0x2000],
-            work_ram: [0; 0x2000],
+            work_ram: [0; 0x8000],
+            work_ram_bank: 1,
             interrupt_enable: 0,
         }
     }
@@ -52,8 +59,12 @@ impl Mmu {
         self.rom_bank[addr as usize] = value;
     }
 
-    pub fn write_io(&mut self, addr: u16, value: u8) {
-        self.io[addr as usize] = value;
+    /// Write one of the registers kept in `io`, unmapped registers and read only bits are ignored
+    pub fn write_io(&mut self, addr: u16, value: u8, cgb: bool) {
+        if let Some(mask) = io_mask(addr, cgb) {
+            let index = (addr - 0xFF00) as usize;
+            self.io[index] = (self.io[index] & !mask.writable) | (value & mask.writable);
+        }
     }
 
     pub fn write_hram(&mut self, addr: u16, value: u8) {
@@ -65,7 +76,25 @@ impl Mmu {
     }
 
     pub fn write_work_ram(&mut self, addr: u16, value: u8) {
-        self.work_ram[addr as usize] = value;
+        let index = self.work_ram_index(addr);
+        self.work_ram[index] = value;
+    }
+
+    pub fn set_work_ram_bank(&mut self, value: u8) {
+        self.work_ram_bank = (value & 0x07).max(1);
+    }
+
+    pub fn work_ram_bank(&self) -> u8 {
+        self.work_ram_bank
+    }
+
+    fn work_ram_index(&self, addr: u16) -> usize {
+        let addr = addr as usize;
+        if addr < WORK_RAM_BANK_SIZE {
+            addr
+        } else {
+            self.work_ram_bank as usize * WORK_RAM_BANK_SIZE + (addr - WORK_RAM_BANK_SIZE)
+        }
     }
 
     pub fn enable_interrupt(&mut self, value: u8) {
@@ -80,8 +109,12 @@ impl Mmu {
         self.rom_bank[addr as usize]
     }
 
-    pub fn read_io(&self, addr: u16) -> u8 {
-        self.io[addr as usize]
+    /// Read one of the registers kept in `io`, unused bits and unmapped registers read as 1
+    pub fn read_io(&self, addr: u16, cgb: bool) -> u8 {
+        match io_mask(addr, cgb) {
+            Some(mask) => self.io[(addr - 0xFF00) as usize] | mask.unused,
+            None => 0xFF,
+        }
     }
 
     pub fn read_hram(&self, addr: u16) -> u8 {
@@ -93,10 +126,42 @@ impl Mmu {
     }
 
     pub fn read_work_ram(&self, addr: u16) -> u8 {
-        self.work_ram[addr as usize]
+        self.work_ram[self.work_ram_index(addr)]
     }
 
     pub fn read_interrupt_enable(&self) -> u8 {
         self.interrupt_enable
     }
 }
+
+// Bits of an I/O register that always read as 1, and bits the CPU can change
+#[derive(Debug, Clone, Copy)]
+struct IoMask {
+    unused: u8,
+    writable: u8,
+}
+
+impl IoMask {
+    const fn new(unused: u8, writable: u8) -> Self {
+        Self { unused, writable }
+    }
+}
+
+// Registers in 0xFF00 - 0xFF7F that aren't owned by another component, None if nothing is mapped
+fn io_mask(addr: u16, cgb: bool) -> Option<IoMask> {
+    let mask = match addr {
+        INTERRUPT_FLAG => IoMask::new(0xE0, 0x1F),
+
+        // Infrared port, bit 1 is the (read only) received signal, 1 while there's none
+        RP if cgb => IoMask::new(0x3E, 0xC1),
+        OPRI if cgb => IoMask::new(0xFE, 0x01),
+        // Undocumented CGB registers
+        0xFF72..=0xFF74 if cgb => IoMask::new(0x00, 0xFF),
+        0xFF75 if cgb => IoMask::new(0x8F, 0x70),
+        // Digital outputs of the audio channels, read only
+        PCM12 | PCM34 if cgb => IoMask::new(0x00, 0x00),
+        _ => return None,
+    };
+
+    Some(mask)
+}
@@ -2,55 +2,228 @@
 
 pub mod apu;
 pub mod cartridge;
+pub mod hdma;
+pub mod infrared;
 pub mod joypad;
 mod mmu;
 pub mod ppu;
-mod serial;
+pub mod serial;
+pub mod sgb;
+mod stream;
 
 use log::debug;
+use log::info;
 use log::warn;
 
 use crate::constants::{
-    BOOT, EXTERNAL_RAM, HIGH_RAM, INTERRUPT_ENABLE, IO, LCD, OAM, ROM_BANK, TIMER, VRAM, WORK_RAM,
+    AUDIO, BCPD, BCPS, BOOT, BOOT_ROM_DISABLE, DIV, ECHO_RAM, EXTERNAL_RAM, HDMA1, HDMA4, HDMA5,
+    HIGH_RAM, INTERRUPT_ENABLE, IO, JOYPAD, KEY1, LCD, OAM, OCPD, OCPS, ROM_BANK, SERIAL,
+    SGB_X_RESOLUTION, SGB_Y_RESOLUTION, SVBK, TIMER, UNUSABLE, VBK, VRAM, WORK_RAM, X_RESOLUTION,
+    Y_RESOLUTION,
 };
 use crate::cpu::interrupts::request_interrupt;
 use crate::cpu::interrupts::InterruptType;
 use crate::cpu::timer::Timer;
+use crate::interconnect::apu::Apu;
+use crate::interconnect::hdma::Hdma;
 use crate::interconnect::joypad::Joypad;
 use crate::interconnect::mmu::Mmu;
-use crate::interconnect::ppu::Ppu;
-use crate::interconnect::serial::SerialOutput;
+use crate::interconnect::ppu::{OamAccess, Ppu, Rgb};
+use crate::interconnect::serial::Serial;
+use crate::interconnect::sgb::Sgb;
 
 use self::cartridge::Cartridge;
 use self::joypad::Key;
 
 use serde::{Deserialize, Serialize};
 
+/// Hardware being emulated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+pub enum Model {
+    #[default]
+    Dmg,
+    // A DMG inside a SNES, with palettes, a border and up to 4 controllers
+    Sgb,
+    Cgb,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Interconnect {
+    pub model: Model,
+
+    // CGB registers and colors are in use, off for DMG-only games running on a CGB
+    cgb_features: bool,
+
     pub cartridge: Cartridge,
     pub mmu: Mmu,
     pub timer: Timer,
     pub ppu: Ppu,
-    pub serial: SerialOutput,
+    pub apu: Apu,
+    pub serial: Serial,
     pub joypad: Joypad,
+    pub sgb: Sgb,
+    pub hdma: Hdma,
     pub boot_active: bool,
     pub write_enabled: bool,
     pub ticks: u64,
+
+    // Total T-cycles elapsed since power on, at normal speed
+    pub cycles: u64,
+
+    // CGB double speed mode (KEY1), the CPU and timer run twice as fast
+    pub double_speed: bool,
+    speed_switch_armed: bool,
 }
 
 impl Interconnect {
     pub fn new() -> Self {
         Self {
+            model: Model::Dmg,
+            cgb_features: false,
             cartridge: Cartridge::empty(),
             mmu: Mmu::new(),
             timer: Timer::new(),
             ppu: Ppu::new(),
-            serial: SerialOutput::new(),
+            apu: Apu::new(),
+            serial: Serial::new(),
             joypad: Joypad::init(),
+            sgb: Sgb::new(),
+            hdma: Hdma::new(),
             boot_active: true,
             write_enabled: true,
             ticks: 0,
+            cycles: 0,
+            double_speed: false,
+            speed_switch_armed: false,
+        }
+    }
+
+    pub fn set_model(&mut self, model: Model) {
+        self.model = model;
+        self.set_cgb_features(model == Model::Cgb);
+    }
+
+    /// Turn the CGB registers and colors off for a DMG-only game, the hardware stays a CGB
+    pub fn set_cgb_features(&mut self, enabled: bool) {
+        self.cgb_features = enabled;
+        self.ppu.cgb = enabled;
+    }
+
+    /// True when the game uses the CGB registers and colors
+    pub fn cgb_mode(&self) -> bool {
+        self.cgb_features
+    }
+
+    // CGB hardware, whether or not the game uses its features
+    fn cgb_hardware(&self) -> bool {
+        self.model == Model::Cgb
+    }
+
+    /// Size of the picture `screen` returns, the SGB draws a border around the game
+    pub fn screen_size(&self) -> (u32, u32) {
+        match self.model {
+            Model::Sgb => (u32::from(SGB_X_RESOLUTION), u32::from(SGB_Y_RESOLUTION)),
+            Model::Dmg | Model::Cgb => (u32::from(X_RESOLUTION), u32::from(Y_RESOLUTION)),
+        }
+    }
+
+    /// The picture to show, one row after the other
+    pub fn screen(&self) -> &[Rgb] {
+        match self.model {
+            Model::Sgb => &self.sgb.screen,
+            Model::Dmg | Model::Cgb => &self.ppu.video_buffer,
+        }
+    }
+
+    /// The CPU put `addr` on the bus, corrupting OAM if the PPU is scanning it (DMG and SGB only)
+    pub fn oam_bug(&mut self, addr: u16, access: OamAccess) {
+        if self.cgb_hardware() || !(0xFE00..=0xFEFF).contains(&addr) {
+            return;
+        }
+
+        self.ppu.corrupt_oam(access);
+    }
+
+    /// Called on STOP, switches between normal and double speed if it was requested through KEY1
+    pub fn speed_switch(&mut self) {
+        if !self.cgb_mode() || !self.speed_switch_armed {
+            return;
+        }
+
+        self.double_speed = !self.double_speed;
+        self.speed_switch_armed = false;
+        self.timer.timer_write(DIV, 0);
+        info!("DOUBLE SPEED: {}", self.double_speed);
+    }
+
+    fn write_cgb_register(&mut self, addr: u16, value: u8) {
+        match addr {
+            KEY1 => self.speed_switch_armed = value & 1 != 0,
+            VBK => self.ppu.set_vram_bank(value),
+            HDMA1..=HDMA4 => self.hdma.write(addr, value),
+            HDMA5 => {
+                if self.hdma.write_control(value) {
+                    self.general_dma();
+                }
+            }
+            BCPS => self.ppu.bg_color_palettes.write_spec(value),
+            BCPD => self.ppu.bg_color_palettes.write_data(value),
+            OCPS => self.ppu.sprite_color_palettes.write_spec(value),
+            OCPD => self.ppu.sprite_color_palettes.write_data(value),
+            SVBK => self.mmu.set_work_ram_bank(value),
+            _ => panic!("NOT A CGB REGISTER: {:#X}", addr),
+        }
+    }
+
+    fn read_cgb_register(&self, addr: u16) -> u8 {
+        match addr {
+            KEY1 => 0x7E | (u8::from(self.double_speed) << 7) | u8::from(self.speed_switch_armed),
+            VBK => 0xFE | self.ppu.vram_bank(),
+            HDMA1..=HDMA4 => 0xFF,
+            HDMA5 => self.hdma.read_control(),
+            BCPS => self.ppu.bg_color_palettes.read_spec(),
+            BCPD => self.ppu.bg_color_palettes.read_data(),
+            OCPS => self.ppu.sprite_color_palettes.read_spec(),
+            OCPD => self.ppu.sprite_color_palettes.read_data(),
+            SVBK => 0xF8 | self.mmu.work_ram_bank(),
+            _ => panic!("NOT A CGB REGISTER: {:#X}", addr),
+        }
+    }
+
+    // 0xFEA0 - 0xFEFF, blocked along with OAM during DMA. The DMG reads 0x00, the CGB (revision E)
+    // repeats the upper nibble of the low address byte
+    fn read_unusable(&self, addr: u16) -> u8 {
+        if self.ppu.dma_transferring() {
+            0xFF
+        } else if self.cgb_hardware() {
+            let nibble = (addr as u8) & 0xF0;
+            nibble | (nibble >> 4)
+        } else {
+            0x00
+        }
+    }
+
+    // Copy all remaining HDMA blocks at once, the CPU is stopped meanwhile
+    fn general_dma(&mut self) {
+        let blocks = u32::from(self.hdma.remaining());
+        while self.hdma.remaining() > 0 {
+            self.hdma_block();
+        }
+
+        self.emu_tick(self.hdma_stall(blocks));
+    }
+
+    // M-cycles the CPU is stopped for while `blocks` HDMA blocks are copied, 8 per block at
+    // normal speed and twice as many in double speed mode
+    fn hdma_stall(&self, blocks: u32) -> u32 {
+        blocks * if self.double_speed { 16 } else { 8 }
+    }
+
+    fn hdma_block(&mut self) {
+        let (source, destination) = self.hdma.next_block();
+        for i in 0..0x10 {
+            let value = self.read_mem(source.wrapping_add(i));
+            self.ppu.write_vram(destination + i, value);
         }
     }
 
@@ -64,6 +237,27 @@ impl Interconnect {
         request_interrupt(self, InterruptType::Joypad);
     }
 
+    /// Press a key on controller `player` (0 - 3), only the SGB reads more than the first
+    pub fn player_key_down(&mut self, player: usize, key: Key) {
+        match player {
+            0 => self.key_down(key),
+            _ => {
+                self.sgb.key_down(player, key);
+                request_interrupt(self, InterruptType::Joypad);
+            }
+        }
+    }
+
+    pub fn player_key_up(&mut self, player: usize, key: Key) {
+        match player {
+            0 => self.key_up(key),
+            _ => {
+                self.sgb.key_up(player, key);
+                request_interrupt(self, InterruptType::Joypad);
+            }
+        }
+    }
+
     pub fn log_timer(&self) {
         debug!(
             "DIV: {:#X} TIMA: {:#X} TMA: {:#X} TAC: {:#X}",
@@ -95,27 +289,45 @@ impl Interconnect {
                 self.mmu.write_rom_bank(addr, value);
             }
             */
-            self.cartridge.mbc.write(addr, value);
+            self.cartridge.write(addr, value);
         } else if VRAM.contains(&addr) {
             self.ppu.write_vram(addr, value);
         } else if EXTERNAL_RAM.contains(&addr) {
-            self.cartridge.mbc.write(addr, value);
+            self.cartridge.write(addr, value);
         } else if WORK_RAM.contains(&addr) {
             self.mmu.write_work_ram(addr - 0xC000, value);
+        } else if ECHO_RAM.contains(&addr) {
+            self.mmu.write_work_ram(addr - 0xE000, value);
         } else if OAM.contains(&addr) {
             if self.ppu.dma_transferring() {
                 return;
             }
             self.ppu.write_oam(addr, value);
+        } else if UNUSABLE.contains(&addr) {
+            // Writes go nowhere
+        } else if SERIAL.contains(&addr) {
+            self.serial.write(addr, value, self.cgb_mode());
         } else if TIMER.contains(&addr) {
             self.timer.timer_write(addr, value);
+        } else if AUDIO.contains(&addr) {
+            self.apu.write(addr, value);
         } else if LCD.contains(&addr) {
             self.ppu.write_lcd(addr, value);
         } else if IO.contains(&addr) {
-            if addr == 0xFF00 {
+            if addr == JOYPAD {
                 self.joypad.write(value);
+                if self.model == Model::Sgb {
+                    self.sgb.write(value);
+                }
+            } else if addr == BOOT_ROM_DISABLE {
+                // Once unmapped the boot rom stays unmapped
+                if value != 0 {
+                    self.boot_active = false;
+                }
+            } else if self.cgb_mode() && is_cgb_register(addr) {
+                self.write_cgb_register(addr, value);
             } else {
-                self.mmu.write_io(addr - 0xFF00, value);
+                self.mmu.write_io(addr, value, self.cgb_mode());
             }
         } else if HIGH_RAM.contains(&addr) {
             self.mmu.write_hram(addr - 0xFF80, value);
@@ -130,28 +342,41 @@ impl Interconnect {
         if self.boot_active && BOOT.contains(&addr) {
             self.mmu.read_boot(addr)
         } else if ROM_BANK.contains(&addr) {
-            self.cartridge.mbc.read(addr)
+            self.cartridge.read(addr)
         } else if VRAM.contains(&addr) {
             self.ppu.read_vram(addr)
         } else if EXTERNAL_RAM.contains(&addr) {
-            self.cartridge.mbc.read(addr)
+            self.cartridge.read(addr)
         } else if WORK_RAM.contains(&addr) {
             self.mmu.read_work_ram(addr - 0xC000)
+        } else if ECHO_RAM.contains(&addr) {
+            self.mmu.read_work_ram(addr - 0xE000)
         } else if OAM.contains(&addr) {
             if self.ppu.dma_transferring() {
                 0xFF
             } else {
                 self.ppu.read_oam(addr)
             }
+        } else if UNUSABLE.contains(&addr) {
+            self.read_unusable(addr)
+        } else if SERIAL.contains(&addr) {
+            self.serial.read(addr, self.cgb_mode())
         } else if TIMER.contains(&addr) {
             self.timer.timer_read(addr)
+        } else if AUDIO.contains(&addr) {
+            self.apu.read(addr)
         } else if LCD.contains(&addr) {
             self.ppu.read_lcd(addr)
         } else if IO.contains(&addr) {
-            if addr == 0xFF00 {
-                self.joypad.read()
+            if addr == JOYPAD {
+                match self.model {
+                    Model::Sgb => self.sgb.read(&self.joypad),
+                    Model::Dmg | Model::Cgb => self.joypad.read(),
+                }
+            } else if self.cgb_mode() && is_cgb_register(addr) {
+                self.read_cgb_register(addr)
             } else {
-                self.mmu.read_io(addr - 0xFF00)
+                self.mmu.read_io(addr, self.cgb_mode())
             }
         } else if HIGH_RAM.contains(&addr) {
             self.mmu.read_hram(addr - 0xFF80)
@@ -182,38 +407,57 @@ impl Interconnect {
         // Convert M cycles to T cycles
         let t_cycles = m_cycles * 4;
 
-        for _ in 0..t_cycles {
+        // The PPU and APU don't speed up in double speed mode
+        let dots = if self.double_speed {
+            t_cycles / 2
+        } else {
+            t_cycles
+        };
+
+        let mut hblank_blocks = 0;
+        for _ in 0..dots {
             let interrupts = self.ppu.tick();
 
+            if self.model == Model::Sgb && interrupts.contains(&InterruptType::VBlank) {
+                self.sgb.frame(&self.ppu);
+            }
+
             for int in interrupts {
                 request_interrupt(self, int);
             }
+
+            if self.ppu.take_hblank_started() && self.hdma.hblank_active() {
+                self.hdma_block();
+                hblank_blocks += 1;
+            }
+
+            self.apu.tick();
         }
 
         self.ticks = u64::from(t_cycles);
+        self.cycles += u64::from(dots);
 
-        let div_value: u8 = self.timer.div_clock.next(t_cycles) as u8;
-        self.timer.set_div(self.timer.div().wrapping_add(div_value));
-
-        let timer_enabled: bool = (self.timer.tac() & 0x04) != 0x00;
-        if timer_enabled {
-            let n = self.timer.tima_clock.next(t_cycles);
+        self.cartridge.tick(dots);
 
-            for _ in 0..n {
-                let tima_value = self.timer.tima().wrapping_add(1);
-                self.timer.set_tima(tima_value);
+        // The serial clock is derived from the system counter too
+        let counter = self.timer.system_counter();
+        if self.timer.tick(t_cycles) {
+            request_interrupt(self, InterruptType::Timer);
+        }
 
-                if self.timer.tima() == 0x00 {
-                    self.timer.set_tima(self.timer.tma());
-                    request_interrupt(self, InterruptType::Timer);
-                }
-            }
+        if self.serial.tick(counter, t_cycles) {
+            request_interrupt(self, InterruptType::Serial);
         }
 
         let dma_cycles = m_cycles;
         for _ in 0..dma_cycles {
             self.dma_tick();
         }
+
+        // HBlank blocks stop the CPU as long as general purpose ones do
+        if hblank_blocks > 0 {
+            self.emu_tick(self.hdma_stall(hblank_blocks));
+        }
     }
 
     pub fn dma_tick(&mut self) {
@@ -239,6 +483,13 @@ impl Interconnect {
     }
 }
 
+fn is_cgb_register(addr: u16) -> bool {
+    matches!(
+        addr,
+        KEY1 | VBK | HDMA1..=HDMA5 | BCPS | BCPD | OCPS | OCPD | SVBK
+    )
+}
+
 impl Default for Interconnect {
     fn default() -> Self {
         Self::new()
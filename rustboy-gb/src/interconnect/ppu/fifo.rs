@@ -0,0 +1,447 @@
+//! Pixel FIFO renderer
+//!
+//! Draws a scanline dot by dot during mode 3 the way the hardware does: a background/window
+//! fetcher fills a FIFO with 8 pixels at a time, sprites are fetched into a second FIFO when the
+//! LCD reaches their X position, and one pixel is shifted out to the LCD per dot. Registers are
+//! read while the line is being drawn, so mid-scanline writes show up where they happen and mode 3
+//! gets longer with SCX fine scrolling, the window and sprites.
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use super::{BgAttributes, Ppu, SpriteEntry};
+use crate::constants::X_RESOLUTION;
+
+/// How the PPU turns VRAM into pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Renderer {
+    /// Draw the whole line at once at the start of mode 3, fast but blind to mid-line changes
+    #[default]
+    Scanline,
+
+    /// Model the pixel FIFO and fetchers dot by dot
+    Fifo,
+}
+
+// Dots the background fetcher is paused for while a sprite is fetched
+const SPRITE_FETCH_DOTS: u8 = 6;
+
+// Extra dots before the fetcher starts on a new line, the first tile is fetched twice on
+// hardware. Puts mode 3 at 172 dots without scrolling, window or sprites
+const LINE_START_DOTS: u8 = 4;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct BgPixel {
+    color: u8,
+    palette: u8,
+
+    // BG map attribute priority (CGB only)
+    priority: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct SpritePixel {
+    color: u8,
+
+    // OBP0/OBP1 in DMG mode, color palette in CGB mode
+    palette: u8,
+
+    // Sprite is drawn behind BG colors 1-3
+    behind_bg: bool,
+    oam_index: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+enum FetcherStep {
+    #[default]
+    Tile,
+    DataLow,
+    DataHigh,
+    Push,
+}
+
+/// State of the pixel FIFO renderer for the line being drawn
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PixelFifo {
+    bg: VecDeque<BgPixel>,
+    sprites: VecDeque<SpritePixel>,
+
+    step: FetcherStep,
+
+    // Dots spent in the current step, fetching the tile and both data bytes take 2 each
+    step_dots: u8,
+
+    // Tile column the fetcher is on, relative to the start of the line or of the window
+    fetch_x: u8,
+
+    tile_number: u8,
+    attributes: u8,
+    data_low: u8,
+    data_high: u8,
+
+    // Next pixel position on the LCD
+    lcd_x: u8,
+
+    // Pixels still to be thrown away, SCX fine scroll or a window starting left of the screen
+    discard: u8,
+
+    fetching_window: bool,
+
+    // WY matched LY at some point this frame
+    window_y_triggered: bool,
+
+    // Internal window line counter, only advances on lines where the window was drawn
+    window_line: u8,
+
+    // Sprites found by the OAM scan that haven't been fetched yet, with their OAM index
+    line_sprites: Vec<(u8, SpriteEntry)>,
+
+    // Dots left before the background fetcher continues, at the start of a line or after a
+    // sprite fetch
+    stall: u8,
+}
+
+impl PixelFifo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Ppu {
+    /// Start of mode 3: scan OAM and reset the fetcher
+    pub(super) fn fifo_start_line(&mut self) {
+        let ly = self.ly();
+        let scroll_x = self.scroll_x();
+        let window_y = self.window_y();
+        let line_sprites = self.scan_oam();
+
+        let fifo = &mut self.fifo;
+        if ly == 0 {
+            fifo.window_y_triggered = false;
+            fifo.window_line = 0;
+        }
+        if ly == window_y {
+            fifo.window_y_triggered = true;
+        }
+
+        fifo.bg.clear();
+        fifo.sprites.clear();
+        fifo.step = FetcherStep::Tile;
+        fifo.step_dots = 0;
+        fifo.fetch_x = 0;
+        fifo.lcd_x = 0;
+        fifo.discard = scroll_x % 8;
+        fifo.fetching_window = false;
+        fifo.line_sprites = line_sprites;
+        fifo.stall = LINE_START_DOTS;
+    }
+
+    // The first 10 sprites overlapping this line, in OAM order
+    fn scan_oam(&self) -> Vec<(u8, SpriteEntry)> {
+        let sprite_size = self.sprite_size();
+        let ly = self.ly();
+
+        self.oam
+            .iter()
+            .enumerate()
+            .filter(|(_, sprite)| ly.wrapping_sub(sprite.y.wrapping_sub(16)) < sprite_size)
+            .take(10)
+            .map(|(index, sprite)| (index as u8, *sprite))
+            .collect()
+    }
+
+    fn sprite_size(&self) -> u8 {
+        if self.control().sprite_size() == 1 {
+            16
+        } else {
+            8
+        }
+    }
+
+    /// Advance mode 3 by one dot, returns true once all 160 pixels of the line are out
+    pub(super) fn fifo_tick(&mut self) -> bool {
+        if self.fifo.stall > 0 {
+            self.fifo.stall -= 1;
+            return false;
+        }
+
+        if self.start_window() {
+            return false;
+        }
+
+        if self.fetch_sprite() {
+            return false;
+        }
+
+        self.fetcher_step();
+
+        if self.fifo.bg.is_empty() {
+            return false;
+        }
+
+        let bg = self.fifo.bg.pop_front().unwrap();
+        if self.fifo.discard > 0 {
+            self.fifo.discard -= 1;
+            return false;
+        }
+
+        let sprite = self.fifo.sprites.pop_front().unwrap_or_default();
+        self.output_pixel(bg, sprite);
+
+        self.fifo.lcd_x += 1;
+        if self.fifo.lcd_x < X_RESOLUTION {
+            return false;
+        }
+
+        if self.fifo.fetching_window {
+            self.fifo.window_line = self.fifo.window_line.wrapping_add(1);
+        }
+
+        true
+    }
+
+    // Switch the fetcher over to the window when the LCD reaches WX
+    fn start_window(&mut self) -> bool {
+        let window_x = self.window_x();
+        let fifo = &self.fifo;
+
+        let reached = window_x <= 166 && u16::from(fifo.lcd_x) + 7 >= u16::from(window_x);
+        if fifo.fetching_window
+            || !fifo.window_y_triggered
+            || self.control().window_enable() == 0
+            || !reached
+        {
+            return false;
+        }
+
+        let fifo = &mut self.fifo;
+        fifo.fetching_window = true;
+        fifo.bg.clear();
+        fifo.step = FetcherStep::Tile;
+        fifo.step_dots = 0;
+        fifo.fetch_x = 0;
+
+        // A window left of the screen edge starts partway into its first tile
+        if fifo.lcd_x == 0 && window_x < 7 {
+            fifo.discard = 7 - window_x;
+        } else {
+            fifo.discard = 0;
+        }
+
+        true
+    }
+
+    // Fetch the next sprite starting at the current LCD position into the sprite FIFO
+    fn fetch_sprite(&mut self) -> bool {
+        if self.control().sprite_enable() == 0 {
+            return false;
+        }
+
+        let lcd_x = u16::from(self.fifo.lcd_x);
+        let position = self
+            .fifo
+            .line_sprites
+            .iter()
+            .position(|(_, sprite)| u16::from(sprite.x) <= lcd_x + 8);
+
+        let Some(position) = position else {
+            return false;
+        };
+        let (oam_index, sprite) = self.fifo.line_sprites.remove(position);
+
+        // Sprites at X = 0 are entirely off screen
+        if sprite.x == 0 {
+            return false;
+        }
+
+        let pixels = self.sprite_row(oam_index, sprite);
+        let skip = 8usize.saturating_sub(sprite.x as usize);
+
+        let cgb = self.cgb;
+        let fifo = &mut self.fifo;
+        for (slot, pixel) in pixels.into_iter().skip(skip).enumerate() {
+            match fifo.sprites.get_mut(slot) {
+                // On DMG the sprite fetched first wins, on CGB the one with the lower OAM index
+                Some(existing) => {
+                    let replace = existing.color == 0
+                        || (cgb && pixel.color != 0 && pixel.oam_index < existing.oam_index);
+                    if replace {
+                        *existing = pixel;
+                    }
+                }
+                None => fifo.sprites.push_back(pixel),
+            }
+        }
+
+        fifo.stall = SPRITE_FETCH_DOTS;
+        true
+    }
+
+    fn sprite_row(&self, oam_index: u8, sprite: SpriteEntry) -> [SpritePixel; 8] {
+        let sprite_size = self.sprite_size();
+        let flags = sprite.flags;
+
+        let mut tile_num = sprite.tile_index;
+        if sprite_size == 16 {
+            tile_num &= 0xFE;
+        }
+
+        let top = sprite.y.wrapping_sub(16);
+        let mut line = self.ly().wrapping_sub(top);
+        if flags.y_flip() == 1 {
+            line = sprite_size - line - 1;
+        }
+
+        if line >= 8 {
+            tile_num += 1;
+            line -= 8;
+        }
+
+        let bank = if self.cgb { flags.tile_vram_bank() } else { 0 };
+        let addr: u16 = 0x8000 + u16::from(tile_num) * 16 + u16::from(line) * 2;
+        let low = self.vram_at(bank, addr);
+        let high = self.vram_at(bank, addr + 1);
+
+        let palette = if self.cgb {
+            flags.palette_number_cgb()
+        } else {
+            flags.palette_number()
+        };
+
+        let mut pixels = [SpritePixel::default(); 8];
+        for (i, pixel) in pixels.iter_mut().enumerate() {
+            let bit = if flags.x_flip() == 1 { i } else { 7 - i };
+
+            *pixel = SpritePixel {
+                color: ((low >> bit) & 1) | (((high >> bit) & 1) << 1),
+                palette,
+                behind_bg: flags.bg_window() == 1,
+                oam_index,
+            };
+        }
+
+        pixels
+    }
+
+    // Run the background/window fetcher for one dot
+    fn fetcher_step(&mut self) {
+        if self.fifo.step == FetcherStep::Push {
+            if self.fifo.bg.is_empty() {
+                self.push_tile_row();
+                self.fifo.fetch_x = self.fifo.fetch_x.wrapping_add(1);
+                self.fifo.step = FetcherStep::Tile;
+            }
+            return;
+        }
+
+        self.fifo.step_dots += 1;
+        if self.fifo.step_dots < 2 {
+            return;
+        }
+        self.fifo.step_dots = 0;
+
+        match self.fifo.step {
+            FetcherStep::Tile => {
+                let addr = self.fetcher_map_addr();
+                self.fifo.tile_number = self.vram_at(0, addr);
+                self.fifo.attributes = if self.cgb { self.vram_at(1, addr) } else { 0 };
+                self.fifo.step = FetcherStep::DataLow;
+            }
+            FetcherStep::DataLow => {
+                let (bank, addr) = self.fetcher_data_addr();
+                self.fifo.data_low = self.vram_at(bank, addr);
+                self.fifo.step = FetcherStep::DataHigh;
+            }
+            FetcherStep::DataHigh => {
+                let (bank, addr) = self.fetcher_data_addr();
+                self.fifo.data_high = self.vram_at(bank, addr + 1);
+                self.fifo.step = FetcherStep::Push;
+            }
+            FetcherStep::Push => unreachable!(),
+        }
+    }
+
+    // Tile map entry of the tile being fetched
+    fn fetcher_map_addr(&self) -> u16 {
+        let fifo = &self.fifo;
+
+        let (map_addr, col, row) = if fifo.fetching_window {
+            (self.window_map_area(), fifo.fetch_x, fifo.window_line / 8)
+        } else {
+            let col = (self.scroll_x() / 8).wrapping_add(fifo.fetch_x);
+            let row = self.ly().wrapping_add(self.scroll_y()) / 8;
+            (self.bg_tile_map_addr(), col, row)
+        };
+
+        map_addr + u16::from(col & 31) + u16::from(row) * 32
+    }
+
+    // VRAM bank and address of the low byte of the tile row being fetched
+    fn fetcher_data_addr(&self) -> (u8, u16) {
+        let attributes = BgAttributes(self.fifo.attributes);
+
+        let line = if self.fifo.fetching_window {
+            self.fifo.window_line % 8
+        } else {
+            self.ly().wrapping_add(self.scroll_y()) % 8
+        };
+        let line = if attributes.y_flip() { 7 - line } else { line };
+
+        let mut tile_number = self.fifo.tile_number;
+        if self.bg_window_data_area() == 0x8800 {
+            tile_number = tile_number.wrapping_add(128);
+        }
+
+        let addr = self.bg_window_data_area() + u16::from(tile_number) * 16 + u16::from(line) * 2;
+        (attributes.tile_vram_bank(), addr)
+    }
+
+    fn push_tile_row(&mut self) {
+        let attributes = BgAttributes(self.fifo.attributes);
+        let (low, high) = (self.fifo.data_low, self.fifo.data_high);
+
+        for i in 0..8 {
+            let bit = if attributes.x_flip() { i } else { 7 - i };
+
+            self.fifo.bg.push_back(BgPixel {
+                color: ((low >> bit) & 1) | (((high >> bit) & 1) << 1),
+                palette: attributes.palette_number(),
+                priority: attributes.priority(),
+            });
+        }
+    }
+
+    // Mix a background and a sprite pixel and draw the result at the current LCD position
+    fn output_pixel(&mut self, mut bg: BgPixel, sprite: SpritePixel) {
+        let control = self.control();
+
+        // On DMG, LCDC bit 0 blanks the background and window
+        if !self.cgb && control.bg_window() == 0 {
+            bg = BgPixel::default();
+        }
+
+        let sprite_on_top = sprite.color != 0
+            && control.sprite_enable() == 1
+            && (bg.color == 0
+                || if self.cgb {
+                    control.bg_window() == 0 || (!sprite.behind_bg && !bg.priority)
+                } else {
+                    !sprite.behind_bg
+                });
+
+        let color = match (sprite_on_top, self.cgb) {
+            (true, true) => self
+                .sprite_color_palettes
+                .color(sprite.palette, sprite.color as usize),
+            (true, false) if sprite.palette == 1 => self.sprite1_palette[sprite.color as usize],
+            (true, false) => self.sprite0_palette[sprite.color as usize],
+            (false, true) => self.bg_color_palettes.color(bg.palette, bg.color as usize),
+            (false, false) => self.bg_palette[bg.color as usize],
+        };
+
+        if self.fifo.lcd_x < X_RESOLUTION {
+            let line_start = (X_RESOLUTION as usize) * (self.ly() as usize);
+            self.video_buffer[line_start + self.fifo.lcd_x as usize] = color;
+        }
+    }
+}
@@ -1,12 +1,20 @@
 #![allow(clippy::must_use_candidate)]
+mod fifo;
+mod oam_bug;
+
+pub use fifo::Renderer;
+pub use oam_bug::OamAccess;
+
 use crate::{constants::*, cpu::interrupts::InterruptType};
 use modular_bitfield::prelude::*;
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use wasm_bindgen::prelude::wasm_bindgen;
 
 use serde::{Serialize, Deserialize};
 use serde_big_array::BigArray;
 
+use self::fifo::PixelFifo;
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[wasm_bindgen]
 pub struct Rgb {
@@ -74,6 +82,86 @@ impl SpriteEntry {
     }
 }
 
+/// BG map attributes, stored in VRAM bank 1 at the same address as the tile number (CGB only)
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
+pub struct BgAttributes(u8);
+
+impl BgAttributes {
+    fn palette_number(self) -> u8 {
+        self.0 & 0x07
+    }
+
+    fn tile_vram_bank(self) -> u8 {
+        (self.0 >> 3) & 0x01
+    }
+
+    fn x_flip(self) -> bool {
+        self.0 & 0x20 != 0
+    }
+
+    fn y_flip(self) -> bool {
+        self.0 & 0x40 != 0
+    }
+
+    // BG over sprites, whatever the sprites' own priority says
+    fn priority(self) -> bool {
+        self.0 & 0x80 != 0
+    }
+}
+
+/// CGB color palette RAM, 8 palettes of 4 RGB555 colors, accessed through BCPS/BCPD or OCPS/OCPD
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ColorPalettes {
+    #[serde(with = "BigArray")]
+    data: [u8; 64],
+
+    // Index into data, bit 7 increments it after every write to the data register
+    spec: u8,
+}
+
+impl ColorPalettes {
+    fn new() -> Self {
+        Self {
+            data: [0xFF; 64],
+            spec: 0,
+        }
+    }
+
+    pub fn read_spec(&self) -> u8 {
+        self.spec | 0x40
+    }
+
+    pub fn write_spec(&mut self, value: u8) {
+        self.spec = value & 0xBF;
+    }
+
+    pub fn read_data(&self) -> u8 {
+        self.data[(self.spec & 0x3F) as usize]
+    }
+
+    pub fn write_data(&mut self, value: u8) {
+        let index = self.spec & 0x3F;
+        self.data[index as usize] = value;
+
+        if self.spec & 0x80 != 0 {
+            self.spec = 0x80 | (index.wrapping_add(1) & 0x3F);
+        }
+    }
+
+    pub fn color(&self, palette: u8, color_value: usize) -> Rgb {
+        let index = palette as usize * 8 + color_value * 2;
+        let color = u16::from_le_bytes([self.data[index], self.data[index + 1]]);
+
+        // Scale the 5 bit channels to 8 bits
+        let channel = |shift: u16| {
+            let value = ((color >> shift) & 0x1F) as u8;
+            (value << 3) | (value >> 2)
+        };
+
+        Rgb::new(channel(0), channel(5), channel(10))
+    }
+}
+
 #[derive(Debug, Copy, Clone, Deserialize, Serialize)]
 pub struct Dma {
     pub active: bool,
@@ -120,8 +208,21 @@ pub struct Status {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Ppu {
+    // Two banks in CGB mode, only bank 0 is used in DMG mode
     #[serde(with = "BigArray")]
-    vram: [u8; 0x2000],
+    vram: [u8; 0x4000],
+
+    // VRAM bank mapped to 0x8000 - 0x9FFF (VBK)
+    vram_bank: u8,
+
+    // Use the CGB attributes, color palettes and sprite priorities
+    pub cgb: bool,
+
+    pub renderer: Renderer,
+    fifo: PixelFifo,
+
+    // Renderer drawing the current line, switching `renderer` takes effect from the next line
+    line_renderer: Renderer,
 
     #[serde(with = "BigArray")]
     oam: [SpriteEntry; 40],
@@ -165,14 +266,33 @@ pub struct Ppu {
     pub sprite1_palette: [Rgb; 4],
     pub sprite1_palette_data: u8,
 
+    // Colors the 4 shades of BGP, OBP0 and OBP1 map to
+    pub shades: [[Rgb; 4]; 3],
+
+    // Color palettes (CGB mode only)
+    pub bg_color_palettes: ColorPalettes,
+    pub sprite_color_palettes: ColorPalettes,
+
     #[serde(with = "BigArray")]
     pub bg_priority: [bool; X_RESOLUTION as usize],
+
+    // BG map attribute priority bit of each pixel on the current line (CGB mode only)
+    #[serde(with = "BigArray")]
+    bg_attribute_priority: [bool; X_RESOLUTION as usize],
+
+    // Set when entering HBlank on a visible line, for HBlank DMA
+    hblank_started: bool,
 }
 
 impl Ppu {
     pub fn new() -> Self {
         let mut ppu = Self {
-            vram: [0; 0x2000],
+            vram: [0; 0x4000],
+            vram_bank: 0,
+            cgb: false,
+            renderer: Renderer::Scanline,
+            fifo: PixelFifo::new(),
+            line_renderer: Renderer::Scanline,
             oam: [SpriteEntry::new(); 40],
             dma: Dma::new(),
             line_ticks: 0,
@@ -196,7 +316,14 @@ impl Ppu {
 
             sprite1_palette: TILE_COLORS,
             sprite1_palette_data: 0,
+
+            shades: [TILE_COLORS; 3],
+            bg_color_palettes: ColorPalettes::new(),
+            sprite_color_palettes: ColorPalettes::new(),
+
             bg_priority: [false; X_RESOLUTION as usize],
+            bg_attribute_priority: [false; X_RESOLUTION as usize],
+            hblank_started: false,
         };
 
         ppu.set_stat_mode(LcdMode::Oam);
@@ -323,10 +450,20 @@ impl Ppu {
 
         *pal_data = palette_data;
 
-        palette_colors[0] = TILE_COLORS[(palette_data & 0b11) as usize];
-        palette_colors[1] = TILE_COLORS[((palette_data >> 2) & 0b11) as usize];
-        palette_colors[2] = TILE_COLORS[((palette_data >> 4) & 0b11) as usize];
-        palette_colors[3] = TILE_COLORS[((palette_data >> 6) & 0b11) as usize];
+        let shades = &self.shades[*palette_type as usize];
+        palette_colors[0] = shades[(palette_data & 0b11) as usize];
+        palette_colors[1] = shades[((palette_data >> 2) & 0b11) as usize];
+        palette_colors[2] = shades[((palette_data >> 4) & 0b11) as usize];
+        palette_colors[3] = shades[((palette_data >> 6) & 0b11) as usize];
+    }
+
+    /// Change the colors DMG palettes map to, e.g. to `CGB_COMPATIBILITY_SHADES`
+    pub fn set_shades(&mut self, shades: [[Rgb; 4]; 3]) {
+        self.shades = shades;
+
+        self.update_palette(&PaletteType::Background, self.bg_palette_data);
+        self.update_palette(&PaletteType::Sprite0, self.sprite0_palette_data);
+        self.update_palette(&PaletteType::Sprite1, self.sprite1_palette_data);
     }
 
     pub fn write_oam(&mut self, addr: u16, value: u8) {
@@ -366,11 +503,29 @@ impl Ppu {
     }
 
     pub fn write_vram(&mut self, addr: u16, value: u8) {
-        self.vram[(addr - 0x8000) as usize] = value;
+        let index = self.vram_bank as usize * VRAM_BANK_SIZE + (addr - 0x8000) as usize;
+        self.vram[index] = value;
     }
 
     pub fn read_vram(&self, addr: u16) -> u8 {
-        self.vram[(addr - 0x8000) as usize]
+        self.vram_at(self.vram_bank, addr)
+    }
+
+    fn vram_at(&self, bank: u8, addr: u16) -> u8 {
+        self.vram[bank as usize * VRAM_BANK_SIZE + (addr - 0x8000) as usize]
+    }
+
+    pub fn set_vram_bank(&mut self, value: u8) {
+        self.vram_bank = value & 1;
+    }
+
+    pub fn vram_bank(&self) -> u8 {
+        self.vram_bank
+    }
+
+    /// Returns true once after entering HBlank on a visible line
+    pub fn take_hblank_started(&mut self) -> bool {
+        std::mem::take(&mut self.hblank_started)
     }
 
     pub fn write_lcd(&mut self, addr: u16, value: u8) {
@@ -378,10 +533,15 @@ impl Ppu {
 
         match index {
             0x0 => self.control = Control::from_bytes([value]),
-            0x1 => self.status = Status::from_bytes([value]),
+            // The mode and LYC=LY bits are read only
+            0x1 => {
+                let value = (self.status.bytes[0] & 0x07) | (value & 0x78);
+                self.status = Status::from_bytes([value]);
+            }
             0x2 => self.scroll_y = value,
             0x3 => self.scroll_x = value,
-            0x4 => self.ly = value,
+            // LY is read only
+            0x4 => (),
             0x5 => self.lyc = value,
             0x6 => {
                 log::info!("DMA START");
@@ -407,7 +567,7 @@ impl Ppu {
 
         match index {
             0x0 => self.control.bytes[0],
-            0x1 => self.status.bytes[0],
+            0x1 => self.status.bytes[0] | 0x80,
             0x2 => self.scroll_y,
             0x3 => self.scroll_x,
             0x4 => self.ly,
@@ -493,6 +653,11 @@ impl Ppu {
         let oam_is_over = self.line_ticks() >= 80;
         if oam_is_over {
             self.set_stat_mode(LcdMode::Transfer);
+
+            self.line_renderer = self.renderer;
+            if self.line_renderer == Renderer::Fifo {
+                self.fifo_start_line();
+            }
         }
     }
 
@@ -501,8 +666,17 @@ impl Ppu {
     // Duration: 168-291 "dots", depends on sprite count
     //
     pub fn transfer_mode(&mut self, interrupts: &mut Vec<InterruptType>) {
-        self.draw_line();
+        match self.line_renderer {
+            Renderer::Scanline => self.draw_line(),
+            Renderer::Fifo => {
+                if !self.fifo_tick() {
+                    return;
+                }
+            }
+        }
+
         self.set_stat_mode(LcdMode::HBlank);
+        self.hblank_started = true;
         if self.stat().hblank_interrupt_soruce() == 1 {
             interrupts.push(InterruptType::LcdStat);
         }
@@ -562,43 +736,90 @@ impl Ppu {
         interrupts
     }
 
+    // Color value and attributes of the pixel at (map_x, map_y) of the background or window map
+    fn tile_pixel(&self, map_addr: u16, map_x: u8, map_y: u8) -> (usize, BgAttributes) {
+        let row: u8 = map_y / 8;
+        let col: u8 = map_x / 8;
+
+        let tile_map_addr: u16 = map_addr + (u16::from(col)) + (u16::from(row) * 32);
+        let mut tile_data: u8 = self.vram_at(0, tile_map_addr);
+        if self.bg_window_data_area() == 0x8800 {
+            tile_data = tile_data.wrapping_add(128);
+        }
+
+        let attributes = if self.cgb {
+            BgAttributes(self.vram_at(1, tile_map_addr))
+        } else {
+            BgAttributes::default()
+        };
+
+        let line = if attributes.y_flip() {
+            7 - map_y % 8
+        } else {
+            map_y % 8
+        };
+        let tile_number: u8 = line * 2;
+
+        let hi_addr: u16 = self.bg_window_data_area() + tile_data as u16 * 16 + tile_number as u16;
+        let low_addr: u16 = hi_addr + 1;
+        let hi: u8 = self.vram_at(attributes.tile_vram_bank(), hi_addr);
+        let low: u8 = self.vram_at(attributes.tile_vram_bank(), low_addr);
+
+        let bit = if attributes.x_flip() {
+            map_x % 8
+        } else {
+            7 - map_x % 8
+        };
+        let hi_bit = (hi >> bit) & 1;
+        let low_bit = ((low >> bit) & 1) << 1;
+
+        ((hi_bit | low_bit) as usize, attributes)
+    }
+
+    fn draw_bg_pixel(&mut self, x: usize, color_value: usize, attributes: BgAttributes) {
+        let color = if self.cgb {
+            self.bg_color_palettes
+                .color(attributes.palette_number(), color_value)
+        } else {
+            self.bg_palette[color_value]
+        };
+
+        self.bg_priority[x] = color_value != 0;
+        self.bg_attribute_priority[x] = attributes.priority();
+
+        let line_start = (X_RESOLUTION as usize) * (self.ly() as usize);
+        self.video_buffer[line_start + x] = color;
+    }
+
+    // Whether a non transparent sprite pixel is drawn over the background at x
+    fn sprite_visible(&self, x: usize, flags: SpriteFlags) -> bool {
+        if !self.bg_priority[x] {
+            return true;
+        }
+
+        if self.cgb {
+            // In CGB mode LCDC bit 0 turns off every BG priority instead of the background
+            self.control().bg_window() == 0
+                || (flags.bg_window() == 0 && !self.bg_attribute_priority[x])
+        } else {
+            flags.bg_window() == 0
+        }
+    }
+
     pub fn draw_line(&mut self) {
         let slice_start = (X_RESOLUTION as usize) * (self.ly() as usize);
         let slice_end = (X_RESOLUTION as usize) + slice_start;
 
-        let background_on = self.control().bg_window() == 1;
+        let background_on = self.control().bg_window() == 1 || self.cgb;
         if background_on {
             let map_y: u8 = self.ly().wrapping_add(self.scroll_y());
-            let row: u8 = map_y / 8;
 
             for x_pos in 0..X_RESOLUTION {
                 let map_x: u8 = x_pos.wrapping_add(self.scroll_x());
-                let col: u8 = map_x / 8;
-                let tile_number: u8 = (map_y % 8) * 2;
-
-                let tile_data_addr: u16 =
-                    self.bg_tile_map_addr() + (u16::from(col)) + (u16::from(row) * 32);
-                let mut tile_data: u8 = self.vram[(tile_data_addr - 0x8000) as usize];
-                if self.bg_window_data_area() == 0x8800 {
-                    tile_data = tile_data.wrapping_add(128);
-                }
 
-                let hi_addr: u16 =
-                    self.bg_window_data_area() + tile_data as u16 * 16 + tile_number as u16;
-                let low_addr: u16 =
-                    self.bg_window_data_area() + tile_data as u16 * 16 + tile_number as u16 + 1;
-                let hi: u8 = self.vram[(hi_addr - 0x8000) as usize];
-                let low: u8 = self.vram[(low_addr - 0x8000) as usize];
-
-                let bit = (map_x % 8).wrapping_sub(7).wrapping_mul(0xFF) as usize;
-                let hi_bit = (hi >> bit) & 1;
-                let low_bit = ((low >> bit) & 1) << 1;
-                let color_value = (hi_bit | low_bit) as usize;
-                let color = self.bg_palette[color_value];
-                self.bg_priority[x_pos as usize] = color_value != 0;
-
-                let pixels = &mut self.video_buffer[slice_start..slice_end];
-                pixels[x_pos as usize] = color;
+                let (color_value, attributes) =
+                    self.tile_pixel(self.bg_tile_map_addr(), map_x, map_y);
+                self.draw_bg_pixel(x_pos as usize, color_value, attributes);
             }
         }
 
@@ -608,7 +829,6 @@ impl Ppu {
             let window_x = self.window_x().wrapping_sub(7);
 
             let map_y = self.ly() - self.window_y();
-            let row = map_y / 8;
 
             for i in (window_x as usize)..X_RESOLUTION as usize {
                 let mut map_x = (i as u8).wrapping_add(self.scroll_x());
@@ -616,34 +836,10 @@ impl Ppu {
                 if map_x >= window_x {
                     map_x = i as u8 - window_x;
                 }
-                let col = map_x / 8;
 
-                let tile_data_addr: u16 =
-                    self.window_map_area() + (u16::from(col)) + (u16::from(row) * 32);
-                let mut tile_data: u8 = self.vram[(tile_data_addr - 0x8000) as usize];
-                if self.bg_window_data_area() == 0x8800 {
-                    tile_data = tile_data.wrapping_add(128);
-                }
-
-                let tile_number = (map_y % 8) * 2;
-
-                let hi_addr: u16 =
-                    self.bg_window_data_area() + tile_data as u16 * 16 + tile_number as u16;
-                let low_addr: u16 =
-                    self.bg_window_data_area() + tile_data as u16 * 16 + tile_number as u16 + 1;
-
-                let hi = self.vram[(hi_addr - 0x8000) as usize];
-                let low = self.vram[(low_addr - 0x8000) as usize];
-
-                let bit = (map_x % 8).wrapping_sub(7).wrapping_mul(0xFF) as usize;
-                let hi_bit = (hi >> bit) & 1;
-                let low_bit = ((low >> bit) & 1) << 1;
-                let color_value = (hi_bit | low_bit) as usize;
-                let color = self.bg_palette[color_value];
-                self.bg_priority[i] = color_value != 0;
-
-                let pixels = &mut self.video_buffer[slice_start..slice_end];
-                pixels[i] = color;
+                let (color_value, attributes) =
+                    self.tile_pixel(self.window_map_area(), map_x, map_y);
+                self.draw_bg_pixel(i, color_value, attributes);
             }
         }
 
@@ -676,14 +872,19 @@ impl Ppu {
                 }
             }
 
-            sprites_to_draw.sort_by(|&(a_index, a), &(b_index, b)| {
-                match a.x.cmp(&b.x) {
-                    // If X coordinates are the same, use OAM index as priority (low index => draw last)
-                    Ordering::Equal => a_index.cmp(&b_index).reverse(),
-                    // Use X coordinate as priority (low X => draw last)
-                    other => other.reverse(),
-                }
-            });
+            if self.cgb {
+                // Only the OAM index counts in CGB mode (low index => draw last)
+                sprites_to_draw.sort_by_key(|&(index, _)| Reverse(index));
+            } else {
+                sprites_to_draw.sort_by(|&(a_index, a), &(b_index, b)| {
+                    match a.x.cmp(&b.x) {
+                        // If X coordinates are the same, use OAM index as priority (low index => draw last)
+                        Ordering::Equal => a_index.cmp(&b_index).reverse(),
+                        // Use X coordinate as priority (low X => draw last)
+                        other => other.reverse(),
+                    }
+                });
+            }
 
             for (_, sprite) in sprites_to_draw {
                 let palette = if sprite.flags.palette_number() == 1 {
@@ -711,10 +912,16 @@ impl Ppu {
                 }
                 line *= 2;
 
+                let bank = if self.cgb {
+                    sprite.flags.tile_vram_bank()
+                } else {
+                    0
+                };
+
                 let hi_addr: u16 = 0x8000 + tile_num as u16 * 16 + (line) as u16;
                 let low_addr: u16 = 0x8000 + tile_num as u16 * 16 + (line) as u16 + 1;
-                let hi = self.vram[(hi_addr - 0x8000) as usize];
-                let low = self.vram[(low_addr - 0x8000) as usize];
+                let hi = self.vram_at(bank, hi_addr);
+                let low = self.vram_at(bank, low_addr);
 
                 for x in (0..8).rev() {
                     let bit = if sprite.flags.x_flip() == 1 { 7 - x } else { x } as usize;
@@ -722,13 +929,19 @@ impl Ppu {
                     let hi_bit = (hi >> bit) & 1;
                     let low_bit = ((low >> bit) & 1) << 1;
                     let color_value = (hi_bit | low_bit) as usize;
-                    let color = palette[color_value];
+                    let color = if self.cgb {
+                        self.sprite_color_palettes
+                            .color(sprite.flags.palette_number_cgb(), color_value)
+                    } else {
+                        palette[color_value]
+                    };
                     let target_x = sprite.x.wrapping_add(7 - x);
-                    if target_x < X_RESOLUTION && color_value != 0 {
-                        if sprite.flags.bg_window() == 0 || !self.bg_priority[target_x as usize] {
-                            let pixels = &mut self.video_buffer[slice_start..slice_end];
-                            pixels[target_x as usize] = color;
-                        }
+                    if target_x < X_RESOLUTION
+                        && color_value != 0
+                        && self.sprite_visible(target_x as usize, sprite.flags)
+                    {
+                        let pixels = &mut self.video_buffer[slice_start..slice_end];
+                        pixels[target_x as usize] = color;
                     }
                 }
             }
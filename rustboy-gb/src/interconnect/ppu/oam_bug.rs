@@ -0,0 +1,88 @@
+//! DMG OAM corruption bug
+//!
+//! While the PPU scans OAM in mode 2 it reads one 8 byte row per M-cycle. If the CPU puts an
+//! address in 0xFE00 - 0xFEFF on the bus at the same time, the row being scanned gets mixed with
+//! the one before it. Which bits end up where depends on the kind of access, see
+//! <https://gbdev.io/pandocs/OAM_Corruption_Bug.html>. The first row is never corrupted.
+use serde::{Deserialize, Serialize};
+
+use super::{LcdMode, Ppu};
+
+// OAM is scanned as 20 rows of 4 16 bit words
+const OAM_ROWS: usize = 20;
+
+/// Kind of CPU bus access that corrupts OAM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OamAccess {
+    /// Reads, including opcode and operand fetches
+    Read,
+
+    /// Writes, and INC rr / DEC rr / PUSH putting a register on the address bus
+    Write,
+
+    /// A read of the register that is incremented or decremented in the same M-cycle,
+    /// e.g. LD A, (HL+) and POP
+    ReadIncrease,
+}
+
+impl Ppu {
+    /// Row of OAM the PPU is reading right now, None outside of mode 2
+    fn scanned_oam_row(&self) -> Option<usize> {
+        let scanning =
+            self.control().lcd_ppu_enable() == 1 && matches!(self.stat_mode(), LcdMode::Oam);
+        if !scanning {
+            return None;
+        }
+
+        Some((self.line_ticks() as usize / 4).min(OAM_ROWS - 1))
+    }
+
+    fn oam_word(&self, row: usize, word: usize) -> u16 {
+        let addr = 0xFE00 + (row * 8 + word * 2) as u16;
+        u16::from_le_bytes([self.read_oam(addr), self.read_oam(addr + 1)])
+    }
+
+    fn set_oam_word(&mut self, row: usize, word: usize, value: u16) {
+        let addr = 0xFE00 + (row * 8 + word * 2) as u16;
+        let [low, high] = value.to_le_bytes();
+        self.write_oam(addr, low);
+        self.write_oam(addr + 1, high);
+    }
+
+    fn copy_oam_row(&mut self, from: usize, to: usize, words: std::ops::Range<usize>) {
+        for word in words {
+            self.set_oam_word(to, word, self.oam_word(from, word));
+        }
+    }
+
+    /// Corrupt OAM for a CPU access to 0xFE00 - 0xFEFF, does nothing unless the PPU is in mode 2
+    pub fn corrupt_oam(&mut self, access: OamAccess) {
+        let row = match self.scanned_oam_row() {
+            Some(row) if row > 0 => row,
+            _ => return,
+        };
+
+        if access == OamAccess::ReadIncrease && (4..OAM_ROWS - 1).contains(&row) {
+            let a = self.oam_word(row - 2, 0);
+            let b = self.oam_word(row - 1, 0);
+            let c = self.oam_word(row, 0);
+            let d = self.oam_word(row - 1, 2);
+
+            self.set_oam_word(row - 1, 0, (b & (a | c | d)) | (a & c & d));
+            self.copy_oam_row(row - 1, row, 0..4);
+            self.copy_oam_row(row - 1, row - 2, 0..4);
+        }
+
+        let a = self.oam_word(row, 0);
+        let b = self.oam_word(row - 1, 0);
+        let c = self.oam_word(row - 1, 2);
+
+        let first_word = match access {
+            OamAccess::Write => ((a ^ c) & (b ^ c)) ^ c,
+            OamAccess::Read | OamAccess::ReadIncrease => b | (a & c),
+        };
+
+        self.set_oam_word(row, 0, first_word);
+        self.copy_oam_row(row - 1, row, 1..4);
+    }
+}
@@ -0,0 +1,240 @@
+//! Serial port (SB / SC)
+//!
+//! A transfer shifts SB out one bit at a time, most significant bit first, while the bits coming
+//! from the other end are shifted in. With the internal clock a bit is shifted on every falling
+//! edge of bit 8 of the system counter (8192 Hz), or bit 3 with the CGB fast clock (262144 Hz).
+//! With the external clock the Game Boy waits for the other end to drive the transfer. Either way
+//! SC bit 7 is cleared and the serial interrupt requested once 8 bits went through.
+mod printer;
+mod socket;
+
+pub use printer::Printer;
+pub use socket::SocketLink;
+
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{SERIAL_TRANSFER_CONTROL, SERIAL_TRASFER_DATA};
+
+// Bits shifted per transfer
+const TRANSFER_BITS: u8 = 8;
+
+// System counter bit clocking the serial port, normal and CGB fast clock
+const CLOCK_BIT: u32 = 8;
+const FAST_CLOCK_BIT: u32 = 3;
+
+/// Whatever is plugged into the link port
+pub trait LinkCable: Debug + Send {
+    /// The Game Boy drives the clock: `byte` is sent to the other end, returns the byte shifted in
+    /// from it
+    fn transfer(&mut self, byte: u8) -> u8;
+
+    /// The other end drives the clock, polled at 8192 Hz while a transfer waits for it. Returns the
+    /// byte it shifted in once it did, `byte` is what it got back
+    fn external_transfer(&mut self, _byte: u8) -> Option<u8> {
+        None
+    }
+
+    /// `t_cycles` went by with `data` in SB, lets a link to another emulator keep in step with it
+    fn tick(&mut self, _t_cycles: u32, _data: u8) {}
+}
+
+/// Nothing plugged in, the data line is pulled up and the external clock never ticks
+#[derive(Debug, Default)]
+pub struct Disconnected;
+
+impl LinkCable for Disconnected {
+    fn transfer(&mut self, _byte: u8) -> u8 {
+        0xFF
+    }
+}
+
+fn disconnected() -> Box<dyn LinkCable> {
+    Box::new(Disconnected)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Serial {
+    /// Serial Transfer Data (R/W), the shift register
+    data: u8,
+
+    /// Serial Transfer Control (R/W) - bit 7 transfer in progress, bit 1 fast clock (CGB),
+    /// bit 0 internal clock
+    control: u8,
+
+    // Bits left to shift in the current transfer with the internal clock
+    bits_left: u8,
+
+    // Byte received from the other end, shifted into SB bit by bit
+    incoming: u8,
+
+    // Not part of save states, the link has to be plugged in again after loading one
+    #[serde(skip, default = "disconnected")]
+    link: Box<dyn LinkCable>,
+
+    // Something other than `Disconnected` is plugged in
+    #[serde(skip)]
+    linked: bool,
+
+    // Every byte sent, kept for whoever wants to see them (test roms print their results)
+    buffer: Vec<u8>,
+
+    // Print bytes to stdout as they are sent, otherwise keep them until taken
+    echo: bool,
+}
+
+impl Serial {
+    pub fn new() -> Serial {
+        Serial {
+            data: 0,
+            control: 0,
+            bits_left: 0,
+            incoming: 0xFF,
+            link: disconnected(),
+            linked: false,
+            buffer: Vec::new(),
+            echo: true,
+        }
+    }
+
+    /// Plug something into the link port, replacing what was there
+    pub fn connect(&mut self, link: Box<dyn LinkCable>) {
+        self.link = link;
+        self.linked = true;
+    }
+
+    pub fn disconnect(&mut self) {
+        self.link = disconnected();
+        self.linked = false;
+    }
+
+    /// Print sent bytes to stdout instead of keeping them, only while nothing is plugged in
+    pub fn set_echo(&mut self, echo: bool) {
+        self.echo = echo;
+    }
+
+    pub fn read_bytes(&self) -> Vec<u8> {
+        self.buffer.clone()
+    }
+
+    pub fn take_bytes(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.buffer)
+    }
+
+    fn transferring(&self) -> bool {
+        self.control & 0x80 != 0
+    }
+
+    fn internal_clock(&self) -> bool {
+        self.control & 0x01 != 0
+    }
+
+    pub fn read(&self, addr: u16, cgb: bool) -> u8 {
+        match addr {
+            SERIAL_TRASFER_DATA => self.data,
+            SERIAL_TRANSFER_CONTROL if cgb => self.control | 0x7C,
+            SERIAL_TRANSFER_CONTROL => self.control | 0x7E,
+            _ => panic!("{:#X}: NOT A SERIAL ADDRESS", addr),
+        }
+    }
+
+    pub fn write(&mut self, addr: u16, value: u8, cgb: bool) {
+        match addr {
+            SERIAL_TRASFER_DATA => self.data = value,
+            SERIAL_TRANSFER_CONTROL => {
+                // The clock speed bit only exists on the CGB
+                self.control = value & if cgb { 0x83 } else { 0x81 };
+
+                if self.transferring() && self.internal_clock() {
+                    self.send(self.data);
+                    self.incoming = self.link.transfer(self.data);
+                    self.bits_left = TRANSFER_BITS;
+                }
+            }
+            _ => panic!("{:#X}: NOT A SERIAL ADDRESS", addr),
+        }
+    }
+
+    /// Advance by `t_cycles`, `counter` being the system counter before they elapsed. Returns true
+    /// if the serial interrupt should be requested
+    pub fn tick(&mut self, counter: u16, t_cycles: u32) -> bool {
+        self.link.tick(t_cycles, self.data);
+
+        if !self.transferring() {
+            return false;
+        }
+
+        if !self.internal_clock() {
+            return self.tick_external(counter, t_cycles);
+        }
+
+        let bit = if self.control & 0x02 != 0 {
+            FAST_CLOCK_BIT
+        } else {
+            CLOCK_BIT
+        };
+
+        for _ in 0..falling_edges(counter, t_cycles, bit) {
+            self.data = (self.data << 1) | (self.incoming >> 7);
+            self.incoming <<= 1;
+            self.bits_left -= 1;
+
+            if self.bits_left == 0 {
+                self.control &= 0x7F;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn tick_external(&mut self, counter: u16, t_cycles: u32) -> bool {
+        if falling_edges(counter, t_cycles, CLOCK_BIT) == 0 {
+            return false;
+        }
+
+        match self.link.external_transfer(self.data) {
+            Some(byte) => {
+                self.send(self.data);
+                self.data = byte;
+                self.control &= 0x7F;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn send(&mut self, byte: u8) {
+        self.buffer.push(byte);
+
+        if !self.echo {
+            return;
+        }
+
+        // With nothing plugged in it's usually a test rom printing, bytes for a device (printer
+        // packets, another Game Boy) aren't text
+        if self.linked {
+            self.buffer.clear();
+        } else {
+            self.output();
+        }
+    }
+
+    fn output(&mut self) {
+        print!("{}", String::from_utf8_lossy(&self.buffer));
+        self.buffer.clear();
+    }
+}
+
+impl Default for Serial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Falling edges of `bit` while the counter goes from `counter` to `counter + t_cycles`
+fn falling_edges(counter: u16, t_cycles: u32, bit: u32) -> u32 {
+    let start = u32::from(counter);
+    ((start + t_cycles) >> (bit + 1)) - (start >> (bit + 1))
+}
@@ -0,0 +1,365 @@
+//! Game Boy Printer
+//!
+//! The Game Boy drives the clock and sends the printer packets:
+//!
+//! | Magic       | Command | Compression | Length  | Data         | Checksum | Reply         |
+//! |-------------|---------|-------------|---------|--------------|----------|---------------|
+//! | 0x88 0x33   | 1 byte  | 1 byte      | 2 bytes | Length bytes | 2 bytes  | 2 bytes       |
+//!
+//! The printer answers 0x00 to every byte except the last two, where it answers 0x81 (it's
+//! alive) and its status. Image data is 2bpp tiles, 20 to a row, and may be run length encoded.
+//! Printed strips are put together on a page until the paper is fed past them, then the page is
+//! saved to `print-NNNN.png` in the output directory.
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use log::{error, info, warn};
+
+use super::LinkCable;
+use crate::constants::{TILE_COLORS, X_RESOLUTION};
+use crate::interconnect::ppu::Rgb;
+use crate::screenshot::Image;
+
+const MAGIC: [u8; 2] = [0x88, 0x33];
+const ALIVE: u8 = 0x81;
+
+// Commands
+const INITIALIZE: u8 = 0x01;
+const PRINT: u8 = 0x02;
+const DATA: u8 = 0x04;
+const STATUS: u8 = 0x0F;
+
+// Status bits
+const CHECKSUM_ERROR: u8 = 0x01;
+const PRINTING: u8 = 0x02;
+const IMAGE_DATA_FULL: u8 = 0x04;
+const UNPROCESSED_DATA: u8 = 0x08;
+
+// 9 data packets, a whole screen
+const BUFFER_SIZE: usize = 0x1680;
+
+// 20 tiles of 16 bytes
+const TILE_ROW_BYTES: usize = 320;
+const WIDTH: usize = X_RESOLUTION as usize;
+
+// Pixel rows the paper is fed by per margin line
+const MARGIN_ROWS: usize = 8;
+
+// Status inquiries the printer stays busy for after a print command
+const PRINT_INQUIRIES: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Magic(usize),
+    Command,
+    Compression,
+    LengthLow,
+    LengthHigh,
+    Data,
+    ChecksumLow,
+    ChecksumHigh,
+    Alive,
+    Status,
+}
+
+#[derive(Debug)]
+pub struct Printer {
+    output_dir: PathBuf,
+    state: State,
+
+    // Packet being received
+    command: u8,
+    compressed: bool,
+    length: usize,
+    data: Vec<u8>,
+    checksum: u16,
+    received_checksum: u16,
+
+    status: u8,
+
+    // Status inquiries left until a print is done
+    printing: u8,
+
+    // Decompressed image data waiting for a print command
+    buffer: Vec<u8>,
+
+    // Shades of the strips printed since the paper was last fed, `WIDTH` per row
+    page: Vec<u8>,
+}
+
+impl Printer {
+    /// Printer saving the pages it prints to `output_dir`, created when the first one comes out
+    pub fn new(output_dir: impl Into<PathBuf>) -> Printer {
+        Printer {
+            output_dir: output_dir.into(),
+            state: State::Magic(0),
+            command: 0,
+            compressed: false,
+            length: 0,
+            data: Vec::new(),
+            checksum: 0,
+            received_checksum: 0,
+            status: 0,
+            printing: 0,
+            buffer: Vec::new(),
+            page: Vec::new(),
+        }
+    }
+
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
+    fn receive(&mut self, byte: u8) -> u8 {
+        let mut reply = 0x00;
+
+        // Everything between the magic bytes and the checksum counts
+        if matches!(
+            self.state,
+            State::Command
+                | State::Compression
+                | State::LengthLow
+                | State::LengthHigh
+                | State::Data
+        ) {
+            self.checksum = self.checksum.wrapping_add(u16::from(byte));
+        }
+
+        self.state = match self.state {
+            State::Magic(i) if byte == MAGIC[i] => {
+                if i + 1 < MAGIC.len() {
+                    State::Magic(i + 1)
+                } else {
+                    self.checksum = 0;
+                    State::Command
+                }
+            }
+            State::Magic(_) if byte == MAGIC[0] => State::Magic(1),
+            State::Magic(_) => State::Magic(0),
+            State::Command => {
+                self.command = byte;
+                State::Compression
+            }
+            State::Compression => {
+                self.compressed = byte & 0x01 != 0;
+                State::LengthLow
+            }
+            State::LengthLow => {
+                self.length = usize::from(byte);
+                State::LengthHigh
+            }
+            State::LengthHigh => {
+                self.length |= usize::from(byte) << 8;
+                self.data.clear();
+
+                if self.length == 0 {
+                    State::ChecksumLow
+                } else {
+                    State::Data
+                }
+            }
+            State::Data => {
+                self.data.push(byte);
+
+                if self.data.len() == self.length {
+                    State::ChecksumLow
+                } else {
+                    State::Data
+                }
+            }
+            State::ChecksumLow => {
+                self.received_checksum = u16::from(byte);
+                State::ChecksumHigh
+            }
+            State::ChecksumHigh => {
+                self.received_checksum |= u16::from(byte) << 8;
+                State::Alive
+            }
+            State::Alive => {
+                reply = ALIVE;
+                self.execute();
+                State::Status
+            }
+            State::Status => {
+                reply = self.status;
+                State::Magic(0)
+            }
+        };
+
+        reply
+    }
+
+    fn execute(&mut self) {
+        if self.checksum != self.received_checksum {
+            warn!(
+                "PRINTER CHECKSUM MISMATCH: {:#X} != {:#X}",
+                self.checksum, self.received_checksum
+            );
+            self.status |= CHECKSUM_ERROR;
+            return;
+        }
+
+        self.status &= !CHECKSUM_ERROR;
+
+        match self.command {
+            INITIALIZE => {
+                self.buffer.clear();
+                self.status = 0;
+                self.printing = 0;
+            }
+            DATA => {
+                // An empty data packet marks the end of the data
+                if self.data.is_empty() {
+                    return;
+                }
+
+                let data = if self.compressed {
+                    decompress(&self.data)
+                } else {
+                    self.data.clone()
+                };
+                self.buffer.extend(data);
+                self.buffer.truncate(BUFFER_SIZE);
+
+                self.status |= UNPROCESSED_DATA;
+                if self.buffer.len() >= BUFFER_SIZE {
+                    self.status |= IMAGE_DATA_FULL;
+                }
+            }
+            PRINT if self.data.len() == 4 => {
+                self.print(self.data[0], self.data[1], self.data[2]);
+                self.status = (self.status & !(UNPROCESSED_DATA | IMAGE_DATA_FULL)) | PRINTING;
+                self.printing = PRINT_INQUIRIES;
+            }
+            STATUS => {
+                if self.printing > 0 {
+                    self.printing -= 1;
+                    if self.printing == 0 {
+                        self.status &= !PRINTING;
+                    }
+                }
+            }
+            _ => warn!("UNKNOWN PRINTER COMMAND: {:#X}", self.command),
+        }
+    }
+
+    // The exposure (4th byte) only changes how dark the paper gets, it's ignored
+    fn print(&mut self, sheets: u8, margins: u8, palette: u8) {
+        let before = usize::from(margins >> 4);
+        let after = usize::from(margins & 0x0F);
+
+        self.feed(before);
+
+        // No sheets only feeds the paper, every copy comes out the same so only one is kept
+        if sheets > 0 {
+            let strip = decode_tiles(&self.buffer, palette);
+            self.page.extend(strip);
+        }
+        self.buffer.clear();
+
+        if after > 0 {
+            self.feed(after);
+
+            if let Err(e) = self.save_page() {
+                error!("COULD NOT SAVE PRINT: {}", e);
+            }
+            self.page.clear();
+        }
+    }
+
+    fn feed(&mut self, lines: usize) {
+        // Blank paper before anything was printed is left out
+        if !self.page.is_empty() {
+            self.page.extend(vec![0; lines * MARGIN_ROWS * WIDTH]);
+        }
+    }
+
+    fn save_page(&self) -> Result<()> {
+        if self.page.is_empty() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.output_dir)?;
+        let path = next_free_path(&self.output_dir);
+
+        let image = Image {
+            width: WIDTH as u32,
+            height: (self.page.len() / WIDTH) as u32,
+            pixels: self
+                .page
+                .iter()
+                .map(|&shade| TILE_COLORS[usize::from(shade)])
+                .collect::<Vec<Rgb>>(),
+        };
+        image.save_png(&path)?;
+
+        info!("PRINTED {}", path.display());
+        Ok(())
+    }
+}
+
+impl LinkCable for Printer {
+    fn transfer(&mut self, byte: u8) -> u8 {
+        self.receive(byte)
+    }
+}
+
+// Control byte with bit 7 set: the next byte repeated (control & 0x7F) + 2 times,
+// otherwise (control + 1) bytes as they are
+fn decompress(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        let control = data[i];
+        i += 1;
+
+        if control & 0x80 != 0 {
+            let count = usize::from(control & 0x7F) + 2;
+            if let Some(&byte) = data.get(i) {
+                out.resize(out.len() + count, byte);
+            }
+            i += 1;
+        } else {
+            let count = usize::from(control) + 1;
+            let end = (i + count).min(data.len());
+            out.extend_from_slice(&data[i..end]);
+            i = end;
+        }
+    }
+
+    out
+}
+
+// Shades of 2bpp tile data laid out 20 tiles to a row, `WIDTH` per pixel row
+fn decode_tiles(data: &[u8], palette: u8) -> Vec<u8> {
+    // Games that don't care send 0, which prints like the usual 0xE4
+    let palette = if palette == 0 { 0xE4 } else { palette };
+
+    let tile_rows = data.len() / TILE_ROW_BYTES;
+    let mut shades = Vec::with_capacity(tile_rows * 8 * WIDTH);
+
+    for tile_row in data.chunks_exact(TILE_ROW_BYTES) {
+        for y in 0..8 {
+            for tile in tile_row.chunks_exact(16) {
+                let low = tile[y * 2];
+                let high = tile[y * 2 + 1];
+
+                for bit in (0..8).rev() {
+                    let color = (((high >> bit) & 1) << 1) | ((low >> bit) & 1);
+                    shades.push((palette >> (color * 2)) & 0x03);
+                }
+            }
+        }
+    }
+
+    shades
+}
+
+fn next_free_path(dir: &Path) -> PathBuf {
+    (1..)
+        .map(|n| dir.join(format!("print-{:04}.png", n)))
+        .find(|path| !path.exists())
+        .unwrap()
+}
@@ -0,0 +1,138 @@
+//! Link cable to another emulator over a TCP or Unix domain socket
+//!
+//! Both ends run in lockstep: every `SYNC_INTERVAL` T-cycles each one sends what is in its SB and
+//! the bytes it started transfers with since the last sync, then waits for the same from the
+//! other end. Everything crossing the cable is applied at these syncs, so what the games see only
+//! depends on emulated time and transfers come out the same on every run.
+//!
+//! The price is latency. A transfer started with the internal clock receives the other end's SB
+//! as of the last sync, and its byte reaches the other end at the next one, where it waits until
+//! a transfer with the external clock takes it.
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+
+use log::{info, warn};
+
+use super::LinkCable;
+use crate::interconnect::stream::{Listener, Stream};
+
+// T-cycles between syncs, one transfer with the internal clock at 8192 Hz
+const SYNC_INTERVAL: u32 = 4096;
+
+#[derive(Debug)]
+pub struct SocketLink {
+    // None once the other end is gone, the cable then acts as if it was unplugged
+    stream: Option<Stream>,
+
+    // T-cycles left until the next sync
+    until_sync: u32,
+
+    // SB as of the last tick
+    data: u8,
+
+    // Bytes transfers were started with since the last sync
+    sent: Vec<u8>,
+
+    // SB of the other end as of the last sync
+    peer_data: u8,
+
+    // Bytes the other end started transfers with, waiting for a transfer with the external clock
+    received: VecDeque<u8>,
+}
+
+impl SocketLink {
+    fn new(stream: Stream) -> SocketLink {
+        SocketLink {
+            stream: Some(stream),
+            until_sync: SYNC_INTERVAL,
+            data: 0,
+            sent: Vec::new(),
+            peer_data: 0xFF,
+            received: VecDeque::new(),
+        }
+    }
+
+    pub fn tcp(stream: TcpStream) -> io::Result<SocketLink> {
+        Ok(Self::new(Stream::tcp(stream)?))
+    }
+
+    #[cfg(unix)]
+    pub fn unix(stream: UnixStream) -> SocketLink {
+        Self::new(Stream::Unix(stream))
+    }
+
+    /// Wait for the other end to connect to `addr`, either `host:port` or `unix:<path>`
+    pub fn listen(addr: &str) -> io::Result<SocketLink> {
+        info!("WAITING FOR LINK CABLE ON {}", addr);
+        Ok(Self::new(Listener::bind(addr)?.accept()?))
+    }
+
+    /// Connect to the other end listening on `addr`, either `host:port` or `unix:<path>`
+    pub fn connect(addr: &str) -> io::Result<SocketLink> {
+        Ok(Self::new(Stream::connect(addr)?))
+    }
+
+    pub fn connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    fn sync(&mut self) {
+        let Some(stream) = &mut self.stream else {
+            return;
+        };
+
+        // Count, SB, then the bytes
+        let count = self.sent.len().min(usize::from(u8::MAX));
+        let mut message = vec![count as u8, self.data];
+        message.extend(self.sent.drain(..count));
+
+        let reply = stream.write_all(&message).and_then(|_| {
+            let mut header = [0; 2];
+            stream.read_exact(&mut header)?;
+
+            let mut bytes = vec![0; usize::from(header[0])];
+            stream.read_exact(&mut bytes)?;
+            Ok((header[1], bytes))
+        });
+
+        match reply {
+            Ok((peer_data, bytes)) => {
+                self.peer_data = peer_data;
+                self.received.extend(bytes);
+            }
+            Err(e) => {
+                warn!("LINK CABLE DISCONNECTED: {}", e);
+                self.stream = None;
+                self.peer_data = 0xFF;
+            }
+        }
+    }
+}
+
+impl LinkCable for SocketLink {
+    fn transfer(&mut self, byte: u8) -> u8 {
+        self.sent.push(byte);
+        self.peer_data
+    }
+
+    fn external_transfer(&mut self, _byte: u8) -> Option<u8> {
+        // The other end got this side's SB at the sync its byte came with
+        self.received.pop_front()
+    }
+
+    fn tick(&mut self, t_cycles: u32, data: u8) {
+        self.data = data;
+
+        let mut t_cycles = t_cycles;
+        while t_cycles >= self.until_sync {
+            t_cycles -= self.until_sync;
+            self.until_sync = SYNC_INTERVAL;
+            self.sync();
+        }
+
+        self.until_sync -= t_cycles;
+    }
+}
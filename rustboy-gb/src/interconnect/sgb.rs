@@ -0,0 +1,604 @@
+//! Super Game Boy
+//!
+//! Games talk to the SGB by pulsing P14 and P15 of the joypad register. Both low starts a
+//! packet, then every bit is a pulse followed by both going back high: P14 low is a 0 and P15
+//! low is a 1. A packet is 16 bytes sent LSB first and a 0 stop bit. The low 3 bits of the first
+//! byte say how many packets the command takes, the upper 5 bits which command it is.
+//!
+//! The SGB colors the 4 shades the game screen is drawn with using one of its 4 palettes, picked
+//! per 8x8 cell by the attribute map. Bigger chunks of data (border tiles and map, palettes and
+//! attribute files) are sent by drawing them to the screen: the next frame is read out of VRAM
+//! through the first 256 entries of the background map, 20 to a row.
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    SGB_BUFFER_SIZE, SGB_X_RESOLUTION, SGB_Y_RESOLUTION, TILE_COLORS, X_RESOLUTION, Y_RESOLUTION,
+};
+use crate::interconnect::joypad::{Joypad, Key};
+use crate::interconnect::ppu::{Ppu, Rgb};
+
+// Commands
+const PAL01: u8 = 0x00;
+const PAL23: u8 = 0x01;
+const PAL03: u8 = 0x02;
+const PAL12: u8 = 0x03;
+const ATTR_BLK: u8 = 0x04;
+const ATTR_LIN: u8 = 0x05;
+const ATTR_DIV: u8 = 0x06;
+const ATTR_CHR: u8 = 0x07;
+const PAL_SET: u8 = 0x0A;
+const PAL_TRN: u8 = 0x0B;
+const MLT_REQ: u8 = 0x11;
+const CHR_TRN: u8 = 0x13;
+const PCT_TRN: u8 = 0x14;
+const ATTR_TRN: u8 = 0x15;
+const ATTR_SET: u8 = 0x16;
+const MASK_EN: u8 = 0x17;
+
+const PACKET_SIZE: usize = 16;
+const PACKET_BITS: usize = PACKET_SIZE * 8;
+
+// Bytes read out of VRAM by a transfer, 256 tiles
+const TRANSFER_SIZE: usize = 0x1000;
+
+// The attribute map has a palette for every 8x8 cell of the game screen
+const ATTRIBUTE_WIDTH: usize = X_RESOLUTION as usize / 8;
+const ATTRIBUTE_HEIGHT: usize = Y_RESOLUTION as usize / 8;
+const ATTRIBUTE_CELLS: usize = ATTRIBUTE_WIDTH * ATTRIBUTE_HEIGHT;
+
+// 45 attribute files of 90 bytes, 4 cells to a byte
+const ATTRIBUTE_FILES: usize = 45;
+const ATTRIBUTE_FILE_SIZE: usize = ATTRIBUTE_CELLS / 4;
+
+// 512 palettes of 4 colors set by PAL_TRN
+const SYSTEM_PALETTES: usize = 512;
+
+// The border is a 32x28 map of 4bpp SNES tiles, 256 of 32 bytes
+const BORDER_TILES: usize = 256;
+const BORDER_TILE_SIZE: usize = 32;
+const BORDER_MAP_WIDTH: usize = SGB_X_RESOLUTION as usize / 8;
+const BORDER_MAP_HEIGHT: usize = SGB_Y_RESOLUTION as usize / 8;
+
+// Where the border palettes start in PCT_TRN data, after a 32x32 map
+const BORDER_PALETTES: usize = 0x800;
+
+// Top left of the game screen inside the border
+const SCREEN_X: usize = 48;
+const SCREEN_Y: usize = 40;
+
+// Palettes before the game sets any (RGB555)
+const DEFAULT_PALETTE: [u16; 4] = [0x67BF, 0x265B, 0x10B5, 0x2866];
+
+/// What the game screen shows, set by MASK_EN
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mask {
+    None,
+    // Keep showing the last frame
+    Freeze,
+    Black,
+    // Fill it with color 0
+    Color0,
+}
+
+// Data to read out of VRAM on the next frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Transfer {
+    Palettes,
+    Attributes,
+    // Border tiles 0x00 - 0x7F or 0x80 - 0xFF
+    Tiles(usize),
+    Border,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Sgb {
+    // Commands are only listened to if the game's header says it supports the SGB
+    pub supported: bool,
+
+    // P14 and P15 as last written, 0 is low
+    select: u8,
+
+    // Packet being received, `ready` once both lines went back high after a pulse
+    receiving: bool,
+    ready: bool,
+    bits: usize,
+    packet: [u8; PACKET_SIZE],
+
+    // Packets of the command being received
+    command: Vec<u8>,
+
+    // Controllers, the game's joypad is player 1 and the other 3 are here
+    players: usize,
+    player: usize,
+    pads: [Joypad; 3],
+
+    // RGB555 colors, color 0 is shared by all 4 palettes
+    palettes: [[u16; 4]; 4],
+    system_palettes: Vec<u16>,
+    attributes: Vec<u8>,
+    attribute_files: Vec<u8>,
+
+    pub mask: Mask,
+    transfer: Option<Transfer>,
+
+    border_tiles: Vec<u8>,
+    border_map: Vec<u16>,
+    border_palettes: [[u16; 16]; 4],
+
+    // The game screen inside the border, `SGB_X_RESOLUTION` pixels per row
+    #[serde(skip, default = "blank_screen")]
+    pub screen: Vec<Rgb>,
+}
+
+impl Sgb {
+    pub fn new() -> Sgb {
+        Sgb {
+            supported: true,
+            select: 0b11,
+            receiving: false,
+            ready: false,
+            bits: 0,
+            packet: [0; PACKET_SIZE],
+            command: Vec::new(),
+            players: 1,
+            player: 0,
+            pads: [Joypad::init(); 3],
+            palettes: [DEFAULT_PALETTE; 4],
+            system_palettes: vec![0; SYSTEM_PALETTES * 4],
+            attributes: vec![0; ATTRIBUTE_CELLS],
+            attribute_files: vec![0; ATTRIBUTE_FILES * ATTRIBUTE_FILE_SIZE],
+            mask: Mask::None,
+            transfer: None,
+            border_tiles: vec![0; BORDER_TILES * BORDER_TILE_SIZE],
+            border_map: vec![0; BORDER_MAP_WIDTH * BORDER_MAP_HEIGHT],
+            border_palettes: [[0; 16]; 4],
+            screen: blank_screen(),
+        }
+    }
+
+    /// Controller the joypad register reads, 0 - 3
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    /// Press a key on player 2, 3 or 4's controller (1 - 3), other players are ignored
+    pub fn key_down(&mut self, player: usize, key: Key) {
+        if let Some(pad) = self.pad(player) {
+            pad.key_down(key);
+        }
+    }
+
+    pub fn key_up(&mut self, player: usize, key: Key) {
+        if let Some(pad) = self.pad(player) {
+            pad.key_up(key);
+        }
+    }
+
+    fn pad(&mut self, player: usize) -> Option<&mut Joypad> {
+        player.checked_sub(1).and_then(|pad| self.pads.get_mut(pad))
+    }
+
+    /// The joypad register, `joypad` being player 1's controller
+    pub fn read(&self, joypad: &Joypad) -> u8 {
+        // With nothing selected the register holds the current controller
+        if self.players > 1 && self.select == 0b11 {
+            return 0xF0 | (0x0F - self.player as u8);
+        }
+
+        match self.player {
+            0 => joypad.read(),
+            player => self.pads[player - 1].read(),
+        }
+    }
+
+    pub fn write(&mut self, value: u8) {
+        let select = (value >> 4) & 0b11;
+        let previous = self.select;
+        self.select = select;
+
+        for pad in &mut self.pads {
+            pad.write(value);
+        }
+
+        match select {
+            // Reset pulse, a packet starts
+            0b00 => {
+                self.receiving = true;
+                self.ready = false;
+                self.bits = 0;
+                self.packet = [0; PACKET_SIZE];
+            }
+            0b11 => {
+                self.ready = true;
+
+                // P15 going high switches to the next controller, unless it was a 1 bit
+                if previous & 0b10 == 0 && !self.receiving {
+                    self.player = (self.player + 1) % self.players;
+                }
+            }
+            _ if self.receiving && self.ready => {
+                self.ready = false;
+                self.receive_bit(select == 0b01);
+            }
+            _ => (),
+        }
+    }
+
+    fn receive_bit(&mut self, bit: bool) {
+        if self.bits < PACKET_BITS {
+            self.packet[self.bits / 8] |= u8::from(bit) << (self.bits % 8);
+            self.bits += 1;
+            return;
+        }
+
+        // Stop bit
+        self.receiving = false;
+        if bit {
+            warn!("SGB PACKET WITHOUT STOP BIT");
+            return;
+        }
+
+        self.command.extend(self.packet);
+
+        let packets = usize::from(self.command[0] & 0x07).max(1);
+        if self.command.len() >= packets * PACKET_SIZE {
+            let command = std::mem::take(&mut self.command);
+            if self.supported {
+                self.execute(&command);
+            }
+        }
+    }
+
+    fn execute(&mut self, data: &[u8]) {
+        let command = data[0] >> 3;
+        debug!("SGB COMMAND: {:#X}", command);
+
+        match command {
+            PAL01 => self.set_palettes(0, 1, data),
+            PAL23 => self.set_palettes(2, 3, data),
+            PAL03 => self.set_palettes(0, 3, data),
+            PAL12 => self.set_palettes(1, 2, data),
+            ATTR_BLK => self.attribute_blocks(data),
+            ATTR_LIN => self.attribute_lines(data),
+            ATTR_DIV => self.attribute_divide(data),
+            ATTR_CHR => self.attribute_cells(data),
+            PAL_SET => {
+                for (i, palette) in data[1..9].chunks_exact(2).enumerate() {
+                    let number = usize::from(u16::from_le_bytes([palette[0], palette[1]]) & 0x1FF);
+                    let colors = &self.system_palettes[number * 4..number * 4 + 4];
+                    self.palettes[i].copy_from_slice(colors);
+                }
+                self.share_color0();
+
+                if data[9] & 0x80 != 0 {
+                    self.apply_attribute_file(usize::from(data[9] & 0x3F));
+                }
+                if data[9] & 0x40 != 0 {
+                    self.mask = Mask::None;
+                }
+            }
+            PAL_TRN => self.transfer = Some(Transfer::Palettes),
+            MLT_REQ => {
+                self.players = match data[1] & 0b11 {
+                    0b01 => 2,
+                    0b11 => 4,
+                    _ => 1,
+                };
+                self.player = 0;
+            }
+            CHR_TRN => self.transfer = Some(Transfer::Tiles(usize::from(data[1] & 1))),
+            PCT_TRN => self.transfer = Some(Transfer::Border),
+            ATTR_TRN => self.transfer = Some(Transfer::Attributes),
+            ATTR_SET => {
+                self.apply_attribute_file(usize::from(data[1] & 0x3F));
+                if data[1] & 0x40 != 0 {
+                    self.mask = Mask::None;
+                }
+            }
+            MASK_EN => {
+                self.mask = match data[1] & 0b11 {
+                    0 => Mask::None,
+                    1 => Mask::Freeze,
+                    2 => Mask::Black,
+                    _ => Mask::Color0,
+                };
+            }
+            // Sound, the SNES side and the rest aren't emulated
+            _ => debug!("UNSUPPORTED SGB COMMAND: {:#X}", command),
+        }
+    }
+
+    // Color 0 followed by colors 1 - 3 of both palettes
+    fn set_palettes(&mut self, first: usize, second: usize, data: &[u8]) {
+        let color = |i: usize| u16::from_le_bytes([data[1 + i * 2], data[2 + i * 2]]);
+
+        self.palettes[0][0] = color(0);
+        for i in 1..4 {
+            self.palettes[first][i] = color(i);
+            self.palettes[second][i] = color(i + 3);
+        }
+        self.share_color0();
+    }
+
+    fn share_color0(&mut self) {
+        let color0 = self.palettes[0][0];
+        for palette in &mut self.palettes {
+            palette[0] = color0;
+        }
+    }
+
+    fn set_attribute(&mut self, x: usize, y: usize, palette: u8) {
+        if x < ATTRIBUTE_WIDTH && y < ATTRIBUTE_HEIGHT {
+            self.attributes[y * ATTRIBUTE_WIDTH + x] = palette & 0b11;
+        }
+    }
+
+    fn attribute_blocks(&mut self, data: &[u8]) {
+        let count = usize::from(data[1]);
+
+        for block in data[2..].chunks_exact(6).take(count) {
+            let control = block[0] & 0b111;
+            let inside = block[1] & 0b11;
+            let outside = (block[1] >> 4) & 0b11;
+
+            // Changing only the inside or the outside takes the line around it along
+            let (control, line) = match control {
+                0b001 => (0b011, inside),
+                0b100 => (0b110, outside),
+                _ => (control, (block[1] >> 2) & 0b11),
+            };
+
+            let (left, top) = (usize::from(block[2]), usize::from(block[3]));
+            let (right, bottom) = (usize::from(block[4]), usize::from(block[5]));
+
+            for y in 0..ATTRIBUTE_HEIGHT {
+                for x in 0..ATTRIBUTE_WIDTH {
+                    let within = (left..=right).contains(&x) && (top..=bottom).contains(&y);
+                    let on_line = within && (x == left || x == right || y == top || y == bottom);
+
+                    let palette = if on_line {
+                        (control & 0b010 != 0).then_some(line)
+                    } else if within {
+                        (control & 0b001 != 0).then_some(inside)
+                    } else {
+                        (control & 0b100 != 0).then_some(outside)
+                    };
+
+                    if let Some(palette) = palette {
+                        self.set_attribute(x, y, palette);
+                    }
+                }
+            }
+        }
+    }
+
+    fn attribute_lines(&mut self, data: &[u8]) {
+        let count = usize::from(data[1]);
+
+        for &line in data[2..].iter().take(count) {
+            let number = usize::from(line & 0x1F);
+            let palette = (line >> 5) & 0b11;
+
+            if line & 0x80 != 0 {
+                for x in 0..ATTRIBUTE_WIDTH {
+                    self.set_attribute(x, number, palette);
+                }
+            } else {
+                for y in 0..ATTRIBUTE_HEIGHT {
+                    self.set_attribute(number, y, palette);
+                }
+            }
+        }
+    }
+
+    fn attribute_divide(&mut self, data: &[u8]) {
+        let after = data[1] & 0b11;
+        let before = (data[1] >> 2) & 0b11;
+        let on_line = (data[1] >> 4) & 0b11;
+        let horizontal = data[1] & 0x40 != 0;
+        let line = usize::from(data[2]);
+
+        for y in 0..ATTRIBUTE_HEIGHT {
+            for x in 0..ATTRIBUTE_WIDTH {
+                let position = if horizontal { y } else { x };
+                let palette = match position.cmp(&line) {
+                    std::cmp::Ordering::Less => before,
+                    std::cmp::Ordering::Equal => on_line,
+                    std::cmp::Ordering::Greater => after,
+                };
+                self.set_attribute(x, y, palette);
+            }
+        }
+    }
+
+    fn attribute_cells(&mut self, data: &[u8]) {
+        let (mut x, mut y) = (usize::from(data[1]), usize::from(data[2]));
+        let count = usize::from(u16::from_le_bytes([data[3], data[4]])).min(ATTRIBUTE_CELLS);
+        let vertical = data[5] & 1 != 0;
+
+        // 4 cells to a byte, from the upper bits down
+        let palettes = data[6..]
+            .iter()
+            .flat_map(|&byte| (0..4).rev().map(move |i| (byte >> (i * 2)) & 0b11));
+
+        for palette in palettes.take(count) {
+            self.set_attribute(x, y, palette);
+
+            if vertical {
+                y += 1;
+                if y == ATTRIBUTE_HEIGHT {
+                    y = 0;
+                    x += 1;
+                }
+            } else {
+                x += 1;
+                if x == ATTRIBUTE_WIDTH {
+                    x = 0;
+                    y += 1;
+                }
+            }
+        }
+    }
+
+    fn apply_attribute_file(&mut self, number: usize) {
+        if number >= ATTRIBUTE_FILES {
+            warn!("SGB ATTRIBUTE FILE OUT OF RANGE: {}", number);
+            return;
+        }
+
+        let file = &self.attribute_files[number * ATTRIBUTE_FILE_SIZE..][..ATTRIBUTE_FILE_SIZE];
+        for (i, byte) in file.iter().enumerate() {
+            for j in 0..4 {
+                self.attributes[i * 4 + j] = (byte >> ((3 - j) * 2)) & 0b11;
+            }
+        }
+    }
+
+    /// Called at the start of VBlank, finishes pending transfers and draws the screen
+    pub fn frame(&mut self, ppu: &Ppu) {
+        if let Some(transfer) = self.transfer.take() {
+            self.finish_transfer(transfer, &transfer_data(ppu));
+        }
+
+        self.draw(ppu);
+    }
+
+    fn finish_transfer(&mut self, transfer: Transfer, data: &[u8]) {
+        let words = data
+            .chunks_exact(2)
+            .map(|word| u16::from_le_bytes([word[0], word[1]]));
+
+        match transfer {
+            Transfer::Palettes => {
+                for (color, word) in self.system_palettes.iter_mut().zip(words) {
+                    *color = word;
+                }
+            }
+            Transfer::Attributes => {
+                let size = self.attribute_files.len();
+                self.attribute_files.copy_from_slice(&data[..size]);
+            }
+            Transfer::Tiles(bank) => {
+                self.border_tiles[bank * TRANSFER_SIZE..][..TRANSFER_SIZE].copy_from_slice(data);
+            }
+            Transfer::Border => {
+                for (entry, word) in self.border_map.iter_mut().zip(words) {
+                    *entry = word;
+                }
+
+                let palettes = data[BORDER_PALETTES..]
+                    .chunks_exact(2)
+                    .map(|word| u16::from_le_bytes([word[0], word[1]]));
+                for (color, word) in self.border_palettes.iter_mut().flatten().zip(palettes) {
+                    *color = word;
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, ppu: &Ppu) {
+        let width = usize::from(SGB_X_RESOLUTION);
+        let backdrop = rgb(self.palettes[0][0]);
+
+        for y in 0..usize::from(SGB_Y_RESOLUTION) {
+            for x in 0..width {
+                let in_screen = (SCREEN_X..SCREEN_X + usize::from(X_RESOLUTION)).contains(&x)
+                    && (SCREEN_Y..SCREEN_Y + usize::from(Y_RESOLUTION)).contains(&y);
+
+                let color = if in_screen {
+                    match self.mask {
+                        Mask::None => self.screen_pixel(ppu, x - SCREEN_X, y - SCREEN_Y),
+                        Mask::Freeze => continue,
+                        Mask::Black => Rgb::new(0, 0, 0),
+                        Mask::Color0 => backdrop,
+                    }
+                } else {
+                    backdrop
+                };
+
+                // The border is drawn over the game, color 0 is see through
+                self.screen[y * width + x] = self.border_pixel(x, y).unwrap_or(color);
+            }
+        }
+    }
+
+    fn screen_pixel(&self, ppu: &Ppu, x: usize, y: usize) -> Rgb {
+        // The PPU draws DMG games with the plain shades, which one it was picks the color
+        let color = ppu.video_buffer[y * usize::from(X_RESOLUTION) + x];
+        let shade = TILE_COLORS.iter().position(|&c| c == color).unwrap_or(0);
+
+        let palette = self.attributes[(y / 8) * ATTRIBUTE_WIDTH + x / 8];
+        rgb(self.palettes[usize::from(palette)][shade])
+    }
+
+    fn border_pixel(&self, x: usize, y: usize) -> Option<Rgb> {
+        let entry = self.border_map[(y / 8) * BORDER_MAP_WIDTH + x / 8];
+        let tile = usize::from(entry & 0xFF);
+        let palette = usize::from((entry >> 10) & 0b11);
+
+        let column = if entry & 0x4000 != 0 {
+            7 - x % 8
+        } else {
+            x % 8
+        };
+        let row = if entry & 0x8000 != 0 {
+            7 - y % 8
+        } else {
+            y % 8
+        };
+
+        // Bitplanes 0 and 1 interleaved, then 2 and 3
+        let data = &self.border_tiles[tile * BORDER_TILE_SIZE..][..BORDER_TILE_SIZE];
+        let planes = [
+            data[row * 2],
+            data[row * 2 + 1],
+            data[16 + row * 2],
+            data[16 + row * 2 + 1],
+        ];
+        let color = planes.iter().enumerate().fold(0, |color, (i, plane)| {
+            color | (((plane >> (7 - column)) & 1) << i)
+        });
+
+        (color != 0).then(|| rgb(self.border_palettes[palette][usize::from(color)]))
+    }
+}
+
+impl Default for Sgb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn blank_screen() -> Vec<Rgb> {
+    vec![Rgb::new(0, 0, 0); SGB_BUFFER_SIZE]
+}
+
+/// RGB555 as used by the SNES, red in the lowest bits
+pub fn rgb(color: u16) -> Rgb {
+    let channel = |shift: u16| {
+        let value = ((color >> shift) & 0x1F) as u8;
+        (value << 3) | (value >> 2)
+    };
+
+    Rgb::new(channel(0), channel(5), channel(10))
+}
+
+// The 256 tiles the background map shows first, as laid out on screen
+fn transfer_data(ppu: &Ppu) -> Vec<u8> {
+    let map = ppu.bg_tile_map_addr();
+    let signed = ppu.bg_window_data_area() == 0x8800;
+    let mut data = Vec::with_capacity(TRANSFER_SIZE);
+
+    for i in 0..(TRANSFER_SIZE / 16) as u16 {
+        let mut tile = ppu.read_vram(map + (i / 20) * 32 + i % 20);
+        if signed {
+            tile = tile.wrapping_add(128);
+        }
+
+        let addr = ppu.bg_window_data_area() + u16::from(tile) * 16;
+        data.extend((addr..addr + 16).map(|addr| ppu.read_vram(addr)));
+    }
+
+    data
+}
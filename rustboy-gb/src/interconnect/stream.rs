@@ -0,0 +1,115 @@
+//! Sockets to another emulator, over TCP (`host:port`) or a Unix domain socket (`unix:<path>`)
+#[cfg(unix)]
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+
+use log::info;
+
+// Addresses starting with this are Unix domain socket paths
+const UNIX_PREFIX: &str = "unix:";
+
+#[derive(Debug)]
+pub(crate) enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    /// Connect to the other end listening on `addr`
+    pub(crate) fn connect(addr: &str) -> io::Result<Stream> {
+        #[cfg(unix)]
+        if let Some(path) = addr.strip_prefix(UNIX_PREFIX) {
+            return Ok(Stream::Unix(UnixStream::connect(path)?));
+        }
+
+        Stream::tcp(TcpStream::connect(addr)?)
+    }
+
+    pub(crate) fn tcp(stream: TcpStream) -> io::Result<Stream> {
+        // Both ends wait on each other's messages, don't let them wait for more data
+        stream.set_nodelay(true)?;
+        Ok(Stream::Tcp(stream))
+    }
+
+    pub(crate) fn try_clone(&self) -> io::Result<Stream> {
+        match self {
+            Stream::Tcp(stream) => stream.try_clone().map(Stream::Tcp),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.try_clone().map(Stream::Unix),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Waits for the other end to connect, the socket file of a Unix socket is only there meanwhile
+#[derive(Debug)]
+pub(crate) enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener, String),
+}
+
+impl Listener {
+    pub(crate) fn bind(addr: &str) -> io::Result<Listener> {
+        #[cfg(unix)]
+        if let Some(path) = addr.strip_prefix(UNIX_PREFIX) {
+            // A socket left behind by a run that didn't get to clean up would make binding fail
+            if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+                fs::remove_file(path)?;
+            }
+
+            return Ok(Listener::Unix(UnixListener::bind(path)?, path.to_string()));
+        }
+
+        Ok(Listener::Tcp(TcpListener::bind(addr)?))
+    }
+
+    pub(crate) fn accept(self) -> io::Result<Stream> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, peer) = listener.accept()?;
+                info!("CONNECTED TO {}", peer);
+                Stream::tcp(stream)
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener, path) => {
+                let accepted = listener.accept();
+                let _ = fs::remove_file(path);
+                accepted.map(|(stream, _)| Stream::Unix(stream))
+            }
+        }
+    }
+}
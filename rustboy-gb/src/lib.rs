@@ -1,5 +1,7 @@
 pub mod constants;
 pub mod cpu;
 pub mod gameboy;
+pub mod harness;
 pub mod interconnect;
+pub mod screenshot;
 pub mod util;
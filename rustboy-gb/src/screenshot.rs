@@ -0,0 +1,325 @@
+//! Screenshot comparisons for visual test roms
+//!
+//! Visual test roms (dmg-acid2, Mealybug Tearoom, ...) don't report a result, they draw a screen
+//! that has to match a reference image. These helpers run a rom for a fixed number of frames,
+//! dump `Ppu::video_buffer` to PNG and compare it against a reference PNG.
+//!
+//! Reference images don't use our palette, so pixels are compared by shade: every color is
+//! reduced to one of the 4 DMG shades by its luminance before comparing.
+use std::fmt;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+
+use crate::constants::{MAX_CYCLES_PER_FRAME, X_RESOLUTION, Y_RESOLUTION};
+use crate::gameboy::{GameBoy, LoadError};
+use crate::interconnect::ppu::{Renderer, Rgb};
+
+const WIDTH: u32 = X_RESOLUTION as u32;
+const HEIGHT: u32 = Y_RESOLUTION as u32;
+
+/// Maximum number of differing pixels listed in a diff report
+const MAX_REPORTED_PIXELS: usize = 16;
+
+/// Color the differing pixels are painted with in a diff image
+const DIFF_COLOR: Rgb = Rgb::new(255, 0, 0);
+
+/// A decoded RGB image
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn from_video_buffer(buffer: &[Rgb]) -> Image {
+        Image {
+            width: WIDTH,
+            height: HEIGHT,
+            pixels: buffer.to_vec(),
+        }
+    }
+
+    pub fn load_png(path: &Path) -> Result<Image> {
+        let mut decoder = png::Decoder::new(File::open(path)?);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        let bytes = &buffer[..info.buffer_size()];
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgb => bytes
+                .chunks_exact(3)
+                .map(|p| Rgb::new(p[0], p[1], p[2]))
+                .collect(),
+            png::ColorType::Rgba => bytes
+                .chunks_exact(4)
+                .map(|p| Rgb::new(p[0], p[1], p[2]))
+                .collect(),
+            png::ColorType::Grayscale => bytes.iter().map(|&p| Rgb::new(p, p, p)).collect(),
+            png::ColorType::GrayscaleAlpha => bytes
+                .chunks_exact(2)
+                .map(|p| Rgb::new(p[0], p[0], p[0]))
+                .collect(),
+            png::ColorType::Indexed => bail!("PNG PALETTE WAS NOT EXPANDED: {}", path.display()),
+        };
+
+        Ok(Image {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+
+    pub fn save_png(&self, path: &Path) -> Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let data: Vec<u8> = self
+            .pixels
+            .iter()
+            .flat_map(|pixel| {
+                let (r, g, b) = pixel.get_rgb();
+                [r, g, b]
+            })
+            .collect();
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&data)?;
+        Ok(())
+    }
+}
+
+/// Reduce a color to a DMG shade, 0 (white) to 3 (black)
+pub fn shade(color: Rgb) -> u8 {
+    let (r, g, b) = color.get_rgb();
+    let luminance = (u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1000;
+
+    3 - ((luminance * 3 + 127) / 255) as u8
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PixelDiff {
+    pub x: u32,
+    pub y: u32,
+    pub expected: Rgb,
+    pub actual: Rgb,
+}
+
+/// Result of comparing a screenshot against a reference image
+#[derive(Debug, Clone)]
+pub struct ScreenshotDiff {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<PixelDiff>,
+}
+
+impl ScreenshotDiff {
+    pub fn matches(&self) -> bool {
+        self.pixels.is_empty()
+    }
+
+    /// The actual screenshot with every differing pixel painted red
+    pub fn diff_image(&self, actual: &Image) -> Image {
+        let mut image = actual.clone();
+        for pixel in &self.pixels {
+            image.pixels[(pixel.y * self.width + pixel.x) as usize] = DIFF_COLOR;
+        }
+
+        image
+    }
+}
+
+impl fmt::Display for ScreenshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.matches() {
+            return write!(f, "SCREENSHOT MATCHES");
+        }
+
+        let total = self.width * self.height;
+        writeln!(f, "{} OF {} PIXELS DIFFER", self.pixels.len(), total)?;
+
+        let min_x = self.pixels.iter().map(|p| p.x).min().unwrap();
+        let max_x = self.pixels.iter().map(|p| p.x).max().unwrap();
+        let min_y = self.pixels.iter().map(|p| p.y).min().unwrap();
+        let max_y = self.pixels.iter().map(|p| p.y).max().unwrap();
+        writeln!(f, "BOUNDS: ({}, {}) - ({}, {})", min_x, min_y, max_x, max_y)?;
+
+        for pixel in self.pixels.iter().take(MAX_REPORTED_PIXELS) {
+            writeln!(
+                f,
+                "({:3}, {:3}): EXPECTED {} (SHADE {}), GOT {} (SHADE {})",
+                pixel.x,
+                pixel.y,
+                pixel.expected.to_string(),
+                shade(pixel.expected),
+                pixel.actual.to_string(),
+                shade(pixel.actual)
+            )?;
+        }
+
+        if self.pixels.len() > MAX_REPORTED_PIXELS {
+            writeln!(f, "...")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compare two images pixel by pixel by shade
+pub fn compare(actual: &Image, expected: &Image) -> Result<ScreenshotDiff> {
+    if actual.width != expected.width || actual.height != expected.height {
+        bail!(
+            "IMAGE SIZE MISMATCH: EXPECTED {}x{}, GOT {}x{}",
+            expected.width,
+            expected.height,
+            actual.width,
+            actual.height
+        );
+    }
+
+    let width = actual.width;
+    let pixels = actual
+        .pixels
+        .iter()
+        .zip(expected.pixels.iter())
+        .enumerate()
+        .filter(|(_, (&actual, &expected))| shade(actual) != shade(expected))
+        .map(|(i, (&actual, &expected))| PixelDiff {
+            x: i as u32 % width,
+            y: i as u32 / width,
+            expected,
+            actual,
+        })
+        .collect();
+
+    Ok(ScreenshotDiff {
+        width: actual.width,
+        height: actual.height,
+        pixels,
+    })
+}
+
+/// Boot `rom` (skipping the boot rom), run it for `frames` frames and return the screen
+pub fn capture(rom: &[u8], frames: u64) -> Result<Image, LoadError> {
+    capture_with(rom, frames, Renderer::default())
+}
+
+/// Like `capture`, drawing with the given renderer
+pub fn capture_with(rom: &[u8], frames: u64, renderer: Renderer) -> Result<Image, LoadError> {
+    let mut gameboy = GameBoy::from_rom_bytes(rom, None)?;
+    gameboy.interconnect.serial.set_echo(false);
+    gameboy.interconnect.ppu.renderer = renderer;
+
+    let max_cycles = frames * MAX_CYCLES_PER_FRAME as u64;
+    while gameboy.interconnect.cycles < max_cycles {
+        gameboy.cpu.step(&mut gameboy.interconnect);
+    }
+
+    Ok(Image::from_video_buffer(
+        &gameboy.interconnect.ppu.video_buffer,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgb = Rgb::new(255, 255, 255);
+    const LIGHT: Rgb = Rgb::new(170, 170, 170);
+    const DARK: Rgb = Rgb::new(85, 85, 85);
+    const BLACK: Rgb = Rgb::new(0, 0, 0);
+
+    fn image(pixels: &[Rgb]) -> Image {
+        Image {
+            width: 2,
+            height: 2,
+            pixels: pixels.to_vec(),
+        }
+    }
+
+    #[test]
+    fn shades() {
+        assert_eq!(shade(WHITE), 0);
+        assert_eq!(shade(LIGHT), 1);
+        assert_eq!(shade(DARK), 2);
+        assert_eq!(shade(BLACK), 3);
+        // Only luminance counts, not the palette
+        assert_eq!(shade(Rgb::new(0xE0, 0xF8, 0xD0)), 0);
+        assert_eq!(shade(Rgb::new(0x08, 0x18, 0x20)), 3);
+    }
+
+    #[test]
+    fn same_shades_match() {
+        let expected = image(&[WHITE, LIGHT, DARK, BLACK]);
+        let actual = image(&[
+            Rgb::new(0xE0, 0xF8, 0xD0),
+            Rgb::new(0x88, 0xC0, 0x70),
+            Rgb::new(0x34, 0x68, 0x56),
+            Rgb::new(0x08, 0x18, 0x20),
+        ]);
+
+        let diff = compare(&actual, &expected).unwrap();
+        assert!(diff.matches(), "{}", diff);
+    }
+
+    #[test]
+    fn counts_differing_pixels() {
+        let expected = image(&[WHITE, LIGHT, DARK, BLACK]);
+        let actual = image(&[WHITE, BLACK, DARK, WHITE]);
+
+        let diff = compare(&actual, &expected).unwrap();
+        assert!(!diff.matches());
+        assert_eq!(diff.pixels.len(), 2);
+        assert_eq!((diff.pixels[0].x, diff.pixels[0].y), (1, 0));
+        assert_eq!((diff.pixels[1].x, diff.pixels[1].y), (1, 1));
+        assert_eq!(diff.pixels[0].expected, LIGHT);
+        assert_eq!(diff.pixels[0].actual, BLACK);
+        assert!(diff.to_string().starts_with("2 OF 4 PIXELS DIFFER"));
+    }
+
+    #[test]
+    fn size_mismatch_is_an_error() {
+        let expected = image(&[WHITE; 4]);
+        let actual = Image {
+            width: 4,
+            height: 1,
+            pixels: vec![WHITE; 4],
+        };
+
+        assert!(compare(&actual, &expected).is_err());
+    }
+
+    #[test]
+    fn diff_image_paints_differences() {
+        let expected = image(&[WHITE, LIGHT, DARK, BLACK]);
+        let actual = image(&[WHITE, BLACK, DARK, WHITE]);
+
+        let diff = compare(&actual, &expected).unwrap();
+        let diff_image = diff.diff_image(&actual);
+        assert_eq!(diff_image.pixels, [WHITE, DIFF_COLOR, DARK, DIFF_COLOR]);
+    }
+
+    #[test]
+    fn png_round_trip() {
+        let path =
+            std::env::temp_dir().join(format!("rustboy-screenshot-{}.png", std::process::id()));
+        let original = image(&[WHITE, Rgb::new(0x88, 0xC0, 0x70), DARK, BLACK]);
+
+        original.save_png(&path).unwrap();
+        let loaded = Image::load_png(&path);
+        let _ = std::fs::remove_file(&path);
+
+        let loaded = loaded.unwrap();
+        assert_eq!((loaded.width, loaded.height), (2, 2));
+        assert_eq!(loaded.pixels, original.pixels);
+    }
+}
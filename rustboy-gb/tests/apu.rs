@@ -0,0 +1,22 @@
+mod common;
+
+use common::rom_with;
+use rustboy::gameboy::GameBoy;
+
+#[test]
+fn undrained_samples_stay_bounded() {
+    let mut gameboy = GameBoy::from_rom_bytes(&rom_with(0x00, 0x00, 0x00, 0x00), None).unwrap();
+    let sample_rate = gameboy.interconnect.apu.sample_rate() as usize;
+
+    gameboy.cpu.run(&mut gameboy.interconnect);
+    let one_frame = gameboy.interconnect.apu.queued_frames();
+    assert!(one_frame > sample_rate / 70 && one_frame < sample_rate / 50);
+
+    for _ in 0..120 {
+        gameboy.cpu.run(&mut gameboy.interconnect);
+    }
+    assert!(gameboy.interconnect.apu.queued_frames() <= sample_rate * 4 / 60);
+
+    // What's left is the latest audio, in whole stereo frames
+    assert_eq!(gameboy.interconnect.apu.drain_samples().len() % 2, 0);
+}
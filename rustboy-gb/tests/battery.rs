@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::PathBuf;
+
+use rustboy::gameboy::{battery_save_path, GameBoy, LoadError};
+use rustboy::interconnect::cartridge::cartridge_info::header_checksum;
+
+// 32 KiB MBC1 rom with 8 KiB of battery backed RAM
+fn battery_rom() -> Vec<u8> {
+    let mut rom = vec![0; 0x8000];
+    rom[0x147] = 0x03;
+    rom[0x149] = 0x02;
+    rom[0x14D] = header_checksum(&rom);
+    rom
+}
+
+fn save_path(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("battery");
+    fs::create_dir_all(&dir).unwrap();
+
+    let path = battery_save_path(&dir.join(format!("{}.gb", name)));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn write_ram(gameboy: &mut GameBoy, addr: u16, value: u8) {
+    gameboy.interconnect.write_mem(0x0000, 0x0A);
+    gameboy.interconnect.write_mem(addr, value);
+}
+
+#[test]
+fn export_and_import_ram() {
+    let mut gameboy = GameBoy::from_rom_bytes(&battery_rom(), None).unwrap();
+    let cartridge = &mut gameboy.interconnect.cartridge;
+    assert!(cartridge.has_battery());
+    assert!(!cartridge.take_ram_dirty());
+
+    write_ram(&mut gameboy, 0xA010, 0x42);
+    let cartridge = &mut gameboy.interconnect.cartridge;
+    assert!(cartridge.take_ram_dirty());
+    assert!(!cartridge.take_ram_dirty());
+
+    let save = cartridge.export_ram();
+    assert_eq!(save.len(), 0x2000);
+    assert_eq!(save[0x10], 0x42);
+
+    let mut other = GameBoy::from_rom_bytes(&battery_rom(), None).unwrap();
+    other.interconnect.cartridge.import_ram(&save).unwrap();
+    other.interconnect.write_mem(0x0000, 0x0A);
+    assert_eq!(other.interconnect.read_mem(0xA010), 0x42);
+}
+
+#[test]
+fn writes_with_ram_disabled_leave_it_clean() {
+    let mut gameboy = GameBoy::from_rom_bytes(&battery_rom(), None).unwrap();
+    gameboy.interconnect.write_mem(0xA010, 0x42);
+    assert!(!gameboy.interconnect.cartridge.take_ram_dirty());
+
+    write_ram(&mut gameboy, 0xA010, 0x42);
+    gameboy.interconnect.write_mem(0x0000, 0x00);
+    gameboy.interconnect.write_mem(0xA010, 0x43);
+    let cartridge = &mut gameboy.interconnect.cartridge;
+    assert!(cartridge.take_ram_dirty());
+    assert!(!cartridge.take_ram_dirty());
+}
+
+#[test]
+fn rejects_wrong_save_size() {
+    let mut gameboy = GameBoy::from_rom_bytes(&battery_rom(), None).unwrap();
+
+    let result = gameboy.interconnect.cartridge.import_ram(&[0; 0x100]);
+    assert_eq!(
+        result.err(),
+        Some(LoadError::InvalidSaveSize {
+            expected: 0x2000,
+            actual: 0x100
+        })
+    );
+}
+
+#[test]
+fn flushes_and_reloads_sav_file() {
+    let path = save_path("flush");
+
+    let mut gameboy = GameBoy::from_rom_bytes(&battery_rom(), None).unwrap();
+    gameboy.attach_battery_save(&path).unwrap();
+
+    // Nothing is written until the RAM changes
+    gameboy.flush_battery_save().unwrap();
+    assert!(!path.exists());
+
+    write_ram(&mut gameboy, 0xA000, 0x99);
+    gameboy.flush_battery_save().unwrap();
+    assert_eq!(fs::read(&path).unwrap()[0], 0x99);
+
+    let mut reloaded = GameBoy::from_rom_bytes(&battery_rom(), None).unwrap();
+    reloaded.attach_battery_save(&path).unwrap();
+    reloaded.interconnect.write_mem(0x0000, 0x0A);
+    assert_eq!(reloaded.interconnect.read_mem(0xA000), 0x99);
+}
+
+#[test]
+fn ignores_cartridges_without_battery() {
+    let path = save_path("no_battery");
+
+    let mut rom = battery_rom();
+    rom[0x147] = 0x02;
+    rom[0x14D] = header_checksum(&rom);
+
+    let mut gameboy = GameBoy::from_rom_bytes(&rom, None).unwrap();
+    gameboy.attach_battery_save(&path).unwrap();
+
+    write_ram(&mut gameboy, 0xA000, 0x99);
+    gameboy.flush_battery_save().unwrap();
+    assert!(!path.exists());
+}
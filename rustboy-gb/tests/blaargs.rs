@@ -1,63 +1,48 @@
-use std::{
-    fs,
-    io::{BufRead, BufReader},
-    path::{Path, PathBuf},
-    process::{Command, Stdio},
-};
-
-#[cfg(test)]
+mod common;
+
+use common::{assert_all_passed, run_dir, run_rom};
+
+// Emulated time limit per rom, the slowest cpu_instrs rom takes about 30 seconds
+const MAX_FRAMES: u64 = 60 * 60;
+
 #[test]
 fn all_cpu_blaargs() {
-    // Directory Path
-    let dir_path = "roms/blaargs/cpu_instrs/individual/";
+    if let Some(results) = run_dir("roms/blaargs/cpu_instrs/individual/", MAX_FRAMES) {
+        assert_all_passed(&results);
+    }
+}
 
-    // Read the directory entries
-    let mut entries: Vec<_> = fs::read_dir(dir_path)
-        .expect("NOT A DIRECTORY")
-        .map(|entry| entry.unwrap())
-        .collect();
+#[test]
+fn instr_timing() {
+    if let Some(results) = run_rom("roms/blaargs/instr_timing/instr_timing.gb", MAX_FRAMES) {
+        assert_all_passed(&results);
+    }
+}
 
-    entries.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+#[test]
+fn mem_timing() {
+    if let Some(results) = run_dir("roms/blaargs/mem_timing/individual/", MAX_FRAMES) {
+        assert_all_passed(&results);
+    }
+}
 
-    // Iterate over the directory entries
-    for entry in entries {
-        let path: PathBuf = entry.path();
+#[test]
+fn mem_timing_2() {
+    if let Some(results) = run_dir("roms/blaargs/mem_timing-2/rom_singles/", MAX_FRAMES) {
+        assert_all_passed(&results);
+    }
+}
 
-        // Check if it's a file
-        if path.is_file() {
-            run_test(path.to_str().unwrap());
-        }
+#[test]
+fn halt_bug() {
+    if let Some(results) = run_rom("roms/blaargs/halt_bug.gb", MAX_FRAMES) {
+        assert_all_passed(&results);
     }
 }
 
-fn run_test(rom_path: &str) {
-    let mut emulator = Command::new("target/release/rustboy")
-        .arg("--rom")
-        .arg(rom_path)
-        .arg("--headless")
-        .arg("--skip-boot")
-        .stdin(Stdio::null())
-        .stdout(Stdio::piped())
-        .spawn()
-        .expect("FAILED TO START");
-
-    let stdout = emulator.stdout.take().expect("FAILED TO cAPTURE");
-    let reader = BufReader::new(stdout);
-
-    let path_parts: Vec<&str> = rom_path.split("/").collect();
-
-    let file_name = path_parts[path_parts.len() - 1];
-
-    for line in reader.lines() {
-        let output = line.expect("FAILED TO READ LINE FROM EMU");
-        if output.contains("Passed") {
-            println!("{}: \x1B[32m{}\x1B[0m", file_name, output);
-
-            emulator.kill().expect("COULDNT KILL");
-            emulator.wait().expect("COUOLDNT WAIT");
-            assert!(true);
-            return;
-        }
+#[test]
+fn oam_bug() {
+    if let Some(results) = run_dir("roms/blaargs/oam_bug/rom_singles/", MAX_FRAMES) {
+        assert_all_passed(&results);
     }
-    panic!("TEST FAILED");
 }
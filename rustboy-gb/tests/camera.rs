@@ -0,0 +1,225 @@
+mod common;
+
+use common::rom_with;
+use std::path::PathBuf;
+
+use rustboy::gameboy::GameBoy;
+use rustboy::interconnect::cartridge::camera::{
+    open_image_source, StillImage, SENSOR_HEIGHT, SENSOR_WIDTH,
+};
+use rustboy::interconnect::cartridge::cartridge_info::CartridgeType;
+use rustboy::interconnect::ppu::Rgb;
+use rustboy::interconnect::Interconnect;
+use rustboy::screenshot::Image;
+
+// Registers
+const CAPTURE: u16 = 0xA000;
+const GAIN: u16 = 0xA001;
+const EXPOSURE: u16 = 0xA002;
+const EDGE: u16 = 0xA004;
+const MATRIX: u16 = 0xA006;
+
+// Photos are written to RAM bank 0 from here, 16 x 14 tiles
+const IMAGE: u16 = 0xA100;
+
+const SHADE_THRESHOLDS: [u8; 3] = [60, 120, 180];
+
+fn boot() -> GameBoy {
+    GameBoy::from_rom_bytes(&rom_with(0xFC, 0x01, 0x04, 0x00), None).unwrap()
+}
+
+fn output_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rustboy-camera-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// A picture the size of the sensor, `left` brightness up to `split` and `right` after it
+fn picture(split: usize, left: u8, right: u8) -> Image {
+    Image {
+        width: SENSOR_WIDTH as u32,
+        height: SENSOR_HEIGHT as u32,
+        pixels: (0..SENSOR_WIDTH * SENSOR_HEIGHT)
+            .map(|i| {
+                let value = if i % SENSOR_WIDTH < split {
+                    left
+                } else {
+                    right
+                };
+                Rgb::new(value, value, value)
+            })
+            .collect(),
+    }
+}
+
+// Exposure passing brightness through, no gain or edge enhancement, the same thresholds everywhere
+fn set_up_sensor(interconnect: &mut Interconnect) {
+    interconnect.write_mem(0x4000, 0x10);
+    interconnect.write_mem(GAIN, 0x00);
+    interconnect.write_mem(EXPOSURE, 0x10);
+    interconnect.write_mem(EXPOSURE + 1, 0x00);
+    interconnect.write_mem(EDGE, 0x00);
+
+    for cell in 0..16 {
+        for (i, &threshold) in SHADE_THRESHOLDS.iter().enumerate() {
+            interconnect.write_mem(MATRIX + cell * 3 + i as u16, threshold);
+        }
+    }
+}
+
+// Returns the T-cycles the photo took
+fn take_photo(interconnect: &mut Interconnect) -> u32 {
+    interconnect.write_mem(0x4000, 0x10);
+    interconnect.write_mem(CAPTURE, 0x01);
+
+    let mut cycles = 0;
+    while interconnect.read_mem(CAPTURE) & 0x01 != 0 {
+        interconnect.cartridge.tick(4);
+        cycles += 4;
+    }
+
+    interconnect.write_mem(0x4000, 0x00);
+    cycles
+}
+
+// Shade of pixel (x, y) of the photo in RAM
+fn shade(interconnect: &Interconnect, x: usize, y: usize) -> u8 {
+    let row = IMAGE + (((y / 8) * 16 + x / 8) * 16 + (y % 8) * 2) as u16;
+    let bit = 7 - (x % 8);
+    let low = (interconnect.read_mem(row) >> bit) & 1;
+    let high = (interconnect.read_mem(row + 1) >> bit) & 1;
+    (high << 1) | low
+}
+
+#[test]
+fn camera_cart_has_128_kib_of_battery_ram() {
+    let gameboy = boot();
+    let cartridge = &gameboy.interconnect.cartridge;
+
+    assert_eq!(cartridge.header.cartridge_type, CartridgeType::PocketCamera);
+    assert!(cartridge.has_battery());
+    assert!(cartridge.has_camera());
+    assert_eq!(cartridge.export_ram().len(), 0x20000);
+}
+
+#[test]
+fn photo_is_dithered_into_ram() {
+    let mut gameboy = boot();
+    let interconnect = &mut gameboy.interconnect;
+    let mbc = &mut interconnect.cartridge.mbc;
+    mbc.set_image_source(Box::new(StillImage::new(&picture(32, 0, 100))));
+    set_up_sensor(interconnect);
+
+    // Busy for as long as the exposure says
+    let cycles = take_photo(interconnect);
+    assert_eq!(cycles, 4 * (32446 + 512 + 16 * 0x1000));
+
+    assert_eq!(shade(interconnect, 0, 0), 3);
+    assert_eq!(shade(interconnect, 31, 111), 3);
+    assert_eq!(shade(interconnect, 32, 0), 2);
+    assert_eq!(shade(interconnect, 127, 111), 2);
+
+    // Twice the exposure brightens it
+    interconnect.write_mem(0x4000, 0x10);
+    interconnect.write_mem(EXPOSURE, 0x20);
+    take_photo(interconnect);
+    assert_eq!(shade(interconnect, 32, 0), 0);
+}
+
+#[test]
+fn ram_is_hidden_while_capturing() {
+    let mut gameboy = boot();
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(0x0000, 0x0A);
+    interconnect.write_mem(0xA000, 0x42);
+    assert_eq!(interconnect.read_mem(0xA000), 0x42);
+
+    set_up_sensor(interconnect);
+    interconnect.write_mem(CAPTURE, 0x01);
+    assert_eq!(interconnect.read_mem(CAPTURE), 0x01);
+
+    // The other registers read 0
+    assert_eq!(interconnect.read_mem(GAIN), 0x00);
+
+    interconnect.write_mem(0x4000, 0x00);
+    assert_eq!(interconnect.read_mem(0xA000), 0x00);
+    interconnect.cartridge.tick(0x100000);
+    assert_eq!(interconnect.read_mem(0xA000), 0x42);
+}
+
+#[test]
+fn edges_are_enhanced_and_output_inverted() {
+    let mut gameboy = boot();
+    let interconnect = &mut gameboy.interconnect;
+    let mbc = &mut interconnect.cartridge.mbc;
+    mbc.set_image_source(Box::new(StillImage::new(&picture(64, 100, 150))));
+    set_up_sensor(interconnect);
+
+    take_photo(interconnect);
+    assert_eq!(
+        (62..66)
+            .map(|x| shade(interconnect, x, 50))
+            .collect::<Vec<_>>(),
+        [2, 2, 1, 1]
+    );
+
+    // Horizontal edge enhancement at a ratio of 1
+    interconnect.write_mem(0x4000, 0x10);
+    interconnect.write_mem(GAIN, 0b10 << 5);
+    interconnect.write_mem(EDGE, 0x20);
+    take_photo(interconnect);
+    assert_eq!(
+        (62..66)
+            .map(|x| shade(interconnect, x, 50))
+            .collect::<Vec<_>>(),
+        [2, 3, 0, 1]
+    );
+
+    interconnect.write_mem(0x4000, 0x10);
+    interconnect.write_mem(GAIN, 0x00);
+    interconnect.write_mem(EDGE, 0x08);
+    take_photo(interconnect);
+    assert_eq!(
+        (62..66)
+            .map(|x| shade(interconnect, x, 50))
+            .collect::<Vec<_>>(),
+        [1, 1, 2, 2]
+    );
+}
+
+#[test]
+fn frames_are_taken_in_turn() {
+    let dir = output_dir("frames");
+    picture(0, 0, 0).save_png(&dir.join("1.png")).unwrap();
+    picture(0, 0, 255).save_png(&dir.join("2.png")).unwrap();
+
+    let mut gameboy = boot();
+    let interconnect = &mut gameboy.interconnect;
+    let source = open_image_source(&dir).unwrap();
+    interconnect.cartridge.mbc.set_image_source(source);
+    set_up_sensor(interconnect);
+
+    let shades = (0..3)
+        .map(|_| {
+            take_photo(interconnect);
+            shade(interconnect, 0, 0)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(shades, [3, 0, 3]);
+
+    assert!(open_image_source(&output_dir("empty")).is_err());
+}
+
+#[test]
+fn test_pattern_is_seen_without_a_source() {
+    let mut gameboy = boot();
+    let interconnect = &mut gameboy.interconnect;
+    set_up_sensor(interconnect);
+    take_photo(interconnect);
+
+    assert_eq!(shade(interconnect, 0, 0), 3);
+    assert_eq!(shade(interconnect, 127, 0), 0);
+    assert_eq!(shade(interconnect, 64, 56), 3);
+}
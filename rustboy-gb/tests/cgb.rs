@@ -0,0 +1,252 @@
+mod common;
+
+use common::rom_with;
+use rustboy::constants::{
+    BCPD, BCPS, CGB_COMPATIBILITY_SHADES, HDMA1, HDMA2, HDMA3, HDMA4, HDMA5, KEY1,
+    MAX_CYCLES_PER_FRAME, SVBK, VBK,
+};
+use rustboy::gameboy::{ChecksumPolicy, GameBoy};
+use rustboy::interconnect::ppu::{PaletteType, Rgb};
+use rustboy::interconnect::{Interconnect, Model};
+
+fn cgb() -> GameBoy {
+    GameBoy::from_rom_bytes(&rom_with(0x00, 0x00, 0x00, 0x80), None).unwrap()
+}
+
+fn run_frame(gameboy: &mut GameBoy) {
+    let end = gameboy.interconnect.cycles + MAX_CYCLES_PER_FRAME as u64;
+    while gameboy.interconnect.cycles < end {
+        gameboy.cpu.step(&mut gameboy.interconnect);
+    }
+}
+
+#[test]
+fn mode_follows_header() {
+    let gameboy = cgb();
+    assert_eq!(gameboy.interconnect.model, Model::Cgb);
+    assert_eq!(gameboy.cpu.registers.a, 0x11);
+
+    let gameboy = GameBoy::from_rom_bytes(&rom_with(0x00, 0x00, 0x00, 0xC0), None).unwrap();
+    assert_eq!(gameboy.interconnect.model, Model::Cgb);
+
+    let gameboy = GameBoy::from_rom_bytes(&rom_with(0x00, 0x00, 0x00, 0x00), None).unwrap();
+    assert_eq!(gameboy.interconnect.model, Model::Dmg);
+    assert_eq!(gameboy.cpu.registers.a, 0x01);
+}
+
+#[test]
+fn vram_banks() {
+    let mut gameboy = cgb();
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(0x8000, 0x11);
+    interconnect.write_mem(VBK, 1);
+    assert_eq!(interconnect.read_mem(VBK), 0xFF);
+    assert_eq!(interconnect.read_mem(0x8000), 0x00);
+
+    interconnect.write_mem(0x8000, 0x22);
+    interconnect.write_mem(VBK, 0);
+    assert_eq!(interconnect.read_mem(0x8000), 0x11);
+}
+
+#[test]
+fn work_ram_banks() {
+    let mut gameboy = cgb();
+    let interconnect = &mut gameboy.interconnect;
+
+    for bank in 1..8 {
+        interconnect.write_mem(SVBK, bank);
+        interconnect.write_mem(0xD000, bank * 0x10);
+    }
+
+    // Bank 0 is fixed at 0xC000 - 0xCFFF, selecting it maps bank 1
+    interconnect.write_mem(SVBK, 0);
+    assert_eq!(interconnect.read_mem(0xD000), 0x10);
+
+    interconnect.write_mem(SVBK, 5);
+    assert_eq!(interconnect.read_mem(SVBK), 0xFD);
+    assert_eq!(interconnect.read_mem(0xD000), 0x50);
+}
+
+#[test]
+fn palette_ram_auto_increment() {
+    let mut gameboy = cgb();
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(BCPS, 0x80 | 0x3E);
+    interconnect.write_mem(BCPD, 0x1F);
+    interconnect.write_mem(BCPD, 0x00);
+    interconnect.write_mem(BCPD, 0x34);
+
+    // The index wraps around
+    assert_eq!(interconnect.read_mem(BCPS), 0x80 | 0x40 | 0x01);
+
+    interconnect.write_mem(BCPS, 0x3E);
+    assert_eq!(interconnect.read_mem(BCPD), 0x1F);
+    interconnect.write_mem(BCPS, 0x00);
+    assert_eq!(interconnect.read_mem(BCPD), 0x34);
+
+    let ppu = &interconnect.ppu;
+    assert_eq!(ppu.bg_color_palettes.color(7, 3), Rgb::new(255, 0, 0));
+}
+
+#[test]
+fn bg_attributes_select_bank_and_palette() {
+    let mut gameboy = cgb();
+    let interconnect = &mut gameboy.interconnect;
+
+    // Tile 0 in bank 1 is all color 1
+    interconnect.write_mem(VBK, 1);
+    for line in 0..8 {
+        interconnect.write_mem(0x8000 + line * 2, 0xFF);
+    }
+
+    // First map entry uses bank 1 and palette 3
+    interconnect.write_mem(0x9800, 0x0B);
+    interconnect.write_mem(VBK, 0);
+
+    // Palette 3 color 1 is blue
+    interconnect.write_mem(BCPS, 3 * 8 + 2);
+    interconnect.write_mem(BCPD, 0x00);
+    interconnect.write_mem(BCPS, 3 * 8 + 3);
+    interconnect.write_mem(BCPD, 0x7C);
+
+    run_frame(&mut gameboy);
+
+    let buffer = &gameboy.interconnect.ppu.video_buffer;
+    assert_eq!(buffer[0], Rgb::new(0, 0, 255));
+    assert_eq!(buffer[7], Rgb::new(0, 0, 255));
+    assert_eq!(buffer[8], Rgb::new(255, 255, 255));
+}
+
+#[test]
+fn double_speed() {
+    let mut gameboy = cgb();
+    let interconnect = &mut gameboy.interconnect;
+    assert_eq!(interconnect.read_mem(KEY1), 0x7E);
+
+    interconnect.write_mem(KEY1, 0x01);
+    assert_eq!(interconnect.read_mem(KEY1), 0x7F);
+
+    interconnect.speed_switch();
+    assert!(interconnect.double_speed);
+    assert_eq!(interconnect.read_mem(KEY1), 0xFE);
+
+    // An M-cycle only takes half as long
+    let start = interconnect.cycles;
+    interconnect.emu_tick(2);
+    assert_eq!(interconnect.cycles - start, 4);
+
+    // Switching needs to be armed again
+    interconnect.speed_switch();
+    assert!(interconnect.double_speed);
+}
+
+#[test]
+fn no_speed_switch_on_dmg() {
+    let mut interconnect = Interconnect::new();
+    interconnect.write_mem(KEY1, 0x01);
+    interconnect.speed_switch();
+    assert!(!interconnect.double_speed);
+}
+
+fn setup_hdma(interconnect: &mut Interconnect) {
+    for i in 0..0x40 {
+        interconnect.write_mem(0xC000 + i, i as u8);
+    }
+
+    interconnect.write_mem(HDMA1, 0xC0);
+    interconnect.write_mem(HDMA2, 0x00);
+    interconnect.write_mem(HDMA3, 0x01);
+    interconnect.write_mem(HDMA4, 0x00);
+}
+
+#[test]
+fn general_purpose_dma() {
+    let mut gameboy = cgb();
+    let interconnect = &mut gameboy.interconnect;
+    setup_hdma(interconnect);
+
+    let start = interconnect.cycles;
+    interconnect.write_mem(HDMA5, 0x03);
+    assert_eq!(interconnect.read_mem(HDMA5), 0xFF);
+    assert_eq!(interconnect.cycles - start, 4 * 8 * 4);
+
+    for i in 0..0x40 {
+        assert_eq!(interconnect.read_mem(0x8100 + i), i as u8);
+    }
+}
+
+#[test]
+fn hblank_dma() {
+    let mut gameboy = cgb();
+    setup_hdma(&mut gameboy.interconnect);
+    gameboy.interconnect.write_mem(HDMA5, 0x80 | 0x03);
+    assert_eq!(gameboy.interconnect.read_mem(HDMA5), 0x03);
+
+    // One block per line, the CPU waits 8 M-cycles for it
+    let interconnect = &mut gameboy.interconnect;
+    let mut start = interconnect.cycles;
+    while interconnect.read_mem(HDMA5) == 0x03 {
+        start = interconnect.cycles;
+        interconnect.emu_tick(1);
+    }
+    assert_eq!(interconnect.cycles - start, 4 + 8 * 4);
+    assert_eq!(gameboy.interconnect.read_mem(HDMA5), 0x02);
+    assert_eq!(gameboy.interconnect.read_mem(0x810F), 0x0F);
+    assert_eq!(gameboy.interconnect.read_mem(0x8110), 0x00);
+
+    // Clearing bit 7 stops the transfer
+    gameboy.interconnect.write_mem(HDMA5, 0x00);
+    assert_eq!(gameboy.interconnect.read_mem(HDMA5), 0x82);
+
+    run_frame(&mut gameboy);
+    assert_eq!(gameboy.interconnect.read_mem(0x8110), 0x00);
+}
+
+#[test]
+fn compatibility_shades() {
+    let mut gameboy = GameBoy::from_rom_bytes(&rom_with(0x00, 0x00, 0x00, 0x00), None).unwrap();
+    let ppu = &mut gameboy.interconnect.ppu;
+    ppu.update_palette(&PaletteType::Background, 0xE4);
+    ppu.set_shades(CGB_COMPATIBILITY_SHADES);
+
+    assert_eq!(ppu.bg_palette, CGB_COMPATIBILITY_SHADES[0]);
+}
+
+#[test]
+fn dmg_game_on_cgb_uses_compatibility_shades() {
+    let mut gameboy = GameBoy::new();
+    gameboy.model_override = Some(Model::Cgb);
+    gameboy
+        .load_rom(
+            &rom_with(0x00, 0x00, 0x00, 0x00),
+            None,
+            ChecksumPolicy::default(),
+        )
+        .unwrap();
+
+    // CGB hardware without its features, DMG PPU with CGB colors
+    assert_eq!(gameboy.interconnect.model, Model::Cgb);
+    assert!(!gameboy.interconnect.cgb_mode());
+    assert!(!gameboy.interconnect.ppu.cgb);
+    assert_eq!(gameboy.cpu.registers.a, 0x11);
+
+    gameboy.interconnect.write_mem(0xFF47, 0xE4);
+    assert_eq!(
+        gameboy.interconnect.ppu.bg_palette,
+        CGB_COMPATIBILITY_SHADES[0]
+    );
+
+    // CGB games are left alone
+    let mut gameboy = GameBoy::new();
+    gameboy.model_override = Some(Model::Cgb);
+    gameboy
+        .load_rom(
+            &rom_with(0x00, 0x00, 0x00, 0x80),
+            None,
+            ChecksumPolicy::default(),
+        )
+        .unwrap();
+    assert!(gameboy.interconnect.ppu.cgb);
+}
@@ -0,0 +1,209 @@
+// Not every test binary uses every helper
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustboy::harness::{run_test_rom, TestResult};
+use rustboy::interconnect::cartridge::cartridge_info::header_checksum;
+use rustboy::interconnect::ppu::Renderer;
+use rustboy::screenshot::{capture_with, compare, Image, ScreenshotDiff};
+
+// Roms aren't checked in, tests without them are skipped unless this is set (e.g. in CI)
+const ROMS_REQUIRED_VAR: &str = "ROM_TESTS_REQUIRED";
+
+/// A rom that loops at the entry point (JR -2) with the given header bytes and a valid checksum
+///
+/// The rom is as big as `rom_size` says, 32 KiB << `rom_size`
+pub fn rom_with(cart_type: u8, rom_size: u8, ram_size: u8, cgb_flag: u8) -> Vec<u8> {
+    let mut rom = vec![0; 0x8000 << rom_size];
+    rom[0x100..0x102].copy_from_slice(&[0x18, 0xFE]);
+    rom[0x143] = cgb_flag;
+    rom[0x147] = cart_type;
+    rom[0x148] = rom_size;
+    rom[0x149] = ram_size;
+    rom[0x14D] = header_checksum(&rom);
+    rom
+}
+
+/// Skip a test whose rom is missing, or fail it when `ROM_TESTS_REQUIRED` is set
+pub fn missing_rom(path: &str, reason: &str) {
+    let required = std::env::var(ROMS_REQUIRED_VAR).is_ok_and(|value| value != "0");
+    assert!(
+        !required,
+        "{}: {} ({} IS SET)",
+        path, reason, ROMS_REQUIRED_VAR
+    );
+
+    println!("SKIPPING {}: {}", path, reason);
+}
+
+/// Run every rom in `dir` (sorted by name), printing a line per rom
+///
+/// Returns `None` when the directory doesn't exist so suites without roms checked out are skipped,
+/// see `missing_rom`
+pub fn run_dir(dir: &str, max_frames: u64) -> Option<Vec<(String, TestResult)>> {
+    let dir_path = Path::new(dir);
+    if !dir_path.is_dir() {
+        missing_rom(dir, "DIRECTORY NOT FOUND");
+        return None;
+    }
+
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir_path)
+        .expect("NOT A DIRECTORY")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "gb"))
+        .collect();
+
+    entries.sort();
+
+    let results = entries
+        .iter()
+        .map(|path| run_file(path, max_frames))
+        .collect();
+
+    Some(results)
+}
+
+/// Run a single rom, returning `None` when it doesn't exist
+pub fn run_rom(path: &str, max_frames: u64) -> Option<Vec<(String, TestResult)>> {
+    if !Path::new(path).is_file() {
+        missing_rom(path, "ROM NOT FOUND");
+        return None;
+    }
+
+    Some(vec![run_file(Path::new(path), max_frames)])
+}
+
+/// Run a single rom, printing its result
+pub fn run_file(path: &Path, max_frames: u64) -> (String, TestResult) {
+    let rom = fs::read(path).expect("FAILED TO READ ROM");
+    let name = path.file_name().unwrap().to_string_lossy().to_string();
+
+    let result = run_test_rom(&rom, max_frames).expect("COULD NOT LOAD ROM");
+    if result.passed() {
+        println!("{}: \x1B[32m{:?}\x1B[0m", name, result.reason);
+    } else {
+        println!(
+            "{}: \x1B[31m{:?}\x1B[0m after {} frames\n{}",
+            name, result.reason, result.frames, result.serial
+        );
+    }
+
+    (name, result)
+}
+
+/// Fail the test listing every rom that didn't pass
+pub fn assert_all_passed(results: &[(String, TestResult)]) {
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|(_, result)| !result.passed())
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    assert!(failed.is_empty(), "FAILED: {:?}", failed);
+}
+
+/// Run `rom` for `frames` frames and compare the screen against the png at `reference`
+///
+/// The screenshot and a diff image (differing pixels in red) are written next to the test
+/// binary's temporary directory, in a folder per renderer, so failures can be inspected. Returns
+/// `None` when the rom or the reference image doesn't exist, see `missing_rom`.
+pub fn compare_screenshot(
+    rom: &str,
+    reference: &str,
+    frames: u64,
+    renderer: Renderer,
+) -> Option<ScreenshotDiff> {
+    let (rom_path, reference_path) = (Path::new(rom), Path::new(reference));
+    if !rom_path.is_file() || !reference_path.is_file() {
+        missing_rom(rom, "ROM OR REFERENCE IMAGE NOT FOUND");
+        return None;
+    }
+
+    let rom_bytes = fs::read(rom_path).expect("FAILED TO READ ROM");
+    let expected = Image::load_png(reference_path).expect("FAILED TO READ REFERENCE IMAGE");
+    let actual = capture_with(&rom_bytes, frames, renderer).expect("COULD NOT LOAD ROM");
+    let diff = compare(&actual, &expected).expect("FAILED TO COMPARE SCREENSHOT");
+
+    let name = rom_path.file_stem().unwrap().to_string_lossy().to_string();
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+        .join("screenshots")
+        .join(format!("{:?}", renderer).to_lowercase());
+    fs::create_dir_all(&out_dir).expect("FAILED TO CREATE SCREENSHOT DIRECTORY");
+
+    let actual_path = out_dir.join(format!("{}.png", name));
+    actual
+        .save_png(&actual_path)
+        .expect("FAILED TO SAVE SCREENSHOT");
+
+    if diff.matches() {
+        println!("{}: \x1B[32mMATCHES\x1B[0m", name);
+    } else {
+        let diff_path = out_dir.join(format!("{}-diff.png", name));
+        diff.diff_image(&actual)
+            .save_png(&diff_path)
+            .expect("FAILED TO SAVE DIFF IMAGE");
+
+        println!(
+            "{}: \x1B[31mDIFFERS\x1B[0m ({}, {})\n{}",
+            name,
+            actual_path.display(),
+            diff_path.display(),
+            diff
+        );
+    }
+
+    Some(diff)
+}
+
+/// Compare every rom in `dir` against the png with the same name in `reference_dir`
+pub fn compare_screenshots_in_dir(
+    dir: &str,
+    reference_dir: &str,
+    frames: u64,
+    renderer: Renderer,
+) -> Option<Vec<(String, ScreenshotDiff)>> {
+    let dir_path = Path::new(dir);
+    if !dir_path.is_dir() {
+        missing_rom(dir, "DIRECTORY NOT FOUND");
+        return None;
+    }
+
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir_path)
+        .expect("NOT A DIRECTORY")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "gb"))
+        .collect();
+
+    entries.sort();
+
+    let results = entries
+        .iter()
+        .filter_map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            let reference = Path::new(reference_dir).join(format!("{}.png", name));
+
+            compare_screenshot(
+                path.to_str().unwrap(),
+                reference.to_str().unwrap(),
+                frames,
+                renderer,
+            )
+            .map(|diff| (name, diff))
+        })
+        .collect();
+
+    Some(results)
+}
+
+/// Fail the test listing every rom whose screen didn't match its reference
+pub fn assert_all_match(results: &[(String, ScreenshotDiff)]) {
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|(_, diff)| !diff.matches())
+        .map(|(name, _)| name.as_str())
+        .collect();
+
+    assert!(failed.is_empty(), "SCREENSHOTS DIFFER: {:?}", failed);
+}
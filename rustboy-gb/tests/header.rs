@@ -0,0 +1,101 @@
+use rustboy::interconnect::cartridge::cartridge_info::{header_checksum, CartridgeType, LoadError};
+use rustboy::interconnect::cartridge::header::{
+    compute_global_checksum, CartridgeHeader, CgbFlag, Destination, Licensee, NINTENDO_LOGO,
+};
+
+// 64 KiB MBC1 rom with every header field filled in and valid checksums
+fn rom_with_title(title: &[u8], cgb_flag: u8) -> Vec<u8> {
+    let mut rom = vec![0; 0x10000];
+    rom[0x104..0x134].copy_from_slice(&NINTENDO_LOGO);
+    // 16 character titles overlap the CGB flag
+    rom[0x143] = cgb_flag;
+    rom[0x134..0x134 + title.len()].copy_from_slice(title);
+    rom[0x144..0x146].copy_from_slice(b"01");
+    rom[0x146] = 0x03;
+    rom[0x147] = 0x01;
+    rom[0x148] = 0x01;
+    rom[0x14A] = 0x01;
+    rom[0x14B] = 0x33;
+    rom[0x14C] = 0x02;
+    rom[0x14D] = header_checksum(&rom);
+
+    let global_checksum = compute_global_checksum(&rom);
+    rom[0x14E..0x150].copy_from_slice(&global_checksum.to_be_bytes());
+    rom
+}
+
+#[test]
+fn parses_dmg_header() {
+    let header = CartridgeHeader::parse(&rom_with_title(b"SIXTEEN CHAR NAM", 0x00)).unwrap();
+
+    assert!(header.valid_logo);
+    assert_eq!(header.title, "SIXTEEN CHAR NAM");
+    assert_eq!(header.manufacturer_code, None);
+    assert_eq!(header.cgb_flag, CgbFlag::Dmg);
+    assert!(header.sgb_flag);
+    assert_eq!(header.licensee, Licensee::New(*b"01"));
+    assert_eq!(header.licensee.publisher(), "Nintendo R&D1");
+    assert_eq!(header.cartridge_type, CartridgeType::MBC1);
+    assert_eq!(header.rom_size, 0x10000);
+    assert_eq!(header.ram_size, 0);
+    assert_eq!(header.destination, Destination::Overseas);
+    assert_eq!(header.mask_rom_version, 2);
+    assert!(header.valid_header_checksum);
+    assert!(header.valid_global_checksum);
+    assert!(!header.oversized());
+}
+
+#[test]
+fn parses_cgb_title_and_manufacturer() {
+    let header = CartridgeHeader::parse(&rom_with_title(b"POKEMON_SLVAAXJ", 0x80)).unwrap();
+
+    assert_eq!(header.title, "POKEMON_SLV");
+    assert_eq!(header.manufacturer_code.as_deref(), Some("AAXJ"));
+    assert_eq!(header.cgb_flag, CgbFlag::Supported);
+
+    let header = CartridgeHeader::parse(&rom_with_title(b"SHORT", 0xC0)).unwrap();
+
+    assert_eq!(header.title, "SHORT");
+    assert_eq!(header.manufacturer_code, None);
+    assert_eq!(header.cgb_flag, CgbFlag::Only);
+}
+
+#[test]
+fn old_licensee_code() {
+    let mut rom = rom_with_title(b"TITLE", 0x00);
+    rom[0x14B] = 0x01;
+
+    let header = CartridgeHeader::parse(&rom).unwrap();
+    assert_eq!(header.licensee, Licensee::Old(0x01));
+    assert_eq!(header.licensee.code(), "01");
+    assert_eq!(header.licensee.publisher(), "Nintendo");
+}
+
+#[test]
+fn detects_bad_checksums() {
+    let mut rom = rom_with_title(b"TITLE", 0x00);
+    rom[0x14D] ^= 0xFF;
+    rom[0x8000] = 0x01;
+
+    let header = CartridgeHeader::parse(&rom).unwrap();
+    assert!(!header.valid_header_checksum);
+    assert!(!header.valid_global_checksum);
+}
+
+#[test]
+fn cross_checks_rom_size() {
+    let mut rom = rom_with_title(b"TITLE", 0x00);
+    rom.extend_from_slice(&[0; 0x100]);
+
+    let header = CartridgeHeader::parse(&rom).unwrap();
+    assert!(header.oversized());
+
+    let result = CartridgeHeader::parse(&rom[..0x8000]);
+    assert_eq!(
+        result.err(),
+        Some(LoadError::TruncatedRom {
+            expected: 0x10000,
+            actual: 0x8000
+        })
+    );
+}
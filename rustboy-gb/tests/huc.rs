@@ -0,0 +1,290 @@
+mod common;
+
+use common::rom_with;
+use rustboy::constants::CLOCK_SPEED;
+use rustboy::gameboy::GameBoy;
+use rustboy::interconnect::cartridge::cartridge_info::CartridgeType;
+use rustboy::interconnect::infrared::{InfraredLink, InfraredSocket};
+use rustboy::interconnect::Interconnect;
+
+// Written to 0x0000 - 0x1FFF
+const HUC1_RAM: u8 = 0x0A;
+const HUC3_RAM_READ_ONLY: u8 = 0x00;
+const HUC3_RAM: u8 = 0x0A;
+const HUC3_COMMAND: u8 = 0x0B;
+const HUC3_RESPONSE: u8 = 0x0C;
+const HUC3_SEMAPHORE: u8 = 0x0D;
+const IR: u8 = 0x0E;
+
+const RAM_SIZE: usize = 0x8000;
+const HUC3_FOOTER_SIZE: usize = 17;
+const CYCLES_PER_MINUTE: u32 = CLOCK_SPEED as u32 * 60;
+
+// 128 KiB of ROM with each bank's number at its start, 32 KiB of RAM
+fn rom(cartridge_type: u8) -> Vec<u8> {
+    let mut rom = rom_with(cartridge_type, 0x02, 0x03, 0x00);
+    for bank in 0..8 {
+        rom[bank * 0x4000] = bank as u8;
+    }
+    rom
+}
+
+fn boot_huc1() -> GameBoy {
+    GameBoy::from_rom_bytes(&rom(0xFF), None).unwrap()
+}
+
+fn boot_huc3() -> GameBoy {
+    GameBoy::from_rom_bytes(&rom(0xFE), None).unwrap()
+}
+
+// Runs a clock command, returning what the response register reads after it
+fn clock_command(interconnect: &mut Interconnect, command: u8) -> u8 {
+    interconnect.write_mem(0x0000, HUC3_COMMAND);
+    interconnect.write_mem(0xA000, command);
+    interconnect.write_mem(0x0000, HUC3_RESPONSE);
+    interconnect.read_mem(0xA000)
+}
+
+fn set_clock_index(interconnect: &mut Interconnect, index: u8) {
+    clock_command(interconnect, 0x40 | (index & 0x0F));
+    clock_command(interconnect, 0x50 | (index >> 4));
+}
+
+// Reads `count` nibbles starting at `index`, lowest first
+fn read_clock(interconnect: &mut Interconnect, index: u8, count: usize) -> u32 {
+    set_clock_index(interconnect, index);
+    (0..count).fold(0, |value, i| {
+        let response = clock_command(interconnect, 0x10);
+        assert_eq!(response & 0xF0, 0x10);
+        value | (u32::from(response & 0x0F) << (i * 4))
+    })
+}
+
+fn write_clock(interconnect: &mut Interconnect, index: u8, count: usize, value: u32) {
+    set_clock_index(interconnect, index);
+    for i in 0..count {
+        clock_command(interconnect, 0x30 | ((value >> (i * 4)) as u8 & 0x0F));
+    }
+}
+
+fn minutes(interconnect: &mut Interconnect) -> u32 {
+    read_clock(interconnect, 0x00, 3)
+}
+
+fn days(interconnect: &mut Interconnect) -> u32 {
+    read_clock(interconnect, 0x03, 4)
+}
+
+#[test]
+fn hudson_carts_are_recognised() {
+    let huc1 = boot_huc1();
+    let cartridge = &huc1.interconnect.cartridge;
+    assert_eq!(
+        cartridge.header.cartridge_type,
+        CartridgeType::HuC1RAMBattery
+    );
+    assert!(cartridge.has_battery());
+    assert!(cartridge.has_infrared());
+    assert_eq!(cartridge.export_ram().len(), RAM_SIZE);
+
+    let huc3 = boot_huc3();
+    let cartridge = &huc3.interconnect.cartridge;
+    assert_eq!(cartridge.header.cartridge_type, CartridgeType::HuC3);
+    assert!(cartridge.has_battery());
+    assert!(cartridge.has_infrared());
+    assert_eq!(cartridge.export_ram().len(), RAM_SIZE + HUC3_FOOTER_SIZE);
+}
+
+#[test]
+fn huc1_switches_banks() {
+    let mut gameboy = boot_huc1();
+    let interconnect = &mut gameboy.interconnect;
+
+    assert_eq!(interconnect.read_mem(0x4000), 1);
+    interconnect.write_mem(0x2000, 5);
+    assert_eq!(interconnect.read_mem(0x4000), 5);
+    interconnect.write_mem(0x2000, 0);
+    assert_eq!(interconnect.read_mem(0x4000), 1);
+
+    // RAM needs no enabling
+    interconnect.write_mem(0xA000, 0x11);
+    interconnect.write_mem(0x4000, 2);
+    interconnect.write_mem(0xA000, 0x22);
+    assert_eq!(interconnect.read_mem(0xA000), 0x22);
+    interconnect.write_mem(0x4000, 0);
+    assert_eq!(interconnect.read_mem(0xA000), 0x11);
+}
+
+#[test]
+fn huc1_ir_mode_maps_the_port_over_ram() {
+    let mut gameboy = boot_huc1();
+    let interconnect = &mut gameboy.interconnect;
+    interconnect.write_mem(0xA000, 0x42);
+
+    // Nothing in front of the port by default
+    interconnect.write_mem(0x0000, IR);
+    assert_eq!(interconnect.read_mem(0xA000), 0xC0);
+
+    // Writing the LED leaves RAM alone
+    interconnect.write_mem(0xA000, 0x01);
+    interconnect.write_mem(0x0000, HUC1_RAM);
+    assert_eq!(interconnect.read_mem(0xA000), 0x42);
+}
+
+#[test]
+fn two_carts_see_each_others_led() {
+    let mut first = boot_huc1();
+    let mut second = boot_huc3();
+    let (first_port, second_port) = InfraredLink::pair();
+    first
+        .interconnect
+        .cartridge
+        .mbc
+        .set_infrared(Box::new(first_port));
+    second
+        .interconnect
+        .cartridge
+        .mbc
+        .set_infrared(Box::new(second_port));
+
+    let first = &mut first.interconnect;
+    let second = &mut second.interconnect;
+    first.write_mem(0x0000, IR);
+    second.write_mem(0x0000, IR);
+
+    first.write_mem(0xA000, 0x01);
+    assert_eq!(second.read_mem(0xA000), 0xC1);
+    assert_eq!(first.read_mem(0xA000), 0xC0);
+
+    second.write_mem(0xA000, 0x01);
+    first.write_mem(0xA000, 0x00);
+    assert_eq!(second.read_mem(0xA000), 0xC0);
+    assert_eq!(first.read_mem(0xA000), 0xC1);
+}
+
+// Polls the IR port until it reads `expected`, light from a socket takes a moment to arrive
+fn wait_for_light(interconnect: &mut Interconnect, expected: u8) {
+    for _ in 0..1000 {
+        if interconnect.read_mem(0xA000) == expected {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    assert_eq!(interconnect.read_mem(0xA000), expected);
+}
+
+#[test]
+fn carts_see_each_others_led_over_a_socket() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    drop(listener);
+
+    let mut first = boot_huc1();
+    let mut second = boot_huc3();
+    let first_port = InfraredSocket::listen(&addr).unwrap();
+    let second_port = InfraredSocket::connect(&addr).unwrap();
+    first
+        .interconnect
+        .cartridge
+        .mbc
+        .set_infrared(Box::new(first_port));
+    second
+        .interconnect
+        .cartridge
+        .mbc
+        .set_infrared(Box::new(second_port));
+
+    let first = &mut first.interconnect;
+    let second = &mut second.interconnect;
+    first.write_mem(0x0000, IR);
+    second.write_mem(0x0000, IR);
+
+    first.write_mem(0xA000, 0x01);
+    wait_for_light(second, 0xC1);
+    assert_eq!(first.read_mem(0xA000), 0xC0);
+
+    first.write_mem(0xA000, 0x00);
+    second.write_mem(0xA000, 0x01);
+    wait_for_light(second, 0xC0);
+    wait_for_light(first, 0xC1);
+}
+
+#[test]
+fn huc3_mode_picks_what_ram_area_maps() {
+    let mut gameboy = boot_huc3();
+    let interconnect = &mut gameboy.interconnect;
+
+    assert_eq!(interconnect.read_mem(0x4000), 1);
+    interconnect.write_mem(0x2000, 7);
+    assert_eq!(interconnect.read_mem(0x4000), 7);
+
+    // RAM is read only until mode 0xA
+    interconnect.write_mem(0x0000, HUC3_RAM_READ_ONLY);
+    interconnect.write_mem(0xA000, 0x42);
+    assert_eq!(interconnect.read_mem(0xA000), 0x00);
+
+    interconnect.write_mem(0x0000, HUC3_RAM);
+    interconnect.write_mem(0xA000, 0x42);
+    interconnect.write_mem(0x0000, HUC3_RAM_READ_ONLY);
+    assert_eq!(interconnect.read_mem(0xA000), 0x42);
+
+    interconnect.write_mem(0x0000, HUC3_SEMAPHORE);
+    assert_eq!(interconnect.read_mem(0xA000), 0x01);
+
+    // The clock answers the status command
+    assert_eq!(clock_command(interconnect, 0x62), 0x61);
+}
+
+#[test]
+fn huc3_clock_is_set_and_read_through_commands() {
+    let mut gameboy = boot_huc3();
+    let interconnect = &mut gameboy.interconnect;
+
+    write_clock(interconnect, 0x00, 3, 1_439);
+    write_clock(interconnect, 0x03, 4, 41);
+    assert_eq!(minutes(interconnect), 1_439);
+    assert_eq!(days(interconnect), 41);
+
+    // Alarm nibbles are kept as well
+    write_clock(interconnect, 0x58, 3, 600);
+    write_clock(interconnect, 0x5F, 1, 1);
+    assert_eq!(read_clock(interconnect, 0x58, 3), 600);
+    assert_eq!(read_clock(interconnect, 0x5F, 1), 1);
+
+    // A minute later it's the next day
+    interconnect.cartridge.tick(CYCLES_PER_MINUTE - 4);
+    assert_eq!(minutes(interconnect), 1_439);
+    interconnect.cartridge.tick(4);
+    assert_eq!(minutes(interconnect), 0);
+    assert_eq!(days(interconnect), 42);
+}
+
+#[test]
+fn huc3_clock_is_saved_with_ram() {
+    let mut gameboy = boot_huc3();
+    let interconnect = &mut gameboy.interconnect;
+    interconnect.write_mem(0x0000, HUC3_RAM);
+    interconnect.write_mem(0xA000, 0x42);
+    write_clock(interconnect, 0x00, 3, 100);
+    write_clock(interconnect, 0x03, 4, 7);
+
+    let save = interconnect.cartridge.export_ram_at(500);
+    assert_eq!(save.len(), RAM_SIZE + HUC3_FOOTER_SIZE);
+    assert_eq!(&save[RAM_SIZE..RAM_SIZE + 8], &500u64.to_le_bytes());
+
+    // A day and an hour pass while the game is off
+    let mut gameboy = boot_huc3();
+    let interconnect = &mut gameboy.interconnect;
+    let cartridge = &mut interconnect.cartridge;
+    cartridge.import_ram_at(&save, 500 + 25 * 3_600).unwrap();
+
+    interconnect.write_mem(0x0000, HUC3_RAM_READ_ONLY);
+    assert_eq!(interconnect.read_mem(0xA000), 0x42);
+    assert_eq!(minutes(interconnect), 160);
+    assert_eq!(days(interconnect), 8);
+
+    // Saves without the footer keep the clock, other sizes are refused
+    let cartridge = &mut interconnect.cartridge;
+    assert!(cartridge.import_ram_at(&save[..RAM_SIZE], 0).is_ok());
+    assert!(cartridge.import_ram_at(&save[..RAM_SIZE + 8], 0).is_err());
+}
@@ -0,0 +1,147 @@
+use std::net::TcpListener;
+use std::thread;
+
+use rustboy::constants::{SERIAL_TRANSFER_CONTROL, SERIAL_TRASFER_DATA};
+use rustboy::gameboy::GameBoy;
+use rustboy::interconnect::cartridge::cartridge_info::header_checksum;
+use rustboy::interconnect::serial::SocketLink;
+
+// Give up on a transfer after this many T-cycles
+const MAX_CYCLES: u64 = 1 << 20;
+
+// Waits a while, sends 0x42 with the internal clock and loops forever once it went through
+const MASTER: &[u8] = &[
+    0x0E, 0x08, // LD C, 8
+    0x06, 0x00, // LD B, 0
+    0x05, // DEC B
+    0x20, 0xFD, // JR NZ, -3
+    0x0D, // DEC C
+    0x20, 0xF8, // JR NZ, -8
+    0x3E, 0x42, // LD A, 0x42
+    0xE0, 0x01, // LDH (SB), A
+    0x3E, 0x81, // LD A, 0x81
+    0xE0, 0x02, // LDH (SC), A
+    0xF0, 0x02, // LDH A, (SC)
+    0xE6, 0x80, // AND 0x80
+    0x20, 0xFA, // JR NZ, -6
+    0x18, 0xFE, // JR -2
+];
+
+// Waits for the other end to clock out 0x99
+const SLAVE: &[u8] = &[
+    0x3E, 0x99, // LD A, 0x99
+    0xE0, 0x01, // LDH (SB), A
+    0x3E, 0x80, // LD A, 0x80
+    0xE0, 0x02, // LDH (SC), A
+    0xF0, 0x02, // LDH A, (SC)
+    0xE6, 0x80, // AND 0x80
+    0x20, 0xFA, // JR NZ, -6
+    0x18, 0xFE, // JR -2
+];
+
+fn rom(code: &[u8]) -> Vec<u8> {
+    let mut rom = vec![0; 0x8000];
+    rom[0x100..0x100 + code.len()].copy_from_slice(code);
+    rom[0x14D] = header_checksum(&rom);
+    rom
+}
+
+// What a Game Boy ended up with once its transfer finished
+#[derive(Debug, PartialEq, Eq)]
+struct Transfer {
+    cycles: u64,
+    received: u8,
+    sent: Vec<u8>,
+}
+
+fn run(code: &[u8], link: SocketLink) -> Transfer {
+    let mut gameboy = GameBoy::from_rom_bytes(&rom(code), None).unwrap();
+    gameboy.interconnect.serial.set_echo(false);
+    gameboy.interconnect.serial.connect(Box::new(link));
+
+    // Wait for the transfer to start, then to finish
+    let interconnect = &mut gameboy.interconnect;
+    while interconnect.read_mem(SERIAL_TRANSFER_CONTROL) & 0x80 == 0 {
+        gameboy.cpu.step(interconnect);
+    }
+    while interconnect.read_mem(SERIAL_TRANSFER_CONTROL) & 0x80 != 0 {
+        assert!(interconnect.cycles < MAX_CYCLES, "TRANSFER NEVER FINISHED");
+        gameboy.cpu.step(interconnect);
+    }
+
+    let transfer = Transfer {
+        cycles: interconnect.cycles,
+        received: interconnect.read_mem(SERIAL_TRASFER_DATA),
+        sent: interconnect.serial.take_bytes(),
+    };
+
+    // Keep the other end in step until it is done too
+    while interconnect.cycles < MAX_CYCLES {
+        gameboy.cpu.step(interconnect);
+    }
+
+    transfer
+}
+
+fn link_over_tcp() -> (Transfer, Transfer) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+
+    let master = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        run(MASTER, SocketLink::tcp(stream).unwrap())
+    });
+    let slave = thread::spawn(move || run(SLAVE, SocketLink::connect(&addr).unwrap()));
+
+    (master.join().unwrap(), slave.join().unwrap())
+}
+
+#[test]
+fn bytes_cross_over_tcp() {
+    let (master, slave) = link_over_tcp();
+
+    assert_eq!(master.sent, [0x42]);
+    assert_eq!(master.received, 0x99);
+    assert_eq!(slave.sent, [0x99]);
+    assert_eq!(slave.received, 0x42);
+}
+
+#[test]
+fn transfers_are_deterministic() {
+    let first = link_over_tcp();
+    for _ in 0..3 {
+        assert_eq!(link_over_tcp(), first);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn bytes_cross_over_unix_socket() {
+    let path = std::env::temp_dir().join(format!("rustboy-link-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    // A socket left behind by an earlier run doesn't get in the way
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    let addr = format!("unix:{}", path.display());
+
+    let master = {
+        let addr = addr.clone();
+        thread::spawn(move || run(MASTER, SocketLink::listen(&addr).unwrap()))
+    };
+
+    // Wait for the other end to listen
+    let link = loop {
+        match SocketLink::connect(&addr) {
+            Ok(link) => break link,
+            Err(_) => thread::yield_now(),
+        }
+    };
+    let slave = run(SLAVE, link);
+    let master = master.join().unwrap();
+    assert!(!path.exists());
+
+    assert_eq!(master.received, 0x99);
+    assert_eq!(slave.received, 0x42);
+}
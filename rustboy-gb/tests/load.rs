@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::PathBuf;
+
+use rustboy::gameboy::{ChecksumPolicy, GameBoy, LoadError};
+use rustboy::interconnect::cartridge::cartridge_info::{header_checksum, CartridgeType};
+
+// Smallest valid rom: 32 KiB, no mapper, no ram, valid header checksum
+fn blank_rom() -> Vec<u8> {
+    let mut rom = vec![0; 0x8000];
+    rom[0x14D] = header_checksum(&rom);
+    rom
+}
+
+#[test]
+fn loads_valid_rom() {
+    let gameboy = GameBoy::from_rom_bytes(&blank_rom(), None).unwrap();
+
+    assert!(gameboy.booted);
+    assert_eq!(gameboy.cpu.pc, 0x100);
+    assert_eq!(
+        gameboy.interconnect.cartridge.header.cartridge_type,
+        CartridgeType::ROMOnly
+    );
+    assert!(gameboy.interconnect.cartridge.header.valid_header_checksum);
+}
+
+#[test]
+fn starts_in_boot_rom() {
+    let gameboy = GameBoy::from_rom_bytes(&blank_rom(), Some(&[0; 0x100])).unwrap();
+
+    assert_eq!(gameboy.cpu.pc, 0x0000);
+}
+
+#[test]
+fn rejects_truncated_rom() {
+    let result = GameBoy::from_rom_bytes(&[0; 0x100], None);
+    assert_eq!(
+        result.err(),
+        Some(LoadError::TruncatedRom {
+            expected: 0x150,
+            actual: 0x100
+        })
+    );
+
+    let mut rom = blank_rom();
+    rom[0x148] = 0x01;
+    rom[0x14D] = header_checksum(&rom);
+
+    let result = GameBoy::from_rom_bytes(&rom, None);
+    assert_eq!(
+        result.err(),
+        Some(LoadError::TruncatedRom {
+            expected: 0x10000,
+            actual: 0x8000
+        })
+    );
+}
+
+#[test]
+fn rejects_unsupported_mapper() {
+    let mut rom = blank_rom();
+    rom[0x147] = 0xFD;
+
+    let result = GameBoy::from_rom_bytes(&rom, None);
+    assert_eq!(
+        result.err(),
+        Some(LoadError::UnsupportedCartridgeType(0xFD))
+    );
+}
+
+#[test]
+fn rejects_bad_size_codes() {
+    let mut rom = blank_rom();
+    rom[0x148] = 0x20;
+    let result = GameBoy::from_rom_bytes(&rom, None);
+    assert_eq!(result.err(), Some(LoadError::InvalidRomSize(0x20)));
+
+    let mut rom = blank_rom();
+    rom[0x149] = 0x20;
+    let result = GameBoy::from_rom_bytes(&rom, None);
+    assert_eq!(result.err(), Some(LoadError::InvalidRamSize(0x20)));
+}
+
+#[test]
+fn rejects_bad_boot_rom() {
+    let result = GameBoy::from_rom_bytes(&blank_rom(), Some(&[0; 0x80]));
+    assert_eq!(result.err(), Some(LoadError::InvalidBootRomSize(0x80)));
+}
+
+#[test]
+fn checksum_policy() {
+    let mut rom = blank_rom();
+    rom[0x14D] = rom[0x14D].wrapping_add(1);
+
+    assert!(GameBoy::from_rom_bytes(&rom, None).is_ok());
+    assert!(GameBoy::from_rom_bytes_with_policy(&rom, None, ChecksumPolicy::Ignore).is_ok());
+
+    let result = GameBoy::from_rom_bytes_with_policy(&rom, None, ChecksumPolicy::Reject);
+    assert_eq!(
+        result.err(),
+        Some(LoadError::BadHeaderChecksum {
+            expected: rom[0x14D],
+            actual: header_checksum(&rom)
+        })
+    );
+}
+
+#[test]
+fn save_states_are_named_after_the_rom() {
+    let mut gameboy = GameBoy::from_rom_bytes(&blank_rom(), None).unwrap();
+
+    // Roms loaded from memory fall back to the title, a blank one to a fixed name
+    assert_eq!(gameboy.state_save_path(), PathBuf::from("rustboy.state"));
+    gameboy.interconnect.cartridge.header.title = String::from("A/B");
+    assert_eq!(gameboy.state_save_path(), PathBuf::from("A_B.state"));
+
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("load");
+    fs::create_dir_all(&dir).unwrap();
+    gameboy.rom_path = Some(dir.join("game.gb"));
+    let path = gameboy.state_save_path();
+    assert_eq!(path, dir.join("game.state"));
+
+    let _ = fs::remove_file(&path);
+    gameboy.save_state(&path).unwrap();
+    assert!(path.is_file());
+
+    // Failing to write is an error, not a panic
+    let missing = dir.join("missing").join("game.state");
+    assert!(gameboy.save_state(&missing).is_err());
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use common::rom_with;
+use rustboy::gameboy::GameBoy;
+use rustboy::interconnect::cartridge::cartridge_info::CartridgeType;
+use rustboy::interconnect::Interconnect;
+
+// EEPROM pins at 0xA080
+const EEPROM: u16 = 0xA080;
+const CS: u8 = 0x80;
+const CLK: u8 = 0x40;
+const DI: u8 = 0x02;
+const DO: u8 = 0x01;
+
+// Opcodes followed by an address, and the 00 opcode commands picked by the top address bits
+const READ: (u16, u16) = (0b10, 0);
+const WRITE: (u16, u16) = (0b01, 0);
+const EWEN: (u16, u16) = (0b00, 0b11 << 6);
+const EWDS: (u16, u16) = (0b00, 0b00);
+const ERAL: (u16, u16) = (0b00, 0b10 << 6);
+
+fn boot() -> GameBoy {
+    let mut gameboy = GameBoy::from_rom_bytes(&rom_with(0x22, 0x01, 0x00, 0x00), None).unwrap();
+    enable_registers(&mut gameboy.interconnect);
+    gameboy
+}
+
+fn enable_registers(interconnect: &mut Interconnect) {
+    interconnect.write_mem(0x0000, 0x0A);
+    interconnect.write_mem(0x4000, 0x40);
+}
+
+fn latch(interconnect: &mut Interconnect) {
+    interconnect.write_mem(0xA000, 0x55);
+    interconnect.write_mem(0xA010, 0xAA);
+}
+
+fn accelerometer(interconnect: &Interconnect) -> (u16, u16) {
+    let read = |addr| u16::from(interconnect.read_mem(addr));
+    (
+        read(0xA020) | (read(0xA030) << 8),
+        read(0xA040) | (read(0xA050) << 8),
+    )
+}
+
+fn clock(interconnect: &mut Interconnect, bit: bool) {
+    let di = if bit { DI } else { 0 };
+    interconnect.write_mem(EEPROM, CS | di);
+    interconnect.write_mem(EEPROM, CS | CLK | di);
+}
+
+// Start bit, opcode, address and 16 bits of data for writes
+fn command(interconnect: &mut Interconnect, (opcode, addr): (u16, u16), data: Option<u16>) {
+    interconnect.write_mem(EEPROM, 0x00);
+
+    let bits = (1 << 10) | (opcode << 8) | addr;
+    for bit in (0..11).rev() {
+        clock(interconnect, bits & (1 << bit) != 0);
+    }
+
+    if let Some(data) = data {
+        for bit in (0..16).rev() {
+            clock(interconnect, data & (1 << bit) != 0);
+        }
+    }
+}
+
+fn read_word(interconnect: &mut Interconnect, addr: u16) -> u16 {
+    command(interconnect, (READ.0, addr), None);
+    assert_eq!(interconnect.read_mem(EEPROM) & DO, 0, "dummy bit");
+
+    next_word(interconnect)
+}
+
+fn next_word(interconnect: &mut Interconnect) -> u16 {
+    (0..16).fold(0, |word, _| {
+        clock(interconnect, false);
+        (word << 1) | u16::from(interconnect.read_mem(EEPROM) & DO)
+    })
+}
+
+#[test]
+fn mbc7_cart_has_eeprom_and_accelerometer() {
+    let gameboy = boot();
+    let cartridge = &gameboy.interconnect.cartridge;
+
+    assert_eq!(
+        cartridge.header.cartridge_type,
+        CartridgeType::MBC7SensorRumbleRAMBattery
+    );
+    assert!(cartridge.has_battery());
+    assert!(cartridge.has_accelerometer());
+    assert_eq!(cartridge.export_ram(), vec![0xFF; 0x100]);
+}
+
+#[test]
+fn tilt_is_seen_once_latched() {
+    let mut gameboy = boot();
+
+    gameboy.set_tilt(1.0, -0.5);
+    assert_eq!(accelerometer(&gameboy.interconnect), (0x8000, 0x8000));
+
+    latch(&mut gameboy.interconnect);
+    assert_eq!(accelerometer(&gameboy.interconnect), (0x8240, 0x8198));
+
+    // Latching again needs an erase first
+    gameboy.set_tilt(0.0, 0.0);
+    gameboy.interconnect.write_mem(0xA010, 0xAA);
+    assert_eq!(accelerometer(&gameboy.interconnect), (0x8240, 0x8198));
+
+    latch(&mut gameboy.interconnect);
+    assert_eq!(accelerometer(&gameboy.interconnect), (0x81D0, 0x81D0));
+}
+
+#[test]
+fn registers_need_both_enables() {
+    let mut gameboy = GameBoy::from_rom_bytes(&rom_with(0x22, 0x01, 0x00, 0x00), None).unwrap();
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(0x0000, 0x0A);
+    latch(interconnect);
+    assert_eq!(interconnect.read_mem(0xA020), 0xFF);
+    assert_eq!(interconnect.read_mem(0xA060), 0xFF);
+
+    interconnect.write_mem(0x4000, 0x40);
+    assert_eq!(interconnect.read_mem(0xA060), 0x00);
+    assert_eq!(interconnect.read_mem(0xA070), 0xFF);
+}
+
+#[test]
+fn eeprom_words_are_written_and_read_back() {
+    let mut gameboy = boot();
+    let interconnect = &mut gameboy.interconnect;
+
+    // Writes are ignored until enabled
+    command(interconnect, (WRITE.0, 0x05), Some(0x1234));
+    assert_eq!(read_word(interconnect, 0x05), 0xFFFF);
+
+    command(interconnect, EWEN, None);
+    command(interconnect, (WRITE.0, 0x05), Some(0x1234));
+    command(interconnect, (WRITE.0, 0x06), Some(0xABCD));
+    assert_eq!(read_word(interconnect, 0x05), 0x1234);
+
+    // Reading on continues with the next word
+    assert_eq!(next_word(interconnect), 0xABCD);
+
+    let save = interconnect.cartridge.export_ram();
+    assert_eq!(&save[0x0A..0x0E], &[0x34, 0x12, 0xCD, 0xAB]);
+
+    command(interconnect, EWDS, None);
+    command(interconnect, ERAL, None);
+    assert_eq!(read_word(interconnect, 0x06), 0xABCD);
+
+    command(interconnect, EWEN, None);
+    command(interconnect, ERAL, None);
+    assert_eq!(read_word(interconnect, 0x06), 0xFFFF);
+}
+
+#[test]
+fn other_carts_ignore_tilt() {
+    let rom = rom_with(0x19, 0x01, 0x00, 0x00);
+    let mut gameboy = GameBoy::from_rom_bytes(&rom, None).unwrap();
+    gameboy.set_tilt(1.0, 1.0);
+    assert!(!gameboy.interconnect.cartridge.has_accelerometer());
+}
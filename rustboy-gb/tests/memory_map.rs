@@ -0,0 +1,137 @@
+mod common;
+
+use common::rom_with;
+use rustboy::constants::{
+    BOOT_ROM_DISABLE, INTERRUPT_FLAG, OPRI, SERIAL_TRANSFER_CONTROL, SERIAL_TRASFER_DATA, SVBK,
+};
+use rustboy::gameboy::GameBoy;
+use rustboy::interconnect::joypad::Key;
+use rustboy::interconnect::Interconnect;
+
+fn gameboy(cgb_flag: u8) -> GameBoy {
+    GameBoy::from_rom_bytes(&rom_with(0x00, 0x00, 0x00, cgb_flag), None).unwrap()
+}
+
+#[test]
+fn echo_ram_mirrors_work_ram() {
+    let mut gameboy = gameboy(0x00);
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(0xC123, 0x42);
+    assert_eq!(interconnect.read_mem(0xE123), 0x42);
+
+    interconnect.write_mem(0xFDFF, 0x24);
+    assert_eq!(interconnect.read_mem(0xDDFF), 0x24);
+}
+
+#[test]
+fn echo_ram_follows_work_ram_bank() {
+    let mut gameboy = gameboy(0x80);
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(SVBK, 3);
+    interconnect.write_mem(0xF000, 0x33);
+    assert_eq!(interconnect.read_mem(0xD000), 0x33);
+
+    interconnect.write_mem(SVBK, 1);
+    assert_ne!(interconnect.read_mem(0xF000), 0x33);
+}
+
+#[test]
+fn unusable_region() {
+    let mut gameboy = gameboy(0x00);
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(0xFEA0, 0x42);
+    assert_eq!(interconnect.read_mem(0xFEA0), 0x00);
+    assert_eq!(interconnect.read_mem(0xFEFF), 0x00);
+
+    let gameboy = self::gameboy(0x80);
+    assert_eq!(gameboy.interconnect.read_mem(0xFEA0), 0xAA);
+    assert_eq!(gameboy.interconnect.read_mem(0xFED5), 0xDD);
+}
+
+#[test]
+fn unused_bits_read_as_one() {
+    let mut gameboy = gameboy(0x00);
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(INTERRUPT_FLAG, 0x00);
+    assert_eq!(interconnect.read_mem(INTERRUPT_FLAG), 0xE0);
+
+    interconnect.write_mem(SERIAL_TRANSFER_CONTROL, 0x00);
+    assert_eq!(interconnect.read_mem(SERIAL_TRANSFER_CONTROL), 0x7E);
+
+    interconnect.write_mem(SERIAL_TRASFER_DATA, 0x00);
+    assert_eq!(interconnect.read_mem(SERIAL_TRASFER_DATA), 0x00);
+
+    // STAT bit 7, LY stays where the PPU put it
+    assert_eq!(interconnect.read_mem(0xFF41) & 0x80, 0x80);
+    let ly = interconnect.read_mem(0xFF44);
+    interconnect.write_mem(0xFF44, ly.wrapping_add(1));
+    assert_eq!(interconnect.read_mem(0xFF44), ly);
+}
+
+#[test]
+fn unmapped_io_reads_open_bus() {
+    let mut gameboy = gameboy(0x00);
+    let interconnect = &mut gameboy.interconnect;
+
+    for addr in [
+        0xFF03, 0xFF08, 0xFF0E, 0xFF4C, 0xFF4E, 0xFF57, 0xFF6C, 0xFF7F,
+    ] {
+        interconnect.write_mem(addr, 0x00);
+        assert_eq!(interconnect.read_mem(addr), 0xFF, "{:#X}", addr);
+    }
+}
+
+#[test]
+fn cgb_only_registers() {
+    let mut gameboy = gameboy(0x80);
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(OPRI, 0x00);
+    assert_eq!(interconnect.read_mem(OPRI), 0xFE);
+
+    interconnect.write_mem(SERIAL_TRANSFER_CONTROL, 0x00);
+    assert_eq!(interconnect.read_mem(SERIAL_TRANSFER_CONTROL), 0x7C);
+
+    interconnect.write_mem(0xFF75, 0xFF);
+    assert_eq!(interconnect.read_mem(0xFF75), 0xFF);
+    interconnect.write_mem(0xFF75, 0x00);
+    assert_eq!(interconnect.read_mem(0xFF75), 0x8F);
+}
+
+#[test]
+fn boot_rom_disable() {
+    let mut interconnect = Interconnect::new();
+    interconnect.load_boot_rom(&[0x31; 0x100]);
+    assert_eq!(interconnect.read_mem(0x0000), 0x31);
+
+    // Writing 0 does nothing
+    interconnect.write_mem(BOOT_ROM_DISABLE, 0x00);
+    assert_eq!(interconnect.read_mem(0x0000), 0x31);
+
+    interconnect.write_mem(BOOT_ROM_DISABLE, 0x01);
+    assert_eq!(interconnect.read_mem(0x0000), 0xFF);
+    assert_eq!(interconnect.read_mem(BOOT_ROM_DISABLE), 0xFF);
+}
+
+#[test]
+fn joypad_select_bits() {
+    let mut gameboy = gameboy(0x00);
+    let interconnect = &mut gameboy.interconnect;
+    interconnect.key_down(Key::A);
+    interconnect.key_down(Key::Down);
+
+    assert_eq!(interconnect.read_mem(0xFF00), 0xFF);
+
+    interconnect.write_mem(0xFF00, 0x10);
+    assert_eq!(interconnect.read_mem(0xFF00), 0xDE);
+
+    interconnect.write_mem(0xFF00, 0x20);
+    assert_eq!(interconnect.read_mem(0xFF00), 0xE7);
+
+    interconnect.write_mem(0xFF00, 0x00);
+    assert_eq!(interconnect.read_mem(0xFF00), 0xC6);
+}
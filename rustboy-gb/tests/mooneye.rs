@@ -0,0 +1,21 @@
+mod common;
+
+use common::{assert_all_passed, run_dir};
+
+// Mooneye's roms finish within a couple of seconds
+const MAX_FRAMES: u64 = 60 * 10;
+
+#[test]
+#[ignore = "most of the acceptance suite still fails"]
+fn acceptance() {
+    if let Some(results) = run_dir("roms/mooneye/acceptance/", MAX_FRAMES) {
+        assert_all_passed(&results);
+    }
+}
+
+#[test]
+fn acceptance_timer() {
+    if let Some(results) = run_dir("roms/mooneye/acceptance/timer/", MAX_FRAMES) {
+        assert_all_passed(&results);
+    }
+}
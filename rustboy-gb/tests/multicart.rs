@@ -0,0 +1,174 @@
+use rustboy::gameboy::{ChecksumPolicy, GameBoy, LoadError};
+use rustboy::interconnect::cartridge::cartridge_info::{header_checksum, CartridgeType};
+use rustboy::interconnect::cartridge::header::NINTENDO_LOGO;
+use rustboy::interconnect::Interconnect;
+
+const BANK_SIZE: usize = 0x4000;
+
+// 1 MiB rom where every bank starts with its number
+fn banked_rom(cartridge_type: u8) -> Vec<u8> {
+    let mut rom = vec![0; 0x100000];
+    for (bank, data) in rom.chunks_mut(BANK_SIZE).enumerate() {
+        data[0] = bank as u8;
+        data[0x2000] = bank as u8;
+    }
+
+    write_header(&mut rom[..0x8000], cartridge_type);
+    rom
+}
+
+fn write_header(rom: &mut [u8], cartridge_type: u8) {
+    rom[0x104..0x134].copy_from_slice(&NINTENDO_LOGO);
+    rom[0x147] = cartridge_type;
+    rom[0x148] = 0x05;
+    rom[0x14D] = header_checksum(rom);
+}
+
+fn boot(rom: &[u8]) -> GameBoy {
+    GameBoy::from_rom_bytes(rom, None).unwrap()
+}
+
+// Banks mapped at 0x0000 and 0x4000
+fn banks(interconnect: &Interconnect) -> (u8, u8) {
+    (interconnect.read_mem(0x0000), interconnect.read_mem(0x4000))
+}
+
+// A game every 256 KiB, each with a header of its own
+fn multicart() -> Vec<u8> {
+    let mut rom = banked_rom(0x01);
+    for game in 1..4 {
+        let start = game * 0x40000;
+        write_header(&mut rom[start..start + 0x8000], 0x01);
+    }
+    rom
+}
+
+#[test]
+fn mbc1_multicart_has_16_banks_per_game() {
+    let mut gameboy = boot(&multicart());
+    let interconnect = &mut gameboy.interconnect;
+
+    // Third game, bank 2 of it
+    interconnect.write_mem(0x4000, 0x02);
+    interconnect.write_mem(0x2000, 0x02);
+    assert_eq!(interconnect.read_mem(0x4000), 0x22);
+
+    // Bit 4 of the bank number isn't connected
+    interconnect.write_mem(0x2000, 0x12);
+    assert_eq!(interconnect.read_mem(0x4000), 0x22);
+
+    // Its bank 0 shows up at 0x0000 in the other banking mode
+    assert_eq!(interconnect.read_mem(0x0000), 0x00);
+    interconnect.write_mem(0x6000, 0x01);
+    assert_eq!(banks(interconnect), (0x20, 0x22));
+}
+
+#[test]
+fn plain_mbc1_uses_bank_bits_5_and_6() {
+    let mut gameboy = boot(&banked_rom(0x01));
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(0x4000, 0x01);
+    interconnect.write_mem(0x2000, 0x12);
+    assert_eq!(interconnect.read_mem(0x4000), 0x32);
+
+    interconnect.write_mem(0x6000, 0x01);
+    assert_eq!(interconnect.read_mem(0x0000), 0x20);
+}
+
+// 64 bank MMM01 cart, its menu in the last 32 KiB and 8 KiB of RAM
+fn mmm01() -> Vec<u8> {
+    let mut rom = banked_rom(0x01);
+    let menu = rom.len() - 0x8000;
+    write_header(&mut rom[menu..], 0x0C);
+    rom[menu + 0x149] = 0x02;
+    rom[menu + 0x14D] = header_checksum(&rom[menu..]);
+    rom
+}
+
+// What the menu does to start the game of 4 banks at bank 8
+fn start_game(interconnect: &mut Interconnect) {
+    interconnect.write_mem(0x2000, 0x08);
+    interconnect.write_mem(0x6000, 0b1110 << 2);
+    interconnect.write_mem(0x0000, 0x40);
+}
+
+#[test]
+fn mmm01_boots_into_the_menu() {
+    let gameboy = boot(&mmm01());
+
+    let header = &gameboy.interconnect.cartridge.header;
+    assert_eq!(header.cartridge_type, CartridgeType::MMM01RAM);
+    assert_eq!(header.ram_size, 0x2000);
+    assert!(header.valid_header_checksum);
+
+    assert_eq!(banks(&gameboy.interconnect), (0x3E, 0x3F));
+}
+
+#[test]
+fn mmm01_checksum_errors_are_about_the_menu_header() {
+    let mut rom = mmm01();
+    let menu = rom.len() - 0x8000;
+    let actual = rom[menu + 0x14D];
+    rom[menu + 0x14D] = actual.wrapping_add(1);
+
+    let result = GameBoy::new().load_rom(&rom, None, ChecksumPolicy::Reject);
+    assert_eq!(
+        result.err(),
+        Some(LoadError::BadHeaderChecksum {
+            expected: actual.wrapping_add(1),
+            actual,
+        })
+    );
+}
+
+#[test]
+fn mmm01_maps_the_game_the_menu_picked() {
+    let mut gameboy = boot(&mmm01());
+    let interconnect = &mut gameboy.interconnect;
+    start_game(interconnect);
+
+    // Bank 0 of the game can't be mapped at 0x4000
+    assert_eq!(banks(interconnect), (0x08, 0x09));
+
+    interconnect.write_mem(0x2000, 0x02);
+    assert_eq!(banks(interconnect), (0x08, 0x0A));
+
+    // The game can only reach its own 4 banks
+    interconnect.write_mem(0x2000, 0x1F);
+    assert_eq!(banks(interconnect), (0x08, 0x0B));
+}
+
+#[test]
+fn mmm01_multiplexed_bank_with_low_bits_0_maps_the_next_one() {
+    let mut gameboy = boot(&mmm01());
+    let interconnect = &mut gameboy.interconnect;
+
+    // Whole cart, the RAM bank register picking bits 5 and 6 of the ROM bank
+    interconnect.write_mem(0x6000, 0x40);
+    interconnect.write_mem(0x0000, 0x40);
+
+    interconnect.write_mem(0x4000, 0x01);
+    interconnect.write_mem(0x2000, 0x00);
+    assert_eq!(banks(interconnect), (0x00, 0x21));
+
+    interconnect.write_mem(0x2000, 0x02);
+    assert_eq!(banks(interconnect), (0x00, 0x22));
+}
+
+#[test]
+fn mmm01_stays_mapped_until_reset() {
+    let mut gameboy = boot(&mmm01());
+    let interconnect = &mut gameboy.interconnect;
+    start_game(interconnect);
+
+    // Neither the mask nor the mapping can be changed by the game
+    interconnect.write_mem(0x6000, 0x00);
+    interconnect.write_mem(0x0000, 0x00);
+    interconnect.write_mem(0x2000, 0x10);
+    assert_eq!(banks(interconnect), (0x08, 0x09));
+
+    interconnect.write_mem(0x0000, 0x0A);
+    interconnect.write_mem(0xA123, 0x42);
+    assert_eq!(interconnect.read_mem(0xA123), 0x42);
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use common::rom_with;
+use rustboy::gameboy::{ChecksumPolicy, GameBoy};
+use rustboy::interconnect::ppu::{LcdMode, OamAccess, Ppu};
+use rustboy::interconnect::{Interconnect, Model};
+
+fn gameboy(cgb_flag: u8) -> GameBoy {
+    let mut gameboy = GameBoy::from_rom_bytes(&rom_with(0x00, 0x00, 0x00, cgb_flag), None).unwrap();
+
+    // Every byte of OAM is different
+    for i in 0..0xA0 {
+        let value = (i as u8).wrapping_mul(0x9D) ^ 0x5A;
+        gameboy.interconnect.write_mem(0xFE00 + i, value);
+    }
+
+    gameboy
+}
+
+fn oam(interconnect: &Interconnect) -> Vec<u8> {
+    (0xFE00..0xFEA0)
+        .map(|addr| interconnect.read_mem(addr))
+        .collect()
+}
+
+fn word(oam: &[u8], row: usize, word: usize) -> u16 {
+    let i = row * 8 + word * 2;
+    u16::from_le_bytes([oam[i], oam[i + 1]])
+}
+
+// PPU scanning the given OAM row
+fn scan_row(ppu: &mut Ppu, row: u32) {
+    ppu.set_stat_mode(LcdMode::Oam);
+    ppu.set_line_ticks(row * 4);
+}
+
+#[test]
+fn write_corruption() {
+    let mut gameboy = gameboy(0x00);
+    let before = oam(&gameboy.interconnect);
+
+    scan_row(&mut gameboy.interconnect.ppu, 3);
+    gameboy.interconnect.ppu.corrupt_oam(OamAccess::Write);
+    let after = oam(&gameboy.interconnect);
+
+    let (a, b, c) = (
+        word(&before, 3, 0),
+        word(&before, 2, 0),
+        word(&before, 2, 2),
+    );
+    assert_eq!(word(&after, 3, 0), ((a ^ c) & (b ^ c)) ^ c);
+
+    // The rest of the row is copied from the previous one
+    assert_eq!(after[0x1A..0x20], before[0x12..0x18]);
+    assert_eq!(after[..0x18], before[..0x18]);
+    assert_eq!(after[0x20..], before[0x20..]);
+}
+
+#[test]
+fn read_corruption() {
+    let mut gameboy = gameboy(0x00);
+    let before = oam(&gameboy.interconnect);
+
+    scan_row(&mut gameboy.interconnect.ppu, 7);
+    gameboy.interconnect.ppu.corrupt_oam(OamAccess::Read);
+    let after = oam(&gameboy.interconnect);
+
+    let (a, b, c) = (
+        word(&before, 7, 0),
+        word(&before, 6, 0),
+        word(&before, 6, 2),
+    );
+    assert_eq!(word(&after, 7, 0), b | (a & c));
+    assert_eq!(after[0x3A..0x40], before[0x32..0x38]);
+}
+
+#[test]
+fn read_during_increase_corruption() {
+    let mut gameboy = gameboy(0x00);
+    let before = oam(&gameboy.interconnect);
+
+    scan_row(&mut gameboy.interconnect.ppu, 5);
+    gameboy
+        .interconnect
+        .ppu
+        .corrupt_oam(OamAccess::ReadIncrease);
+    let after = oam(&gameboy.interconnect);
+
+    let a = word(&before, 3, 0);
+    let b = word(&before, 4, 0);
+    let c = word(&before, 5, 0);
+    let d = word(&before, 4, 2);
+    let corrupted = (b & (a | c | d)) | (a & c & d);
+
+    // The corrupted previous row ends up in the rows around it, then a read corruption follows
+    assert_eq!(word(&after, 4, 0), corrupted);
+    assert_eq!(after[0x18..0x20], after[0x20..0x28]);
+    // Both rows read the same, so the read corruption leaves the copy as it is
+    assert_eq!(word(&after, 5, 0), corrupted);
+    assert_eq!(after[0x2A..0x30], after[0x22..0x28]);
+}
+
+#[test]
+fn first_row_and_other_modes_are_safe() {
+    let mut gameboy = gameboy(0x00);
+    let before = oam(&gameboy.interconnect);
+
+    scan_row(&mut gameboy.interconnect.ppu, 0);
+    gameboy.interconnect.ppu.corrupt_oam(OamAccess::Write);
+    assert_eq!(oam(&gameboy.interconnect), before);
+
+    let ppu = &mut gameboy.interconnect.ppu;
+    ppu.set_line_ticks(20);
+    ppu.set_stat_mode(LcdMode::HBlank);
+    ppu.corrupt_oam(OamAccess::Write);
+    assert_eq!(oam(&gameboy.interconnect), before);
+}
+
+// INC HL with HL in OAM, PPU at the given dot of mode 2
+fn inc_hl_in_oam(gameboy: &mut GameBoy, dot: u32) {
+    gameboy.cpu.pc = 0xC000;
+    gameboy.cpu.registers.set_hl(0xFE10);
+    gameboy.interconnect.write_mem(0xC000, 0x23);
+
+    let ppu = &mut gameboy.interconnect.ppu;
+    ppu.set_stat_mode(LcdMode::Oam);
+    ppu.set_line_ticks(dot);
+
+    gameboy.cpu.execute_instruction(&mut gameboy.interconnect);
+}
+
+#[test]
+fn inc_rr_in_oam_corrupts() {
+    let mut gameboy = gameboy(0x00);
+    let before = oam(&gameboy.interconnect);
+
+    // The register is on the bus in the second M-cycle, 8 dots later
+    inc_hl_in_oam(&mut gameboy, 12);
+    let after = oam(&gameboy.interconnect);
+
+    assert_ne!(after, before);
+    assert_eq!(after[0x2A..0x30], before[0x22..0x28]);
+}
+
+#[test]
+fn no_corruption_in_cgb_mode() {
+    let mut gameboy = gameboy(0x80);
+    let before = oam(&gameboy.interconnect);
+
+    inc_hl_in_oam(&mut gameboy, 12);
+    assert_eq!(oam(&gameboy.interconnect), before);
+}
+
+#[test]
+fn no_corruption_for_dmg_games_on_cgb_hardware() {
+    let mut gameboy = GameBoy::new();
+    gameboy.model_override = Some(Model::Cgb);
+    gameboy
+        .load_rom(
+            &rom_with(0x00, 0x00, 0x00, 0x00),
+            None,
+            ChecksumPolicy::default(),
+        )
+        .unwrap();
+    assert!(!gameboy.interconnect.cgb_mode());
+
+    let before = oam(&gameboy.interconnect);
+    inc_hl_in_oam(&mut gameboy, 12);
+    assert_eq!(oam(&gameboy.interconnect), before);
+}
@@ -0,0 +1,190 @@
+use std::path::{Path, PathBuf};
+
+use rustboy::interconnect::serial::{LinkCable, Printer};
+use rustboy::screenshot::{shade, Image};
+
+const INITIALIZE: u8 = 0x01;
+const PRINT: u8 = 0x02;
+const DATA: u8 = 0x04;
+const STATUS: u8 = 0x0F;
+
+// One band of 2 tile rows, what a data packet usually holds
+const BAND_BYTES: usize = 0x280;
+
+fn output_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rustboy-printer-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+// Send a whole packet, returns the printer's answer to the last two bytes (alive, status)
+fn send(printer: &mut Printer, command: u8, compressed: bool, data: &[u8]) -> (u8, u8) {
+    let mut packet = vec![0x88, 0x33, command, u8::from(compressed)];
+    packet.extend((data.len() as u16).to_le_bytes());
+    packet.extend(data);
+
+    let checksum = packet[2..]
+        .iter()
+        .fold(0u16, |sum, &byte| sum.wrapping_add(u16::from(byte)));
+    packet.extend(checksum.to_le_bytes());
+    packet.extend([0x00, 0x00]);
+
+    let replies: Vec<u8> = packet.iter().map(|&byte| printer.transfer(byte)).collect();
+    assert!(replies[..replies.len() - 2]
+        .iter()
+        .all(|&reply| reply == 0x00));
+    (replies[replies.len() - 2], replies[replies.len() - 1])
+}
+
+// A band where every pixel of the first tile has color 3, every other pixel color 0
+fn band() -> Vec<u8> {
+    let mut data = vec![0x00; BAND_BYTES];
+    data[..16].fill(0xFF);
+    data
+}
+
+fn print(printer: &mut Printer, margins: u8, palette: u8) -> u8 {
+    send(printer, PRINT, false, &[0x01, margins, palette, 0x40]).1
+}
+
+fn printed(dir: &Path) -> Vec<Image> {
+    (1..)
+        .map(|n| dir.join(format!("print-{:04}.png", n)))
+        .take_while(|path| path.exists())
+        .map(|path| Image::load_png(&path).unwrap())
+        .collect()
+}
+
+fn pixel(image: &Image, x: u32, y: u32) -> u8 {
+    shade(image.pixels[(y * image.width + x) as usize])
+}
+
+#[test]
+fn answers_alive_and_status() {
+    let mut printer = Printer::new(output_dir("status"));
+
+    assert_eq!(send(&mut printer, INITIALIZE, false, &[]), (0x81, 0x00));
+
+    // Data waiting to be printed
+    assert_eq!(send(&mut printer, DATA, false, &band()), (0x81, 0x08));
+    assert_eq!(send(&mut printer, STATUS, false, &[]), (0x81, 0x08));
+}
+
+#[test]
+fn checksum_errors_are_reported() {
+    let mut printer = Printer::new(output_dir("checksum"));
+
+    let packet = [
+        0x88, 0x33, INITIALIZE, 0x00, 0x00, 0x00, 0x12, 0x34, 0x00, 0x00,
+    ];
+    let replies: Vec<u8> = packet.iter().map(|&byte| printer.transfer(byte)).collect();
+    assert_eq!(replies[8..], [0x81, 0x01]);
+
+    // Cleared by the next good packet
+    assert_eq!(send(&mut printer, STATUS, false, &[]), (0x81, 0x00));
+}
+
+#[test]
+fn stays_busy_for_a_while_after_printing() {
+    let dir = output_dir("busy");
+    let mut printer = Printer::new(&dir);
+
+    send(&mut printer, INITIALIZE, false, &[]);
+    send(&mut printer, DATA, false, &band());
+    assert_eq!(print(&mut printer, 0x01, 0xE4), 0x02);
+
+    assert_eq!(send(&mut printer, STATUS, false, &[]).1, 0x02);
+    assert_eq!(send(&mut printer, STATUS, false, &[]).1, 0x00);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn prints_png_with_palette_and_margin() {
+    let dir = output_dir("png");
+    let mut printer = Printer::new(&dir);
+
+    send(&mut printer, INITIALIZE, false, &[]);
+    send(&mut printer, DATA, false, &band());
+    send(&mut printer, DATA, false, &[]);
+
+    // Colors 0 and 3 swapped, 2 lines fed after
+    print(&mut printer, 0x02, 0x1B);
+
+    let images = printed(&dir);
+    assert_eq!(images.len(), 1);
+
+    let image = &images[0];
+    assert_eq!(image.width, 160);
+    assert_eq!(image.height, 16 + 2 * 8);
+    assert_eq!(pixel(image, 0, 0), 0);
+    assert_eq!(pixel(image, 7, 7), 0);
+    assert_eq!(pixel(image, 8, 0), 3);
+    assert_eq!(pixel(image, 0, 8), 3);
+
+    // The margin is blank paper
+    assert_eq!(pixel(image, 0, 20), 0);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn strips_are_joined_until_the_paper_is_fed() {
+    let dir = output_dir("strips");
+    let mut printer = Printer::new(&dir);
+
+    for margins in [0x00, 0x00, 0x01] {
+        send(&mut printer, DATA, false, &band());
+        print(&mut printer, margins, 0xE4);
+        assert_eq!(printed(&dir).len(), usize::from(margins));
+    }
+
+    assert_eq!(printed(&dir)[0].height, 3 * 16 + 8);
+
+    // The next page goes to a new file
+    send(&mut printer, DATA, false, &band());
+    print(&mut printer, 0x01, 0xE4);
+    assert_eq!(printed(&dir).len(), 2);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn run_length_encoded_data() {
+    let dir = output_dir("rle");
+    let mut printer = Printer::new(&dir);
+
+    // 16 bytes of 0xFF, then 0x270 bytes of 0x00 in runs of at most 129
+    let mut data = vec![0x80 | (16 - 2), 0xFF];
+    let mut left = BAND_BYTES - 16;
+    while left > 0 {
+        let run = left.min(129);
+        data.extend([0x80 | (run - 2) as u8, 0x00]);
+        left -= run;
+    }
+
+    send(&mut printer, DATA, true, &data);
+    print(&mut printer, 0x01, 0xE4);
+
+    let compressed = printed(&dir).remove(0);
+    let mut printer = Printer::new(output_dir("rle-raw"));
+    send(&mut printer, DATA, false, &band());
+    print(&mut printer, 0x01, 0xE4);
+    let raw = printed(printer.output_dir()).remove(0);
+
+    assert_eq!(compressed.pixels, raw.pixels);
+    assert_eq!(pixel(&compressed, 0, 0), 3);
+
+    // Literal runs
+    let dir = output_dir("rle-literal");
+    let mut printer = Printer::new(&dir);
+    let mut data = Vec::new();
+    for chunk in band().chunks(0x80) {
+        data.push((chunk.len() - 1) as u8);
+        data.extend(chunk);
+    }
+    send(&mut printer, DATA, true, &data);
+    print(&mut printer, 0x01, 0xE4);
+    assert_eq!(printed(&dir).remove(0).pixels, raw.pixels);
+
+    for name in ["rle", "rle-raw", "rle-literal"] {
+        let _ = std::fs::remove_dir_all(output_dir(name));
+    }
+}
@@ -0,0 +1,214 @@
+mod common;
+
+use common::rom_with;
+use rustboy::constants::{MAX_CYCLES_PER_FRAME, TILE_COLORS, X_RESOLUTION};
+use rustboy::gameboy::GameBoy;
+use rustboy::interconnect::ppu::{LcdMode, Renderer};
+use rustboy::interconnect::Interconnect;
+
+const LCDC: u16 = 0xFF40;
+const SCX: u16 = 0xFF43;
+const BGP: u16 = 0xFF47;
+const OBP0: u16 = 0xFF48;
+const WY: u16 = 0xFF4A;
+const WX: u16 = 0xFF4B;
+
+fn gameboy(renderer: Renderer) -> GameBoy {
+    let mut gameboy = GameBoy::from_rom_bytes(&rom_with(0x00, 0x00, 0x00, 0x00), None).unwrap();
+    gameboy.interconnect.ppu.renderer = renderer;
+    gameboy
+}
+
+// Tile 1: vertical stripes of colors 0-3, tile 2: solid color 3
+fn draw_scene(interconnect: &mut Interconnect) {
+    for line in 0..8 {
+        interconnect.write_mem(0x8010 + line * 2, 0b0101_0101);
+        interconnect.write_mem(0x8011 + line * 2, 0b0011_0011);
+
+        interconnect.write_mem(0x8020 + line * 2, 0xFF);
+        interconnect.write_mem(0x8021 + line * 2, 0xFF);
+    }
+
+    // Checkerboard of tile 0 and 1 in the background map
+    for i in 0..0x400 {
+        let tile = ((i % 32) + (i / 32)) % 2;
+        interconnect.write_mem(0x9800 + i, tile as u8);
+    }
+
+    interconnect.write_mem(BGP, 0xE4);
+    interconnect.write_mem(OBP0, 0xE4);
+
+    // A few sprites using tile 2, two of them overlapping
+    let sprites = [(16, 8), (40, 30), (40, 34), (100, 3), (130, 160)];
+    for (i, (y, x)) in sprites.iter().enumerate() {
+        let addr = 0xFE00 + i as u16 * 4;
+        interconnect.write_mem(addr, *y);
+        interconnect.write_mem(addr + 1, *x);
+        interconnect.write_mem(addr + 2, 2);
+        interconnect.write_mem(addr + 3, 0);
+    }
+
+    // Background, sprites, 0x8000 tile data
+    interconnect.write_mem(LCDC, 0x93);
+}
+
+fn run_frames(gameboy: &mut GameBoy, frames: u64) {
+    let end = gameboy.interconnect.cycles + frames * MAX_CYCLES_PER_FRAME as u64;
+    while gameboy.interconnect.cycles < end {
+        gameboy.cpu.step(&mut gameboy.interconnect);
+    }
+}
+
+// Tick until the PPU is at `dot` of mode 3 on line `ly`
+fn run_to(interconnect: &mut Interconnect, ly: u8, dot: u32) {
+    loop {
+        let ppu = &interconnect.ppu;
+        if ppu.ly() == ly && ppu.line_ticks() >= dot {
+            return;
+        }
+        interconnect.emu_tick(1);
+    }
+}
+
+#[test]
+fn renderers_agree_on_static_scene() {
+    let mut scanline = gameboy(Renderer::Scanline);
+    let mut fifo = gameboy(Renderer::Fifo);
+
+    for gameboy in [&mut scanline, &mut fifo] {
+        draw_scene(&mut gameboy.interconnect);
+        gameboy.interconnect.write_mem(SCX, 3);
+        run_frames(gameboy, 2);
+    }
+
+    let scanline = &scanline.interconnect.ppu.video_buffer;
+    let fifo = &fifo.interconnect.ppu.video_buffer;
+    let differing = scanline
+        .iter()
+        .zip(fifo.iter())
+        .filter(|(a, b)| a != b)
+        .count();
+    assert_eq!(differing, 0);
+}
+
+#[test]
+fn window_agrees_with_scanline() {
+    let mut scanline = gameboy(Renderer::Scanline);
+    let mut fifo = gameboy(Renderer::Fifo);
+
+    for gameboy in [&mut scanline, &mut fifo] {
+        let interconnect = &mut gameboy.interconnect;
+        draw_scene(interconnect);
+
+        // Window from (7, 40) using the 0x9C00 map, filled with tile 2
+        for i in 0..0x400 {
+            interconnect.write_mem(0x9C00 + i, 2);
+        }
+        interconnect.write_mem(WY, 40);
+        interconnect.write_mem(WX, 7);
+        interconnect.write_mem(LCDC, 0x93 | 0x20 | 0x40);
+
+        run_frames(gameboy, 2);
+    }
+
+    let line = |gameboy: &GameBoy, y: usize| {
+        let start = y * X_RESOLUTION as usize;
+        gameboy.interconnect.ppu.video_buffer[start..start + X_RESOLUTION as usize].to_vec()
+    };
+
+    assert_eq!(line(&fifo, 39), line(&scanline, 39));
+    assert_eq!(line(&fifo, 60), line(&scanline, 60));
+    assert_eq!(line(&fifo, 60)[50], TILE_COLORS[3]);
+}
+
+#[test]
+fn mid_scanline_palette_write() {
+    let mut gameboy = gameboy(Renderer::Fifo);
+    let interconnect = &mut gameboy.interconnect;
+    draw_scene(interconnect);
+
+    // Solid color 3 background
+    for i in 0..0x400 {
+        interconnect.write_mem(0x9800 + i, 2);
+    }
+    interconnect.write_mem(LCDC, 0x91);
+
+    // Halfway through drawing line 10, make color 3 white
+    run_to(interconnect, 10, 80 + 12 + 80);
+    interconnect.write_mem(BGP, 0x24);
+    run_to(interconnect, 11, 0);
+
+    let start = 10 * X_RESOLUTION as usize;
+    let line = &interconnect.ppu.video_buffer[start..start + X_RESOLUTION as usize];
+    assert_eq!(line[0], TILE_COLORS[3]);
+    assert_eq!(line[159], TILE_COLORS[0]);
+
+    let changed_at = line.iter().position(|&color| color == TILE_COLORS[0]);
+    assert!(changed_at.is_some_and(|x| x > 60 && x < 100));
+}
+
+// Dots spent in mode 3 on line 20
+fn mode_3_length(gameboy: &mut GameBoy) -> u32 {
+    let interconnect = &mut gameboy.interconnect;
+    run_to(interconnect, 20, 80);
+    while !matches!(interconnect.ppu.stat_mode(), LcdMode::HBlank) {
+        interconnect.emu_tick(1);
+    }
+
+    interconnect.ppu.line_ticks() - 80
+}
+
+#[test]
+fn mode_3_length_varies() {
+    let mut plain = gameboy(Renderer::Fifo);
+    plain.interconnect.write_mem(LCDC, 0x91);
+    let plain_length = mode_3_length(&mut plain);
+    assert!((172..=180).contains(&plain_length), "{}", plain_length);
+
+    // Fine scrolling throws away pixels
+    let mut scrolled = gameboy(Renderer::Fifo);
+    scrolled.interconnect.write_mem(LCDC, 0x91);
+    scrolled.interconnect.write_mem(SCX, 5);
+    assert!(mode_3_length(&mut scrolled) >= plain_length + 4);
+
+    // Every sprite stalls the fetcher
+    let mut sprites = gameboy(Renderer::Fifo);
+    for i in 0..10 {
+        let addr = 0xFE00 + i * 4;
+        sprites.interconnect.write_mem(addr, 30);
+        sprites.interconnect.write_mem(addr + 1, 8 + i as u8 * 10);
+    }
+    sprites.interconnect.write_mem(LCDC, 0x93);
+    assert!(mode_3_length(&mut sprites) >= plain_length + 60);
+}
+
+#[test]
+fn switching_renderer_during_mode_3_waits_for_the_next_line() {
+    let mut gameboy = gameboy(Renderer::Fifo);
+    draw_scene(&mut gameboy.interconnect);
+    run_frames(&mut gameboy, 1);
+
+    // Back to FIFO right as the scanline renderer starts drawing the last line
+    let interconnect = &mut gameboy.interconnect;
+    interconnect.ppu.renderer = Renderer::Scanline;
+    run_to(interconnect, 143, 0);
+    while !matches!(interconnect.ppu.stat_mode(), LcdMode::Transfer) {
+        interconnect.ppu.tick();
+    }
+    interconnect.ppu.renderer = Renderer::Fifo;
+
+    run_frames(&mut gameboy, 2);
+
+    let mut scanline = self::gameboy(Renderer::Scanline);
+    draw_scene(&mut scanline.interconnect);
+    run_frames(&mut scanline, 3);
+
+    let scanline = &scanline.interconnect.ppu.video_buffer;
+    let fifo = &gameboy.interconnect.ppu.video_buffer;
+    let differing = scanline
+        .iter()
+        .zip(fifo.iter())
+        .filter(|(a, b)| a != b)
+        .count();
+    assert_eq!(differing, 0);
+}
@@ -0,0 +1,175 @@
+use rustboy::constants::CLOCK_SPEED;
+use rustboy::gameboy::{GameBoy, LoadError};
+use rustboy::interconnect::cartridge::cartridge_info::header_checksum;
+use rustboy::interconnect::cartridge::rtc::{Rtc, RTC_FOOTER_SIZE, RTC_FOOTER_SIZE_32};
+
+const SECONDS: usize = 0x08;
+const MINUTES: usize = 0x09;
+const HOURS: usize = 0x0A;
+const DAY_LOW: usize = 0x0B;
+const DAY_HIGH: usize = 0x0C;
+
+// 32 KiB MBC3 + TIMER + RAM + BATTERY rom with 8 KiB of RAM
+fn rtc_rom() -> Vec<u8> {
+    let mut rom = vec![0; 0x8000];
+    rom[0x147] = 0x10;
+    rom[0x149] = 0x02;
+    rom[0x14D] = header_checksum(&rom);
+    rom
+}
+
+fn latch(rtc: &mut Rtc) {
+    rtc.write_latch(0x00);
+    rtc.write_latch(0x01);
+}
+
+#[test]
+fn registers_are_latched() {
+    let mut rtc = Rtc::new();
+    rtc.advance_seconds(5);
+    assert_eq!(rtc.read(SECONDS), 0);
+
+    latch(&mut rtc);
+    assert_eq!(rtc.read(SECONDS), 5);
+
+    // Only a 0x00 -> 0x01 sequence latches
+    rtc.advance_seconds(5);
+    rtc.write_latch(0x01);
+    assert_eq!(rtc.read(SECONDS), 5);
+
+    latch(&mut rtc);
+    assert_eq!(rtc.read(SECONDS), 10);
+}
+
+#[test]
+fn ticks_once_per_second_of_cycles() {
+    let mut rtc = Rtc::new();
+    rtc.tick(CLOCK_SPEED as u32 - 4);
+    latch(&mut rtc);
+    assert_eq!(rtc.read(SECONDS), 0);
+
+    rtc.tick(4);
+    latch(&mut rtc);
+    assert_eq!(rtc.read(SECONDS), 1);
+}
+
+#[test]
+fn counters_carry_into_days() {
+    let mut rtc = Rtc::new();
+    rtc.advance_seconds(2 * 86_400 + 3 * 3_600 + 4 * 60 + 5);
+    latch(&mut rtc);
+
+    assert_eq!(rtc.read(SECONDS), 5);
+    assert_eq!(rtc.read(MINUTES), 4);
+    assert_eq!(rtc.read(HOURS), 3);
+    assert_eq!(rtc.read(DAY_LOW), 2);
+    assert_eq!(rtc.read(DAY_HIGH), 0);
+}
+
+#[test]
+fn day_counter_overflow_sets_carry() {
+    let mut rtc = Rtc::new();
+    rtc.write(DAY_LOW, 0xFF);
+    rtc.write(DAY_HIGH, 0x01);
+    rtc.write(HOURS, 23);
+    rtc.write(MINUTES, 59);
+    rtc.write(SECONDS, 59);
+
+    rtc.tick(CLOCK_SPEED as u32);
+    latch(&mut rtc);
+    assert_eq!(rtc.read(DAY_LOW), 0);
+    assert_eq!(rtc.read(DAY_HIGH), 0x80);
+
+    // The carry stays set until the game clears it
+    rtc.advance_seconds(86_400);
+    latch(&mut rtc);
+    assert_eq!(rtc.read(DAY_HIGH), 0x80);
+
+    rtc.write(DAY_HIGH, 0x00);
+    assert_eq!(rtc.read(DAY_HIGH), 0x00);
+}
+
+#[test]
+fn halt_stops_the_clock() {
+    let mut rtc = Rtc::new();
+    rtc.write(DAY_HIGH, 0x40);
+
+    rtc.tick(CLOCK_SPEED as u32 * 10);
+    rtc.advance_seconds(100);
+    latch(&mut rtc);
+    assert_eq!(rtc.read(SECONDS), 0);
+    assert_eq!(rtc.read(DAY_HIGH), 0x40);
+}
+
+#[test]
+fn footer_catches_up_with_elapsed_time() {
+    let mut rtc = Rtc::new();
+    rtc.write(MINUTES, 10);
+    latch(&mut rtc);
+
+    let footer = rtc.to_footer(1_000);
+    assert_eq!(footer.len(), RTC_FOOTER_SIZE);
+
+    let mut loaded = Rtc::new();
+    loaded.load_footer(&footer, 1_000 + 90);
+    assert_eq!(loaded.read(MINUTES), 10);
+
+    latch(&mut loaded);
+    assert_eq!(loaded.read(SECONDS), 30);
+    assert_eq!(loaded.read(MINUTES), 11);
+
+    // Older 44 byte footers only have a 32 bit timestamp
+    let mut loaded = Rtc::new();
+    loaded.load_footer(&footer[..RTC_FOOTER_SIZE_32], 1_000 + 60);
+    latch(&mut loaded);
+    assert_eq!(loaded.read(MINUTES), 11);
+}
+
+#[test]
+fn rtc_registers_are_mapped_to_external_ram() {
+    let mut gameboy = GameBoy::from_rom_bytes(&rtc_rom(), None).unwrap();
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(0x0000, 0x0A);
+    interconnect.write_mem(0x4000, 0x09);
+    interconnect.write_mem(0xA000, 42);
+    assert_eq!(interconnect.read_mem(0xA000), 42);
+
+    // RAM bank 0 is still there
+    interconnect.write_mem(0x4000, 0x00);
+    interconnect.write_mem(0xA000, 0x12);
+    assert_eq!(interconnect.read_mem(0xA000), 0x12);
+
+    interconnect.write_mem(0x4000, 0x09);
+    assert_eq!(interconnect.read_mem(0xA000), 42);
+}
+
+#[test]
+fn save_includes_rtc_footer() {
+    let mut gameboy = GameBoy::from_rom_bytes(&rtc_rom(), None).unwrap();
+    let cartridge = &mut gameboy.interconnect.cartridge;
+    cartridge.mbc.rtc_mut().unwrap().write(HOURS, 5);
+
+    let save = cartridge.export_ram_at(500);
+    assert_eq!(save.len(), 0x2000 + RTC_FOOTER_SIZE);
+
+    let mut other = GameBoy::from_rom_bytes(&rtc_rom(), None).unwrap();
+    let cartridge = &mut other.interconnect.cartridge;
+    cartridge.import_ram_at(&save, 500 + 3_600).unwrap();
+
+    let rtc = cartridge.mbc.rtc_mut().unwrap();
+    latch(rtc);
+    assert_eq!(rtc.read(HOURS), 6);
+
+    // Saves without a footer still load
+    assert!(cartridge.import_ram_at(&save[..0x2000], 0).is_ok());
+
+    let result = cartridge.import_ram_at(&save[..0x2000 + 10], 0);
+    assert_eq!(
+        result,
+        Err(LoadError::InvalidSaveSize {
+            expected: 0x2000 + RTC_FOOTER_SIZE,
+            actual: 0x2000 + 10,
+        })
+    );
+}
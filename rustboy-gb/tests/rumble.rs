@@ -0,0 +1,88 @@
+mod common;
+
+use common::rom_with;
+use rustboy::gameboy::GameBoy;
+
+const MOTOR: u8 = 0x08;
+
+// MBC5 with 128 KiB of RAM, 16 banks
+fn boot(cartridge_type: u8) -> GameBoy {
+    let mut gameboy =
+        GameBoy::from_rom_bytes(&rom_with(cartridge_type, 0x01, 0x04, 0x00), None).unwrap();
+    gameboy.interconnect.write_mem(0x0000, 0x0A);
+    gameboy
+}
+
+// Writes `value` to the start of each RAM bank in turn, then reads back the first one
+fn first_bank_after_writing_all(gameboy: &mut GameBoy, banks: u8) -> u8 {
+    let interconnect = &mut gameboy.interconnect;
+    for bank in 0..banks {
+        interconnect.write_mem(0x4000, bank);
+        interconnect.write_mem(0xA000, bank + 1);
+    }
+
+    interconnect.write_mem(0x4000, 0x00);
+    interconnect.read_mem(0xA000)
+}
+
+#[test]
+fn motor_bit_is_not_a_bank_bit_on_rumble_carts() {
+    let mut gameboy = boot(0x1E);
+    assert!(gameboy.interconnect.cartridge.has_rumble());
+
+    // Banks 8 and up fold back onto 0 - 7
+    assert_eq!(first_bank_after_writing_all(&mut gameboy, 16), 9);
+
+    // Turning the motor on keeps the bank
+    let interconnect = &mut gameboy.interconnect;
+    interconnect.write_mem(0x4000, 0x02);
+    interconnect.write_mem(0xA000, 0x42);
+    interconnect.write_mem(0x4000, 0x02 | MOTOR);
+    assert_eq!(interconnect.read_mem(0xA000), 0x42);
+    assert!(gameboy.rumble());
+
+    gameboy.interconnect.write_mem(0x4000, 0x02);
+    assert!(!gameboy.rumble());
+}
+
+#[test]
+fn plain_mbc5_uses_all_four_bank_bits() {
+    let mut gameboy = boot(0x1B);
+    assert!(!gameboy.interconnect.cartridge.has_rumble());
+
+    assert_eq!(first_bank_after_writing_all(&mut gameboy, 16), 1);
+
+    gameboy.interconnect.write_mem(0x4000, MOTOR);
+    assert!(!gameboy.rumble());
+    gameboy.interconnect.cartridge.tick(1000);
+    assert_eq!(gameboy.take_rumble_duty(), 0.0);
+}
+
+#[test]
+fn duty_cycle_is_the_share_of_time_the_motor_ran() {
+    let mut gameboy = boot(0x1C);
+    let interconnect = &mut gameboy.interconnect;
+
+    interconnect.write_mem(0x4000, MOTOR);
+    interconnect.cartridge.tick(1000);
+    interconnect.write_mem(0x4000, 0x00);
+    interconnect.cartridge.tick(3000);
+    assert_eq!(gameboy.take_rumble_duty(), 0.25);
+
+    // Taking it starts over
+    gameboy.interconnect.cartridge.tick(1000);
+    assert_eq!(gameboy.take_rumble_duty(), 0.0);
+
+    // Without any time passing it's whether the motor is on
+    gameboy.interconnect.write_mem(0x4000, MOTOR);
+    assert_eq!(gameboy.take_rumble_duty(), 1.0);
+}
+
+#[test]
+fn motor_runs_while_the_game_does() {
+    let mut gameboy = boot(0x1D);
+    gameboy.interconnect.write_mem(0x4000, MOTOR);
+
+    gameboy.cpu.run(&mut gameboy.interconnect);
+    assert_eq!(gameboy.take_rumble_duty(), 1.0);
+}
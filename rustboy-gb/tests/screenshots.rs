@@ -0,0 +1,52 @@
+mod common;
+
+use common::{assert_all_match, compare_screenshot, compare_screenshots_in_dir};
+use rustboy::interconnect::ppu::Renderer;
+
+// Both suites draw their final screen within a few frames, give them a second to settle
+const FRAMES: u64 = 60;
+
+fn dmg_acid2(renderer: Renderer) {
+    if let Some(diff) = compare_screenshot(
+        "roms/dmg-acid2/dmg-acid2.gb",
+        "roms/dmg-acid2/reference-dmg.png",
+        FRAMES,
+        renderer,
+    ) {
+        assert!(diff.matches(), "{}", diff);
+    }
+}
+
+fn mealybug_tearoom(renderer: Renderer) {
+    if let Some(results) = compare_screenshots_in_dir(
+        "roms/mealybug/",
+        "roms/mealybug/expected/DMG-blob/",
+        FRAMES,
+        renderer,
+    ) {
+        assert_all_match(&results);
+    }
+}
+
+#[test]
+#[ignore = "the window internal line counter isn't emulated, the window rows follow LY - WY"]
+fn dmg_acid2_scanline() {
+    dmg_acid2(Renderer::Scanline);
+}
+
+#[test]
+fn dmg_acid2_fifo() {
+    dmg_acid2(Renderer::Fifo);
+}
+
+#[test]
+#[ignore = "mid-scanline register writes are not emulated by the scanline renderer"]
+fn mealybug_tearoom_scanline() {
+    mealybug_tearoom(Renderer::Scanline);
+}
+
+#[test]
+#[ignore = "sprite fetch timing is only approximated"]
+fn mealybug_tearoom_fifo() {
+    mealybug_tearoom(Renderer::Fifo);
+}
@@ -0,0 +1,150 @@
+use std::sync::{Arc, Mutex};
+
+use rustboy::constants::{INTERRUPT_FLAG, SERIAL_TRANSFER_CONTROL, SERIAL_TRASFER_DATA};
+use rustboy::interconnect::serial::LinkCable;
+use rustboy::interconnect::{Interconnect, Model};
+
+// Answers every byte with `reply`, remembering what it was sent
+#[derive(Debug)]
+struct Echo {
+    reply: u8,
+    external: bool,
+    sent: Arc<Mutex<Vec<u8>>>,
+}
+
+impl LinkCable for Echo {
+    fn transfer(&mut self, byte: u8) -> u8 {
+        self.sent.lock().unwrap().push(byte);
+        self.reply
+    }
+
+    fn external_transfer(&mut self, byte: u8) -> Option<u8> {
+        if !self.external {
+            return None;
+        }
+
+        self.sent.lock().unwrap().push(byte);
+        Some(self.reply)
+    }
+}
+
+fn connect(interconnect: &mut Interconnect, reply: u8, external: bool) -> Arc<Mutex<Vec<u8>>> {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    interconnect.serial.connect(Box::new(Echo {
+        reply,
+        external,
+        sent: Arc::clone(&sent),
+    }));
+    sent
+}
+
+fn interconnect() -> Interconnect {
+    let mut interconnect = Interconnect::new();
+    interconnect.serial.set_echo(false);
+    interconnect.write_mem(INTERRUPT_FLAG, 0x00);
+    interconnect
+}
+
+fn serial_interrupt(interconnect: &Interconnect) -> bool {
+    interconnect.read_mem(INTERRUPT_FLAG) & 0x08 != 0
+}
+
+fn start_transfer(interconnect: &mut Interconnect, data: u8, control: u8) {
+    interconnect.write_mem(SERIAL_TRASFER_DATA, data);
+    interconnect.write_mem(SERIAL_TRANSFER_CONTROL, control);
+}
+
+#[test]
+fn internal_clock_transfer_takes_8_bits_at_8192_hz() {
+    let mut interconnect = interconnect();
+    start_transfer(&mut interconnect, b'A', 0x81);
+
+    // 512 T-cycles per bit
+    interconnect.emu_tick(1023);
+    assert!(!serial_interrupt(&interconnect));
+    assert_eq!(interconnect.read_mem(SERIAL_TRANSFER_CONTROL), 0xFF);
+
+    interconnect.emu_tick(1);
+    assert!(serial_interrupt(&interconnect));
+    assert_eq!(interconnect.read_mem(SERIAL_TRANSFER_CONTROL), 0x7F);
+
+    // Nothing connected, only 1s are shifted in
+    assert_eq!(interconnect.read_mem(SERIAL_TRASFER_DATA), 0xFF);
+    assert_eq!(interconnect.serial.take_bytes(), b"A");
+}
+
+#[test]
+fn bits_are_shifted_in_msb_first() {
+    let mut interconnect = interconnect();
+    connect(&mut interconnect, 0x0F, false);
+    start_transfer(&mut interconnect, 0xA5, 0x81);
+
+    // Half way through, the upper half of the received byte replaced the lower half of SB
+    interconnect.emu_tick(512);
+    assert_eq!(interconnect.read_mem(SERIAL_TRASFER_DATA), 0x50);
+
+    interconnect.emu_tick(512);
+    assert_eq!(interconnect.read_mem(SERIAL_TRASFER_DATA), 0x0F);
+}
+
+#[test]
+fn link_receives_sent_bytes() {
+    let mut interconnect = interconnect();
+    let sent = connect(&mut interconnect, 0x42, false);
+
+    for byte in [0x12, 0x34] {
+        start_transfer(&mut interconnect, byte, 0x81);
+        interconnect.emu_tick(1024);
+        assert_eq!(interconnect.read_mem(SERIAL_TRASFER_DATA), 0x42);
+    }
+
+    assert_eq!(*sent.lock().unwrap(), [0x12, 0x34]);
+}
+
+#[test]
+fn bytes_for_a_device_are_not_echoed() {
+    let mut interconnect = Interconnect::new();
+    interconnect.write_mem(INTERRUPT_FLAG, 0x00);
+    let sent = connect(&mut interconnect, 0x00, false);
+
+    start_transfer(&mut interconnect, 0x88, 0x81);
+    interconnect.emu_tick(1024);
+
+    assert_eq!(*sent.lock().unwrap(), [0x88]);
+    assert!(interconnect.serial.read_bytes().is_empty());
+}
+
+#[test]
+fn cgb_fast_clock() {
+    let mut interconnect = interconnect();
+    interconnect.set_model(Model::Cgb);
+    start_transfer(&mut interconnect, 0x00, 0x83);
+
+    // 16 T-cycles per bit
+    interconnect.emu_tick(31);
+    assert!(!serial_interrupt(&interconnect));
+    interconnect.emu_tick(1);
+    assert!(serial_interrupt(&interconnect));
+    assert_eq!(interconnect.read_mem(SERIAL_TRANSFER_CONTROL), 0x7F);
+
+    // The fast clock bit doesn't exist on the DMG
+    let mut interconnect = self::interconnect();
+    start_transfer(&mut interconnect, 0x00, 0x83);
+    interconnect.emu_tick(32);
+    assert!(!serial_interrupt(&interconnect));
+}
+
+#[test]
+fn external_clock_waits_for_the_other_end() {
+    let mut interconnect = interconnect();
+    start_transfer(&mut interconnect, 0x12, 0x80);
+    interconnect.emu_tick(0x10000);
+    assert!(!serial_interrupt(&interconnect));
+    assert_eq!(interconnect.read_mem(SERIAL_TRANSFER_CONTROL), 0xFE);
+
+    let sent = connect(&mut interconnect, 0x34, true);
+    interconnect.emu_tick(128);
+    assert!(serial_interrupt(&interconnect));
+    assert_eq!(interconnect.read_mem(SERIAL_TRASFER_DATA), 0x34);
+    assert_eq!(*sent.lock().unwrap(), [0x12]);
+}
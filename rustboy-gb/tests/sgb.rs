@@ -0,0 +1,268 @@
+use rustboy::constants::{JOYPAD, SGB_X_RESOLUTION, TILE_COLORS};
+use rustboy::gameboy::{ChecksumPolicy, GameBoy};
+use rustboy::interconnect::cartridge::cartridge_info::header_checksum;
+use rustboy::interconnect::joypad::Key;
+use rustboy::interconnect::ppu::Rgb;
+use rustboy::interconnect::sgb::{rgb, Mask};
+use rustboy::interconnect::{Interconnect, Model};
+
+const PAL01: u8 = 0x00;
+const ATTR_BLK: u8 = 0x04;
+const MLT_REQ: u8 = 0x11;
+const CHR_TRN: u8 = 0x13;
+const PCT_TRN: u8 = 0x14;
+const MASK_EN: u8 = 0x17;
+
+const RED: u16 = 0x001F;
+const GREEN: u16 = 0x03E0;
+const BLUE: u16 = 0x7C00;
+const WHITE: u16 = 0x7FFF;
+
+fn sgb() -> Interconnect {
+    let mut interconnect = Interconnect::new();
+    interconnect.set_model(Model::Sgb);
+    interconnect
+}
+
+// Pulse a command over P14/P15, `data` starting with its header byte
+fn send(interconnect: &mut Interconnect, data: &[u8]) {
+    let packets = usize::from(data[0] & 0x07).max(1);
+    let mut bytes = data.to_vec();
+    bytes.resize(packets * 16, 0);
+
+    for packet in bytes.chunks(16) {
+        interconnect.write_mem(JOYPAD, 0x00);
+        interconnect.write_mem(JOYPAD, 0x30);
+
+        for byte in packet {
+            for bit in 0..8 {
+                let pulse = if byte & (1 << bit) != 0 { 0x10 } else { 0x20 };
+                interconnect.write_mem(JOYPAD, pulse);
+                interconnect.write_mem(JOYPAD, 0x30);
+            }
+        }
+
+        // Stop bit
+        interconnect.write_mem(JOYPAD, 0x20);
+        interconnect.write_mem(JOYPAD, 0x30);
+    }
+}
+
+fn command(command: u8, packets: u8) -> u8 {
+    (command << 3) | packets
+}
+
+// The game screen drawn with a single shade
+fn fill_screen(interconnect: &mut Interconnect, shade: usize) {
+    interconnect.ppu.video_buffer.fill(TILE_COLORS[shade]);
+}
+
+fn frame(interconnect: &mut Interconnect) {
+    interconnect.sgb.frame(&interconnect.ppu);
+}
+
+fn pixel(interconnect: &Interconnect, x: usize, y: usize) -> Rgb {
+    interconnect.screen()[y * usize::from(SGB_X_RESOLUTION) + x]
+}
+
+// Pixel (x, y) of the game screen inside the border
+fn game_pixel(interconnect: &Interconnect, x: usize, y: usize) -> Rgb {
+    pixel(interconnect, 48 + x, 40 + y)
+}
+
+fn set_palette01(interconnect: &mut Interconnect, first: [u16; 4], second: [u16; 3]) {
+    let mut data = vec![command(PAL01, 1)];
+    for color in first.iter().chain(&second) {
+        data.extend(color.to_le_bytes());
+    }
+    send(interconnect, &data);
+}
+
+// Put 4 KiB where a transfer reads it: tiles at 0x8000 shown 20 to a row by the 0x9800 map
+fn load_vram(interconnect: &mut Interconnect, data: &[u8]) {
+    for (i, &byte) in data.iter().enumerate() {
+        interconnect.write_mem(0x8000 + i as u16, byte);
+    }
+
+    for tile in 0..256u16 {
+        interconnect.write_mem(0x9800 + (tile / 20) * 32 + tile % 20, tile as u8);
+    }
+}
+
+#[test]
+fn palettes_color_the_shades() {
+    let mut interconnect = sgb();
+    set_palette01(&mut interconnect, [WHITE, RED, GREEN, BLUE], [0, 0, 0]);
+
+    for (shade, color) in [WHITE, RED, GREEN, BLUE].into_iter().enumerate() {
+        fill_screen(&mut interconnect, shade);
+        frame(&mut interconnect);
+        assert_eq!(game_pixel(&interconnect, 0, 0), rgb(color));
+    }
+
+    // Color 0 is the backdrop around the game screen too
+    assert_eq!(pixel(&interconnect, 0, 0), rgb(WHITE));
+    assert_eq!(interconnect.screen_size(), (256, 224));
+}
+
+#[test]
+fn attribute_blocks_pick_palettes_per_cell() {
+    let mut interconnect = sgb();
+    set_palette01(&mut interconnect, [WHITE, 0, 0, RED], [0, 0, GREEN]);
+
+    // Palette 1 inside and on the line around cells (2, 2) - (4, 4)
+    send(
+        &mut interconnect,
+        &[command(ATTR_BLK, 1), 1, 0b001, 0b01, 2, 2, 4, 4],
+    );
+
+    fill_screen(&mut interconnect, 3);
+    frame(&mut interconnect);
+
+    assert_eq!(game_pixel(&interconnect, 8, 8), rgb(RED));
+    assert_eq!(game_pixel(&interconnect, 16, 16), rgb(GREEN));
+    assert_eq!(game_pixel(&interconnect, 24, 24), rgb(GREEN));
+    assert_eq!(game_pixel(&interconnect, 39, 39), rgb(GREEN));
+    assert_eq!(game_pixel(&interconnect, 40, 40), rgb(RED));
+}
+
+#[test]
+fn mask_hides_the_game_screen() {
+    let mut interconnect = sgb();
+    set_palette01(&mut interconnect, [WHITE, 0, 0, RED], [0, 0, 0]);
+    fill_screen(&mut interconnect, 3);
+    frame(&mut interconnect);
+
+    send(&mut interconnect, &[command(MASK_EN, 1), 1]);
+    assert_eq!(interconnect.sgb.mask, Mask::Freeze);
+    fill_screen(&mut interconnect, 0);
+    frame(&mut interconnect);
+    assert_eq!(game_pixel(&interconnect, 0, 0), rgb(RED));
+
+    send(&mut interconnect, &[command(MASK_EN, 1), 2]);
+    frame(&mut interconnect);
+    assert_eq!(game_pixel(&interconnect, 0, 0), Rgb::new(0, 0, 0));
+
+    send(&mut interconnect, &[command(MASK_EN, 1), 0]);
+    frame(&mut interconnect);
+    assert_eq!(game_pixel(&interconnect, 0, 0), rgb(WHITE));
+}
+
+#[test]
+fn border_tiles_and_map_come_from_vram() {
+    let mut interconnect = sgb();
+
+    // Tile 0 is solid color 15, the others are see through
+    let mut tiles = vec![0; 0x1000];
+    tiles[..32].fill(0xFF);
+    load_vram(&mut interconnect, &tiles);
+    send(&mut interconnect, &[command(CHR_TRN, 1), 0]);
+    frame(&mut interconnect);
+
+    // Tile 0 with palette 4 in the top left corner, tile 1 everywhere else
+    let mut border = vec![0; 0x1000];
+    for entry in border[..0x800].chunks_exact_mut(2) {
+        entry.copy_from_slice(&0x1001u16.to_le_bytes());
+    }
+    border[..2].copy_from_slice(&0x1000u16.to_le_bytes());
+    border[0x800 + 15 * 2..][..2].copy_from_slice(&RED.to_le_bytes());
+    load_vram(&mut interconnect, &border);
+    send(&mut interconnect, &[command(PCT_TRN, 1)]);
+    frame(&mut interconnect);
+
+    assert_eq!(pixel(&interconnect, 0, 0), rgb(RED));
+    assert_eq!(pixel(&interconnect, 7, 7), rgb(RED));
+    assert_ne!(pixel(&interconnect, 8, 0), rgb(RED));
+}
+
+#[test]
+fn multiplayer_reads_each_controller_in_turn() {
+    let mut interconnect = sgb();
+
+    // Only one controller, nothing selected reads all 1s
+    interconnect.write_mem(JOYPAD, 0x30);
+    assert_eq!(interconnect.read_mem(JOYPAD), 0xFF);
+
+    send(&mut interconnect, &[command(MLT_REQ, 1), 0x01]);
+    interconnect.player_key_down(1, Key::Start);
+
+    assert_eq!(interconnect.read_mem(JOYPAD), 0xFF);
+    interconnect.write_mem(JOYPAD, 0x10);
+    assert_eq!(interconnect.read_mem(JOYPAD) & 0x0F, 0x0F);
+
+    // P15 going high moves on to player 2
+    interconnect.write_mem(JOYPAD, 0x30);
+    assert_eq!(interconnect.read_mem(JOYPAD), 0xFE);
+    interconnect.write_mem(JOYPAD, 0x10);
+    assert_eq!(interconnect.read_mem(JOYPAD) & 0x0F, 0x07);
+
+    interconnect.write_mem(JOYPAD, 0x30);
+    assert_eq!(interconnect.sgb.player(), 0);
+}
+
+#[test]
+fn keys_for_missing_controllers_are_ignored() {
+    let mut interconnect = sgb();
+    send(&mut interconnect, &[command(MLT_REQ, 1), 0x03]);
+
+    interconnect.sgb.key_down(0, Key::Start);
+    interconnect.player_key_down(4, Key::Start);
+    interconnect.player_key_up(usize::MAX, Key::Start);
+
+    for _ in 0..4 {
+        interconnect.write_mem(JOYPAD, 0x10);
+        assert_eq!(interconnect.read_mem(JOYPAD) & 0x0F, 0x0F);
+        interconnect.write_mem(JOYPAD, 0x30);
+    }
+}
+
+#[test]
+fn dmg_ignores_packets() {
+    let mut interconnect = Interconnect::new();
+    send(&mut interconnect, &[command(MLT_REQ, 1), 0x01]);
+
+    interconnect.write_mem(JOYPAD, 0x10);
+    interconnect.write_mem(JOYPAD, 0x30);
+    assert_eq!(interconnect.read_mem(JOYPAD), 0xFF);
+    assert_eq!(interconnect.screen_size(), (160, 144));
+}
+
+fn rom(sgb_flag: bool) -> Vec<u8> {
+    let mut rom = vec![0; 0x8000];
+    rom[0x100..0x102].copy_from_slice(&[0x18, 0xFE]);
+    if sgb_flag {
+        rom[0x146] = 0x03;
+    }
+    rom[0x14D] = header_checksum(&rom);
+    rom
+}
+
+#[test]
+fn sgb_is_picked_over_the_header() {
+    let mut gameboy = GameBoy::new();
+    gameboy.model_override = Some(Model::Sgb);
+    gameboy
+        .load_rom(&rom(true), None, ChecksumPolicy::default())
+        .unwrap();
+
+    assert_eq!(gameboy.interconnect.model, Model::Sgb);
+    assert_eq!(gameboy.cpu.registers.c, 0x14);
+    assert!(gameboy.interconnect.sgb.supported);
+
+    // The border is drawn every frame
+    gameboy.cpu.run(&mut gameboy.interconnect);
+    let (width, height) = gameboy.interconnect.screen_size();
+    assert_eq!(
+        gameboy.interconnect.screen().len(),
+        (width * height) as usize
+    );
+
+    // Games that don't say they support it aren't listened to
+    let mut gameboy = GameBoy::new();
+    gameboy.model_override = Some(Model::Sgb);
+    gameboy
+        .load_rom(&rom(false), None, ChecksumPolicy::default())
+        .unwrap();
+    send(&mut gameboy.interconnect, &[command(MASK_EN, 1), 2]);
+    assert_eq!(gameboy.interconnect.sgb.mask, Mask::None);
+}
@@ -0,0 +1,126 @@
+use rustboy::constants::{DIV, TAC, TIMA, TMA};
+use rustboy::cpu::timer::Timer;
+
+// Enabled, TIMA clocked from bit 3 of the counter (every 16 T-cycles)
+const TAC_16: u8 = 0x05;
+
+fn timer(tac: u8) -> Timer {
+    let mut timer = Timer::new();
+    timer.timer_write(TAC, tac);
+    timer
+}
+
+fn timer_at(tac: u8, t_cycles: u32) -> Timer {
+    let mut timer = timer(tac);
+    timer.tick(t_cycles);
+    timer
+}
+
+#[test]
+fn div_is_upper_byte_of_counter() {
+    let mut timer = Timer::new();
+    timer.tick(0x1FF);
+    assert_eq!(timer.timer_read(DIV), 0x01);
+    assert_eq!(timer.system_counter(), 0x1FF);
+
+    // Any write resets the whole counter
+    timer.timer_write(DIV, 0xAB);
+    assert_eq!(timer.system_counter(), 0);
+    timer.tick(0xFF);
+    assert_eq!(timer.timer_read(DIV), 0x00);
+}
+
+#[test]
+fn tac_frequencies() {
+    for (tac, period) in [(0x04, 1024), (0x05, 16), (0x06, 64), (0x07, 256)] {
+        let mut timer = timer(tac);
+        timer.tick(period - 1);
+        assert_eq!(timer.tima(), 0, "TAC {:#X}", tac);
+        timer.tick(1);
+        assert_eq!(timer.tima(), 1, "TAC {:#X}", tac);
+    }
+
+    // Disabled timer never increments
+    let mut timer = timer(0x01);
+    timer.tick(0x1000);
+    assert_eq!(timer.tima(), 0);
+    assert_eq!(timer.timer_read(TAC), 0xF9);
+}
+
+#[test]
+fn div_write_falling_edge_increments_tima() {
+    let mut timer = timer_at(TAC_16, 8);
+    assert_eq!(timer.tima(), 0);
+
+    // Bit 3 is set, resetting the counter makes it fall
+    timer.timer_write(DIV, 0);
+    assert_eq!(timer.tima(), 1);
+
+    // Bit 3 is clear, nothing happens
+    timer.tick(4);
+    timer.timer_write(DIV, 0);
+    assert_eq!(timer.tima(), 1);
+}
+
+#[test]
+fn tac_write_falling_edge_increments_tima() {
+    let mut timer = timer_at(TAC_16, 8);
+
+    // Disabling the timer while the selected bit is set
+    timer.timer_write(TAC, 0x01);
+    assert_eq!(timer.tima(), 1);
+
+    // Switching to a bit that is clear
+    let mut timer = timer_at(TAC_16, 8);
+    timer.timer_write(TAC, 0x06);
+    assert_eq!(timer.tima(), 1);
+}
+
+#[test]
+fn overflow_reloads_after_delay() {
+    let mut timer = timer(TAC_16);
+    timer.timer_write(TMA, 0x42);
+    timer.timer_write(TIMA, 0xFF);
+
+    assert!(!timer.tick(16));
+    assert_eq!(timer.tima(), 0x00);
+
+    // TIMA stays 0x00 for an M-cycle before being reloaded
+    assert!(!timer.tick(3));
+    assert_eq!(timer.tima(), 0x00);
+    assert!(timer.tick(1));
+    assert_eq!(timer.tima(), 0x42);
+}
+
+#[test]
+fn tima_write_cancels_pending_reload() {
+    let mut timer = timer(TAC_16);
+    timer.timer_write(TMA, 0x42);
+    timer.timer_write(TIMA, 0xFF);
+    timer.tick(16);
+
+    timer.timer_write(TIMA, 0x10);
+    assert!(!timer.tick(4));
+    assert_eq!(timer.tima(), 0x10);
+}
+
+#[test]
+fn writes_during_reload() {
+    let mut timer = timer(TAC_16);
+    timer.timer_write(TMA, 0x42);
+    timer.timer_write(TIMA, 0xFF);
+    timer.tick(20);
+
+    // TIMA writes are ignored in the M-cycle TIMA is reloaded
+    timer.timer_write(TIMA, 0x10);
+    assert_eq!(timer.tima(), 0x42);
+
+    // TMA writes go through to TIMA
+    timer.timer_write(TMA, 0x24);
+    assert_eq!(timer.tima(), 0x24);
+
+    // Afterwards TIMA can be written again
+    timer.tick(4);
+    timer.timer_write(TIMA, 0x10);
+    assert_eq!(timer.tima(), 0x10);
+}
@@ -0,0 +1,70 @@
+use sdl2::audio::{AudioQueue, AudioSpecDesired};
+use sdl2::Sdl;
+
+use crate::constants::{AUDIO_BUFFER_SAMPLES, AUDIO_LATENCY_FRAMES, AUDIO_MAX_RATE_DELTA};
+
+/// Audio output backed by an SDL2 audio queue
+///
+/// The emulator pushes the samples it produced every frame and the fill level of the queue is
+/// used to slightly speed up or slow down the rate the APU resamples at, so that audio and video
+/// stay in sync without the queue running dry (crackles) or growing without bound (latency).
+pub struct Audio {
+    queue: AudioQueue<f32>,
+    sample_rate: u32,
+}
+
+impl Audio {
+    pub fn new(sdl: &Sdl, sample_rate: u32) -> Result<Audio, String> {
+        let audio_subsystem = sdl.audio()?;
+
+        let desired_spec = AudioSpecDesired {
+            freq: Some(sample_rate as i32),
+            channels: Some(2),
+            samples: Some(AUDIO_BUFFER_SAMPLES),
+        };
+
+        let queue: AudioQueue<f32> = audio_subsystem.open_queue(None, &desired_spec)?;
+        let sample_rate = queue.spec().freq as u32;
+        queue.resume();
+
+        Ok(Audio { queue, sample_rate })
+    }
+
+    /// Number of stereo frames waiting to be played
+    pub fn queued_frames(&self) -> u32 {
+        self.queue.size() / (2 * std::mem::size_of::<f32>() as u32)
+    }
+
+    /// Number of stereo frames we aim to keep queued
+    pub fn target_frames(&self) -> u32 {
+        self.sample_rate * AUDIO_LATENCY_FRAMES / 60
+    }
+
+    /// Returns true once the queue holds more than twice the target latency, in which case the
+    /// next emulated frame should be skipped to let the queue drain
+    pub fn overfilled(&self) -> bool {
+        self.queued_frames() > self.target_frames() * 2
+    }
+
+    /// Sample rate the APU should resample at for the next frame
+    ///
+    /// An empty queue asks for up to `AUDIO_MAX_RATE_DELTA` more samples, a queue filled to twice
+    /// the target asks for up to `AUDIO_MAX_RATE_DELTA` fewer.
+    pub fn dynamic_rate(&self) -> u32 {
+        let target = f64::from(self.target_frames());
+        let fill = f64::from(self.queued_frames()).min(target * 2.);
+        let delta = (target - fill) / target * AUDIO_MAX_RATE_DELTA;
+
+        (f64::from(self.sample_rate) * (1. + delta)).round() as u32
+    }
+
+    pub fn push(&mut self, samples: &[f32]) {
+        if samples.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self.queue.queue_audio(samples) {
+            log::warn!("FAILED TO QUEUE AUDIO: {}", e);
+        }
+    }
+}
@@ -1,3 +1,29 @@
+use std::time::Duration;
+
+// Audio constants
+pub const AUDIO_BUFFER_SAMPLES: u16 = 512;
+pub const AUDIO_LATENCY_FRAMES: u32 = 4;
+pub const AUDIO_MAX_RATE_DELTA: f64 = 0.005;
+
+// Duration of one Gameboy frame (59.7275 Hz)
+pub const FRAME_DURATION: Duration = Duration::from_nanos(16_742_706);
+
+// Frames caught up at most after a stall (window dragged, file dialog open) instead of racing
+pub const MAX_FRAME_LAG: u32 = 4;
+
+// Battery saves are written every 5 seconds if the cartridge RAM changed, and on exit
+pub const SAVE_FLUSH_FRAMES: u32 = 60 * 5;
+
+// Tilt of MBC7 cartridges at full key, mouse or stick deflection, in g
+pub const TILT_G: f32 = 1.0;
+pub const STICK_DEAD_ZONE: f32 = 0.1;
+
+// Controller rumble is renewed every frame, this lets it stop on its own if frames stall
+pub const RUMBLE_DURATION_MS: u32 = 100;
+
+// Where the infrared port listens or connects to until another address is typed in
+pub const DEFAULT_INFRARED_ADDRESS: &str = "127.0.0.1:5001";
+
 // ImGui window constants
 pub const SCALE: i32 = 3;
 pub const TILE_SCALE: i32 = 2;
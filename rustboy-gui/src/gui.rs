@@ -1,15 +1,116 @@
 use crate::constants::{
-    GB_POS, GB_SCREEN_HEIGHT, GB_SCREEN_SIZE, GB_SCREEN_WIDTH, GB_SCREEN_X, GB_SCREEN_Y, SCALE,
-    TILE_SCALE, TILE_SCREEN_HEIGHT, TILE_SCREEN_WIDTH, WINDOW_HEIGHT, WINDOW_WIDTH,
+    DEFAULT_INFRARED_ADDRESS, GB_POS, GB_SCREEN_HEIGHT, GB_SCREEN_SIZE, GB_SCREEN_WIDTH,
+    GB_SCREEN_X, GB_SCREEN_Y, SCALE, TILE_SCALE, TILE_SCREEN_HEIGHT, TILE_SCREEN_WIDTH,
+    WINDOW_HEIGHT, WINDOW_WIDTH,
 };
 
 use imgui::{Condition, DrawListMut, ImColor32, Ui};
 use rfd::FileDialog;
-use rustboy::constants::{TILE_COLORS, X_RESOLUTION, Y_RESOLUTION};
-use rustboy::gameboy::GameBoy;
-use rustboy::interconnect::Interconnect;
+use rustboy::constants::{DEFAULT_PRINT_DIR, TILE_COLORS, X_RESOLUTION, Y_RESOLUTION};
+use rustboy::gameboy::{battery_save_path, ChecksumPolicy, GameBoy};
+use rustboy::interconnect::cartridge::camera::{open_image_source, TestPattern};
+use rustboy::interconnect::infrared::InfraredSocket;
+use rustboy::interconnect::ppu::Renderer;
+use rustboy::interconnect::serial::Printer;
+use rustboy::interconnect::{Interconnect, Model};
 
-pub fn menu(ui: &mut Ui, picker: &FileDialog, gameboy: &mut GameBoy) {
+use log::{error, warn};
+use std::path::{Path, PathBuf};
+
+/// Game Boy Printer plugged into the link port, kept across roms and save states
+pub struct PrinterSettings {
+    pub enabled: bool,
+    pub output_dir: PathBuf,
+}
+
+impl PrinterSettings {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            output_dir: PathBuf::from(DEFAULT_PRINT_DIR),
+        }
+    }
+
+    fn apply(&self, gameboy: &mut GameBoy) {
+        if self.enabled {
+            let printer = Printer::new(&self.output_dir);
+            gameboy.interconnect.serial.connect(Box::new(printer));
+        } else {
+            gameboy.interconnect.serial.disconnect();
+        }
+    }
+}
+
+impl Default for PrinterSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What the Game Boy Camera sees, a PNG or a directory of them, or the test pattern when None
+#[derive(Default)]
+pub struct CameraSettings {
+    pub source: Option<PathBuf>,
+}
+
+impl CameraSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn apply(&self, gameboy: &mut GameBoy) {
+        let mbc = &mut gameboy.interconnect.cartridge.mbc;
+
+        match self.source.as_deref().map(open_image_source) {
+            Some(Ok(source)) => mbc.set_image_source(source),
+            Some(Err(e)) => error!("COULD NOT LOAD CAMERA IMAGE: {}", e),
+            None => mbc.set_image_source(Box::new(TestPattern)),
+        }
+    }
+}
+
+/// Address the infrared port of the running cart is pointed at from the link menu
+pub struct InfraredSettings {
+    pub address: String,
+}
+
+impl InfraredSettings {
+    pub fn new() -> Self {
+        Self {
+            address: DEFAULT_INFRARED_ADDRESS.to_string(),
+        }
+    }
+
+    fn apply(&self, gameboy: &mut GameBoy, listen: bool) {
+        let mbc = &mut gameboy.interconnect.cartridge.mbc;
+        let port = if listen {
+            InfraredSocket::listen(&self.address)
+        } else {
+            InfraredSocket::connect(&self.address)
+        };
+
+        match port {
+            Ok(port) => mbc.set_infrared(Box::new(port)),
+            Err(e) => error!("COULD NOT CONNECT INFRARED PORT: {}", e),
+        }
+    }
+}
+
+impl Default for InfraredSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn menu(
+    ui: &mut Ui,
+    picker: &FileDialog,
+    gameboy: &mut GameBoy,
+    printer: &mut PrinterSettings,
+    camera: &mut CameraSettings,
+    infrared: &mut InfraredSettings,
+    model: &mut Option<Model>,
+) {
     if let Some(main) = ui.begin_main_menu_bar() {
         let file_menu = ui.begin_menu("File");
         if let Some(f_menu) = file_menu {
@@ -19,8 +120,15 @@ pub fn menu(ui: &mut Ui, picker: &FileDialog, gameboy: &mut GameBoy) {
             if select_rom {
                 if !gameboy.booted {
                     let pick = picker.clone().pick_files().unwrap();
-                    let rom_path = pick[0].clone().into_os_string().into_string().unwrap();
-                    gameboy.boot(&rom_path, true).unwrap();
+                    let rom_path = pick[0].clone();
+                    match load_rom(&rom_path, *model) {
+                        Ok(loaded) => {
+                            *gameboy = loaded;
+                            printer.apply(gameboy);
+                            camera.apply(gameboy);
+                        }
+                        Err(e) => error!("COULD NOT LOAD ROM: {}", e),
+                    }
                 }
             }
 
@@ -30,19 +138,147 @@ pub fn menu(ui: &mut Ui, picker: &FileDialog, gameboy: &mut GameBoy) {
                 let data: Vec<u8> = std::fs::read(state_path).unwrap();
                 gameboy.load_state(data);
                 gameboy.booted = true;
+                printer.apply(gameboy);
+                camera.apply(gameboy);
             }
 
             if save {
-                gameboy.save_state(&gameboy.interconnect.cartridge.title);
+                if let Err(e) = gameboy.save_state(&gameboy.state_save_path()) {
+                    error!("COULD NOT SAVE STATE: {}", e);
+                }
             }
 
             f_menu.end();
         }
 
+        if let Some(video_menu) = ui.begin_menu("Video") {
+            let ppu = &mut gameboy.interconnect.ppu;
+            let fifo = ppu.renderer == Renderer::Fifo;
+
+            if ui
+                .menu_item_config("Pixel FIFO Renderer")
+                .selected(fifo)
+                .build()
+            {
+                ppu.renderer = if fifo {
+                    Renderer::Scanline
+                } else {
+                    Renderer::Fifo
+                };
+            }
+
+            video_menu.end();
+        }
+
+        // Takes effect with the next rom
+        if let Some(hardware_menu) = ui.begin_menu("Hardware") {
+            let models = [
+                ("Auto", None),
+                ("Game Boy", Some(Model::Dmg)),
+                ("Super Game Boy", Some(Model::Sgb)),
+                ("Game Boy Color", Some(Model::Cgb)),
+            ];
+
+            for (name, choice) in models {
+                if ui.menu_item_config(name).selected(*model == choice).build() {
+                    *model = choice;
+                }
+            }
+
+            hardware_menu.end();
+        }
+
+        if let Some(link_menu) = ui.begin_menu("Link") {
+            if ui
+                .menu_item_config("Game Boy Printer")
+                .selected(printer.enabled)
+                .build()
+            {
+                printer.enabled = !printer.enabled;
+                printer.apply(gameboy);
+            }
+
+            if ui.menu_item("Print Directory") {
+                let picked = FileDialog::new()
+                    .set_directory(&printer.output_dir)
+                    .pick_folder();
+
+                if let Some(dir) = picked {
+                    printer.output_dir = dir;
+                    printer.apply(gameboy);
+                }
+            }
+
+            // Only Hudson carts have an IR port, it's pointed at nothing again with the next rom
+            ui.separator();
+            ui.input_text("Infrared Address", &mut infrared.address)
+                .build();
+
+            let has_infrared = gameboy.interconnect.cartridge.has_infrared();
+            if ui
+                .menu_item_config("Infrared Listen")
+                .enabled(has_infrared)
+                .build()
+            {
+                infrared.apply(gameboy, true);
+            }
+
+            if ui
+                .menu_item_config("Infrared Connect")
+                .enabled(has_infrared)
+                .build()
+            {
+                infrared.apply(gameboy, false);
+            }
+
+            link_menu.end();
+        }
+
+        if let Some(camera_menu) = ui.begin_menu("Camera") {
+            if ui
+                .menu_item_config("Test Pattern")
+                .selected(camera.source.is_none())
+                .build()
+            {
+                camera.source = None;
+                camera.apply(gameboy);
+            }
+
+            let picked = if ui.menu_item("Image") {
+                FileDialog::new().add_filter("png", &["png"]).pick_file()
+            } else if ui.menu_item("Frame Directory") {
+                FileDialog::new().pick_folder()
+            } else {
+                None
+            };
+
+            if picked.is_some() {
+                camera.source = picked;
+                camera.apply(gameboy);
+            }
+
+            camera_menu.end();
+        }
+
         main.end();
     }
 }
 
+// Read a rom picked from the menu and boot it without the boot rom
+fn load_rom(path: &Path, model: Option<Model>) -> anyhow::Result<GameBoy> {
+    let rom = std::fs::read(path)?;
+    let mut gameboy = GameBoy::new();
+    gameboy.model_override = model;
+    gameboy.load_rom(&rom, None, ChecksumPolicy::default())?;
+    gameboy.rom_path = Some(path.to_path_buf());
+
+    if let Err(e) = gameboy.attach_battery_save(battery_save_path(path)) {
+        warn!("COULD NOT LOAD SAVE: {}", e);
+    }
+
+    Ok(gameboy)
+}
+
 pub fn memory_viewer(ui: &mut Ui, gameboy: &GameBoy) {
     let rom_size = 0xFFFF;
 
@@ -95,37 +331,40 @@ pub fn display_info(ui: &mut Ui, gameboy: &GameBoy) {
         .position([400.0, 600.0], Condition::FirstUseEver)
         .collapsed(true, Condition::FirstUseEver)
         .build(|| {
-            let title = format!("TITLE: {}", &gameboy.interconnect.cartridge.title);
-            let cart_type = format!(
-                "CART TYPE: {:?}",
-                &gameboy.interconnect.cartridge.cartridge_type
-            );
-
-            ui.text(title);
-            ui.text(cart_type);
+            let header = gameboy.interconnect.cartridge.header.to_string();
+
+            for line in header.lines() {
+                ui.text(line);
+            }
         });
 }
 
 pub fn display_emulator(ui: &mut Ui, gameboy: &GameBoy) {
+    // The SGB border makes the screen bigger
+    let (screen_width, screen_height) = gameboy.interconnect.screen_size();
+    let size = [
+        GB_SCREEN_SIZE[0] + ((screen_width - u32::from(X_RESOLUTION)) * SCALE as u32) as f32,
+        GB_SCREEN_SIZE[1] + ((screen_height - u32::from(Y_RESOLUTION)) * SCALE as u32) as f32,
+    ];
+
     ui.window("Gameboy Emualtor")
-        .size(GB_SCREEN_SIZE, Condition::FirstUseEver)
+        .size(size, Condition::Always)
         .position(GB_POS, Condition::FirstUseEver)
         .scroll_bar(false)
         .build(|| {
             let draw_list = ui.get_window_draw_list();
             let origin: [f32; 2] = ui.cursor_screen_pos();
-            let video_buffer = gameboy.interconnect.ppu.video_buffer;
+            let video_buffer = gameboy.interconnect.screen();
 
-            for line_num in 0..Y_RESOLUTION {
-                for x in 0..X_RESOLUTION {
+            for line_num in 0..screen_height {
+                for x in 0..screen_width {
                     let new_x = (x as u16 * SCALE as u16) as f32;
                     let new_y = (line_num as u16 * SCALE as u16) as f32;
 
                     let width = SCALE as f32;
                     let height = SCALE as f32;
 
-                    let index =
-                        (u32::from(x) + (u32::from(line_num) * u32::from(X_RESOLUTION))) as usize;
+                    let index = (x + line_num * screen_width) as usize;
                     let color = video_buffer[index];
                     let (r, g, b) = color.get_rgb();
                     let mut top_left = [new_x, new_y];
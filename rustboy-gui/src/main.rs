@@ -1,17 +1,27 @@
+mod audio;
 mod constants;
 mod gui;
 mod sdl_support;
+mod tilt;
 
+use std::time::{Duration, Instant};
+
+use audio::Audio;
+use constants::{FRAME_DURATION, MAX_FRAME_LAG, RUMBLE_DURATION_MS, SAVE_FLUSH_FRAMES};
 use glow::HasContext;
 use imgui::Context;
 use imgui_glow_renderer::AutoRenderer;
 use sdl2::{
+    controller::{Button, GameController},
     event::Event,
     video::{GLProfile, Window},
 };
 use sdl_support::SdlPlatform;
+use tilt::Tilt;
 
 use env_logger::*;
+use log::warn;
+use rustboy::constants::SAMPLE_RATE;
 use rustboy::gameboy::*;
 use rustboy::interconnect::joypad::Key;
 
@@ -36,11 +46,40 @@ fn keycode_to_key(keycode: sdl2::keyboard::Keycode) -> Option<Key> {
     }
 }
 
+fn button_to_key(button: Button) -> Option<Key> {
+    match button {
+        Button::DPadRight => Some(Key::Right),
+        Button::DPadLeft => Some(Key::Left),
+        Button::DPadUp => Some(Key::Up),
+        Button::DPadDown => Some(Key::Down),
+        Button::A => Some(Key::A),
+        Button::B => Some(Key::B),
+        Button::Back => Some(Key::Select),
+        Button::Start => Some(Key::Start),
+        _ => None,
+    }
+}
+
+// The first controller plays along with the keyboard, the next ones are SGB players 2 - 4
+fn controller_player(controllers: &[GameController], which: u32) -> Option<usize> {
+    controllers
+        .iter()
+        .position(|controller| controller.instance_id() == which)
+}
+
 fn main() {
+    let mut logger = Builder::from_default_env();
+    logger.target(Target::Stdout);
+    logger.init();
+
     /* initialize SDL and its video subsystem */
     let sdl = sdl2::init().unwrap();
     let video_subsystem = sdl.video().unwrap();
 
+    /* game controllers are opened as they are plugged in */
+    let controller_subsystem = sdl.game_controller().unwrap();
+    let mut controllers = Vec::new();
+
     /* hint SDL to initialize an OpenGL 3.3 core profile context */
     let gl_attr = video_subsystem.gl_attr();
 
@@ -61,8 +100,23 @@ fn main() {
     let gl_context = window.gl_create_context().unwrap();
     window.gl_make_current(&gl_context).unwrap();
 
-    /* enable vsync to cap framerate */
-    //window.subsystem().gl_set_swap_interval(1).unwrap();
+    /* enable vsync against tearing, emulation is paced by the clock whatever the refresh rate */
+    let vsync = match window.subsystem().gl_set_swap_interval(1) {
+        Ok(_) => true,
+        Err(e) => {
+            warn!("COULD NOT ENABLE VSYNC: {}", e);
+            false
+        }
+    };
+
+    /* open audio queue, the emulator still runs (silently) if there is no audio device */
+    let mut audio = match Audio::new(&sdl, SAMPLE_RATE) {
+        Ok(audio) => Some(audio),
+        Err(e) => {
+            warn!("COULD NOT OPEN AUDIO DEVICE: {}", e);
+            None
+        }
+    };
 
     /* create new glow and imgui contexts */
     let gl = glow_context(&window);
@@ -86,22 +140,32 @@ fn main() {
     /* start main loop */
     let mut event_pump = sdl.event_pump().unwrap();
 
-    let mut logger = Builder::from_default_env();
-    logger.target(Target::Stdout);
-    logger.init();
-
     // File Dialog
     let path = std::env::current_dir().unwrap();
     let file_picker: rfd::FileDialog = rfd::FileDialog::new()
-        .add_filter("gameboy", &["gb"])
-        .add_filter("gameboy saves", &["sav"])
+        .add_filter("gameboy", &["gb", "gbc", "sgb"])
+        .add_filter("gameboy saves", &["sav", "state"])
         .set_directory(&path);
 
     let mut gameboy = GameBoy::new();
+    let mut printer = gui::PrinterSettings::new();
+    let mut camera = gui::CameraSettings::new();
+    let mut infrared = gui::InfraredSettings::new();
+    let mut model = None;
+    let mut tilt = Tilt::new();
+    let mut frames_since_flush: u32 = 0;
+    // Emulated time owed to the wall clock, a frame is run for every FRAME_DURATION of it
+    let mut lag = Duration::ZERO;
+    let mut last_tick = Instant::now();
     'main: loop {
+        let now = Instant::now();
+        lag = (lag + (now - last_tick)).min(FRAME_DURATION * MAX_FRAME_LAG);
+        last_tick = now;
+
         for event in event_pump.poll_iter() {
             /* pass all events to imgui platfrom */
             platform.handle_event(&mut imgui, &event);
+            tilt.handle_event(&event);
 
             match event {
                 Event::Quit { .. } => break 'main,
@@ -117,6 +181,33 @@ fn main() {
                     }
                 }
 
+                Event::ControllerDeviceAdded { which, .. } => {
+                    match controller_subsystem.open(which) {
+                        Ok(controller) => controllers.push(controller),
+                        Err(e) => warn!("COULD NOT OPEN CONTROLLER: {}", e),
+                    }
+                }
+
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    controllers.retain(|controller| controller.instance_id() != which);
+                }
+
+                Event::ControllerButtonUp { which, button, .. } => {
+                    if let (Some(player), Some(key)) =
+                        (controller_player(&controllers, which), button_to_key(button))
+                    {
+                        gameboy.interconnect.player_key_up(player, key)
+                    }
+                }
+
+                Event::ControllerButtonDown { which, button, .. } => {
+                    if let (Some(player), Some(key)) =
+                        (controller_player(&controllers, which), button_to_key(button))
+                    {
+                        gameboy.interconnect.player_key_down(player, key)
+                    }
+                }
+
                 _ => {}
             }
 
@@ -129,7 +220,15 @@ fn main() {
         platform.prepare_frame(&mut imgui, &window, &event_pump);
 
         let ui = imgui.new_frame();
-        gui::menu(ui, &file_picker, &mut gameboy);
+        gui::menu(
+            ui,
+            &file_picker,
+            &mut gameboy,
+            &mut printer,
+            &mut camera,
+            &mut infrared,
+            &mut model,
+        );
         gui::display_info(ui, &gameboy);
         gui::draw_tiles(ui, &gameboy.interconnect);
         gui::display_emulator(ui, &gameboy);
@@ -137,7 +236,41 @@ fn main() {
 
 
         if gameboy.booted {
-            gameboy.cpu.run(&mut gameboy.interconnect);
+            tilt.set_window_size(window.size());
+            let (x, y) = tilt.value();
+            gameboy.set_tilt(x, y);
+        }
+
+        while lag >= FRAME_DURATION {
+            lag -= FRAME_DURATION;
+            if !gameboy.booted {
+                continue;
+            }
+
+            match audio.as_mut() {
+                Some(audio) => {
+                    // Let the queue drain instead of adding more latency
+                    if !audio.overfilled() {
+                        gameboy.interconnect.apu.set_sample_rate(audio.dynamic_rate());
+                        gameboy.cpu.run(&mut gameboy.interconnect);
+                        audio.push(&gameboy.interconnect.apu.drain_samples());
+                    }
+                }
+                None => {
+                    gameboy.cpu.run(&mut gameboy.interconnect);
+                    gameboy.interconnect.apu.drain_samples();
+                }
+            }
+
+            if gameboy.interconnect.cartridge.has_rumble() {
+                rumble(&mut controllers, gameboy.take_rumble_duty());
+            }
+
+            frames_since_flush += 1;
+            if frames_since_flush >= SAVE_FLUSH_FRAMES {
+                frames_since_flush = 0;
+                flush_battery_save(&mut gameboy);
+            }
         }
 
         /* render */
@@ -147,5 +280,28 @@ fn main() {
         renderer.render(draw_data).unwrap();
 
         window.gl_swap_window();
+
+        /* without vsync nothing blocks, sleep until the next frame is due */
+        let owed = lag + last_tick.elapsed();
+        if !vsync && owed < FRAME_DURATION {
+            std::thread::sleep(FRAME_DURATION - owed);
+        }
+    }
+
+    flush_battery_save(&mut gameboy);
+}
+
+// Run the motors of all controllers at `duty`, from 0 to 1
+fn rumble(controllers: &mut [GameController], duty: f32) {
+    let strength = (duty.clamp(0.0, 1.0) * f32::from(u16::MAX)) as u16;
+    for controller in controllers {
+        // Controllers without motors refuse, there's nothing to do about it
+        let _ = controller.set_rumble(strength, strength, RUMBLE_DURATION_MS);
+    }
+}
+
+fn flush_battery_save(gameboy: &mut GameBoy) {
+    if let Err(e) = gameboy.flush_battery_save() {
+        warn!("COULD NOT WRITE SAVE: {}", e);
     }
 }
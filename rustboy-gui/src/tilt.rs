@@ -0,0 +1,114 @@
+use sdl2::controller::Axis;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+
+use crate::constants::{STICK_DEAD_ZONE, TILT_G};
+
+/// Tilt of MBC7 cartridges, from the mouse while the right button is held, I/J/K/L or the right
+/// stick of a game controller, in that order
+#[derive(Default)]
+pub struct Tilt {
+    // Left, right, up, down
+    keys: [bool; 4],
+    mouse: Option<(f32, f32)>,
+    stick: (f32, f32),
+    window_size: (u32, u32),
+}
+
+impl Tilt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_window_size(&mut self, size: (u32, u32)) {
+        self.window_size = size;
+    }
+
+    pub fn handle_event(&mut self, event: &Event) {
+        match *event {
+            Event::KeyDown {
+                keycode: Some(keycode),
+                ..
+            } => self.set_key(keycode, true),
+            Event::KeyUp {
+                keycode: Some(keycode),
+                ..
+            } => self.set_key(keycode, false),
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Right,
+                x,
+                y,
+                ..
+            } => self.mouse = Some(self.mouse_tilt(x, y)),
+            Event::MouseMotion { x, y, .. } if self.mouse.is_some() => {
+                self.mouse = Some(self.mouse_tilt(x, y))
+            }
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Right,
+                ..
+            } => self.mouse = None,
+            Event::ControllerAxisMotion { axis, value, .. } => {
+                let value = stick_tilt(value);
+                match axis {
+                    Axis::RightX => self.stick.0 = value,
+                    Axis::RightY => self.stick.1 = value,
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Tilt in g to the right and towards the player
+    pub fn value(&self) -> (f32, f32) {
+        if let Some(mouse) = self.mouse {
+            return mouse;
+        }
+
+        if self.keys.iter().any(|&held| held) {
+            let axis = |negative: bool, positive: bool| match (negative, positive) {
+                (true, false) => -TILT_G,
+                (false, true) => TILT_G,
+                _ => 0.0,
+            };
+            return (
+                axis(self.keys[0], self.keys[1]),
+                axis(self.keys[2], self.keys[3]),
+            );
+        }
+
+        self.stick
+    }
+
+    fn set_key(&mut self, keycode: Keycode, held: bool) {
+        let index = match keycode {
+            Keycode::J => 0,
+            Keycode::L => 1,
+            Keycode::I => 2,
+            Keycode::K => 3,
+            _ => return,
+        };
+
+        self.keys[index] = held;
+    }
+
+    // The middle of the window is level, its edges tilt by `TILT_G`
+    fn mouse_tilt(&self, x: i32, y: i32) -> (f32, f32) {
+        let axis = |position: i32, size: u32| {
+            let half = (size.max(1) as f32) / 2.0;
+            ((position as f32 - half) / half).clamp(-1.0, 1.0) * TILT_G
+        };
+
+        (axis(x, self.window_size.0), axis(y, self.window_size.1))
+    }
+}
+
+fn stick_tilt(value: i16) -> f32 {
+    let value = f32::from(value) / f32::from(i16::MAX);
+    if value.abs() < STICK_DEAD_ZONE {
+        0.0
+    } else {
+        value.clamp(-1.0, 1.0) * TILT_G
+    }
+}
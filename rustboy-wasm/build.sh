@@ -1 +1,7 @@
- wasm-bindgen ../target/wasm32-unknown-unknown/release/rustboy_wasm.wasm --out-dir ./web/ --target web
+#!/bin/bash
+# Builds the wasm module and generates its JS bindings into web/, they aren't checked in
+set -e
+cd "$(dirname "$0")"
+
+cargo build --release --target wasm32-unknown-unknown -p rustboy-wasm
+wasm-bindgen ../target/wasm32-unknown-unknown/release/rustboy_wasm.wasm --out-dir ./web/ --target web
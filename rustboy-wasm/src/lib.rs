@@ -1,13 +1,7 @@
 use rustboy::constants::TILE_COLORS;
 use rustboy::interconnect::joypad::Key;
 use rustboy::interconnect::ppu::Rgb;
-use rustboy::{
-    gameboy::GameBoy,
-    interconnect::{
-        cartridge::cartridge_info::{ram_size, u8_to_cart_type},
-        cartridge::Cartridge,
-    },
-};
+use rustboy::gameboy::GameBoy;
 use std::f64;
 use wasm_bindgen::prelude::*;
 use web_sys::console;
@@ -53,6 +47,9 @@ pub fn js_value_to_joypad_key(value: JsValue) -> Option<Key> {
 pub struct WebGameBoy {
     gb: GameBoy,
     prev_buffer: Option<[Rgb; BUFFER_SIZE]>,
+
+    // FNV-1a hash of the booted rom, tells apart games with the same title
+    rom_hash: u32,
 }
 
 #[wasm_bindgen]
@@ -62,35 +59,66 @@ impl WebGameBoy {
         WebGameBoy {
             gb: GameBoy::new(),
             prev_buffer: None,
+            rom_hash: 0,
         }
     }
 
     pub fn reset(&mut self) {
         self.gb = GameBoy::new();
         self.prev_buffer = None;
+        self.rom_hash = 0;
     }
 
-    pub fn boot(&mut self, rom: &[u8]) {
-        let game_rom = rom.to_vec();
+    pub fn boot(&mut self, rom: &[u8]) -> Result<(), JsValue> {
+        self.gb = GameBoy::from_rom_bytes(rom, None)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.prev_buffer = None;
+        self.rom_hash = rom.iter().fold(0x811C_9DC5, |hash, &byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+        });
 
-        let cart_type_value = game_rom[0x147];
-        let rom_size_value = game_rom[0x148];
-        let ram_size_value = game_rom[0x149];
-        let cart_type = u8_to_cart_type(cart_type_value);
+        Ok(())
+    }
 
-        /*
-        let cart_type = format!("{}", u8_to_cart_type(cart_type_value));
-        let cart_type_js = JsValue::from_str(&cart_type);
+    /// True if the cartridge RAM is battery backed and should be persisted
+    pub fn has_battery(&self) -> bool {
+        self.gb.interconnect.cartridge.has_battery()
+    }
 
-        console::log_1(&"BOOTING".into());
-        console::log_2(&"CART TYPE: ".into(), &cart_type_js);
-        console::log_2(&"ROM SIZE: ".into(), &rom_size_value.into());
-        console::log_2(&"RAM SIZE: ".into(), &ram_size_value.into());
-        */
-        let ram = vec![0x00; ram_size(ram_size_value) as usize];
+    pub fn title(&self) -> String {
+        self.gb.interconnect.cartridge.header.title.clone()
+    }
+
+    pub fn rom_hash(&self) -> u32 {
+        self.rom_hash
+    }
 
-        self.gb.interconnect.cartridge = Cartridge::new(&game_rom, &ram, &cart_type);
-        self.gb.cpu.pc = 0x100;
+    /// `now` is the unix time in seconds, used to timestamp the RTC
+    pub fn export_save(&self, now: f64) -> Vec<u8> {
+        self.gb.interconnect.cartridge.export_ram_at(now as u64)
+    }
+
+    pub fn import_save(&mut self, save: &[u8], now: f64) -> Result<(), JsValue> {
+        self.gb
+            .interconnect
+            .cartridge
+            .import_ram_at(save, now as u64)
+            .map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    /// Returns true if the cartridge RAM may have changed since the last call
+    pub fn save_changed(&mut self) -> bool {
+        self.gb.interconnect.cartridge.take_ram_dirty()
+    }
+
+    /// True if the cartridge has a rumble motor
+    pub fn has_rumble(&self) -> bool {
+        self.gb.interconnect.cartridge.has_rumble()
+    }
+
+    /// Rumble strength from 0 to 1 since the last call, meant to be taken once per frame
+    pub fn take_rumble(&mut self) -> f32 {
+        self.gb.take_rumble_duty()
     }
 
     pub fn on_key_down(&mut self, value: JsValue) {
@@ -111,6 +139,9 @@ impl WebGameBoy {
     pub fn run(&mut self) {
         let interconnect = &mut self.gb.interconnect;
         self.gb.cpu.run(interconnect);
+
+        // Nothing plays the samples yet
+        interconnect.apu.drain_samples();
     }
     pub fn draw(&mut self) {
         let _timer = Timer::new("WebGameBoy::draw");
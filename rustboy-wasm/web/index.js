@@ -39,6 +39,68 @@ const fps = new class {
     }
 }
 
+// BATTERY SAVES, kept in localStorage as base64
+const SAVE_FLUSH_FRAMES = 60 * 5;
+
+// Titles can be empty or shared, the hash of the rom keeps every game's save apart
+function saveKey(gb) {
+    return 'rustboy-save-' + gb.title() + '-' + gb.rom_hash().toString(16);
+}
+
+function loadSave(gb) {
+    if (!gb.has_battery()) {
+        return;
+    }
+
+    const encoded = localStorage.getItem(saveKey(gb));
+    if (encoded === null) {
+        return;
+    }
+
+    const binary = atob(encoded);
+    const save = new Uint8Array(binary.length);
+    for (let i = 0; i < binary.length; i++) {
+        save[i] = binary.charCodeAt(i);
+    }
+
+    try {
+        gb.import_save(save, Date.now() / 1000);
+    } catch (e) {
+        console.log('COULD NOT LOAD SAVE: ' + e);
+    }
+}
+
+function storeSave(gb) {
+    if (!gb.has_battery() || !gb.save_changed()) {
+        return;
+    }
+
+    const save = gb.export_save(Date.now() / 1000);
+    let binary = '';
+    for (let i = 0; i < save.length; i++) {
+        binary += String.fromCharCode(save[i]);
+    }
+
+    localStorage.setItem(saveKey(gb), btoa(binary));
+}
+
+// RUMBLE, forwarded to every gamepad with a vibration actuator
+const RUMBLE_DURATION_MS = 100;
+
+function rumble(duty) {
+    for (const gamepad of navigator.getGamepads()) {
+        if (gamepad === null || !gamepad.vibrationActuator) {
+            continue;
+        }
+
+        gamepad.vibrationActuator.playEffect('dual-rumble', {
+            duration: RUMBLE_DURATION_MS,
+            strongMagnitude: duty,
+            weakMagnitude: duty,
+        }).catch(() => {});
+    }
+}
+
 async function startWasm() {
     await init();
 
@@ -96,13 +158,33 @@ async function startWasm() {
         let context = canvas.getContext('2d');
 
         let gb = new WebGameBoy();
-        gb.boot(contents);
+        try {
+            gb.boot(contents);
+        } catch (e) {
+            alert('Could not load rom: ' + e);
+            return;
+        }
+        loadSave(gb);
+        window.addEventListener('beforeunload', () => storeSave(gb));
+
+        let framesSinceFlush = 0;
         game_loop();
 
         function game_loop() {
             fps.render();
             gb.run();
             gb.draw();
+
+            if (gb.has_rumble()) {
+                rumble(gb.take_rumble());
+            }
+
+            framesSinceFlush += 1;
+            if (framesSinceFlush >= SAVE_FLUSH_FRAMES) {
+                framesSinceFlush = 0;
+                storeSave(gb);
+            }
+
             if (keyPressed != null) {
                 gb.on_key_down(keyPressed);
                 keyPressed = null;
@@ -114,8 +196,10 @@ async function startWasm() {
             }
 
             if (resetPressed) {
+                storeSave(gb);
                 gb.reset();
                 gb.boot(contents);
+                loadSave(gb);
                 resetPressed = false;
                 context.clearRect(0, 0, canvas.width, canvas.height);
 
@@ -1,16 +0,0 @@
-/* tslint:disable */
-/* eslint-disable */
-export const memory: WebAssembly.Memory;
-export function js_value_to_joypad_key(a: number): number;
-export function __wbg_webgameboy_free(a: number): void;
-export function webgameboy_new(): number;
-export function webgameboy_reset(a: number): void;
-export function webgameboy_boot(a: number, b: number, c: number): void;
-export function webgameboy_on_key_down(a: number, b: number): void;
-export function webgameboy_on_key_up(a: number, b: number): void;
-export function webgameboy_run(a: number): void;
-export function webgameboy_draw(a: number): void;
-export function __wbg_rgb_free(a: number): void;
-export function __wbindgen_malloc(a: number, b: number): number;
-export function __wbindgen_realloc(a: number, b: number, c: number, d: number): number;
-export function __wbindgen_exn_store(a: number): void;